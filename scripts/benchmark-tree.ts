@@ -10,20 +10,30 @@ function makeSyntheticTree(depth: number, breadth: number, prefix = "node"): Fil
 
     nodes.push({
       path: folderPath,
+      relative_path: folderPath,
       name: folderPath,
       is_file: false,
       size: null,
       modified: null,
+      created: null,
+      extension: "",
       children: folderChildren,
+      has_children: folderChildren.length > 0,
+      kind: "directory",
     });
 
     nodes.push({
       path: `${folderPath}/file-${index}.md`,
+      relative_path: `${folderPath}/file-${index}.md`,
       name: `file-${index}.md`,
       is_file: true,
       size: 1024,
       modified: null,
+      created: null,
+      extension: "md",
       children: null,
+      has_children: false,
+      kind: "markdown",
     });
   }
 
@@ -3,7 +3,8 @@
  */
 
 import { useEffect, useState } from "react";
-import { Download } from "lucide-react";
+import { Download, Lock } from "lucide-react";
+import { toast } from "sonner";
 import {
   ResizablePanelGroup,
   ResizablePanel,
@@ -18,6 +19,22 @@ import { useEditorStore } from "./stores/editorStore";
 import { useFileTreeStore } from "./stores/fileTreeStore";
 import { useWorkspaceStore } from "./stores/workspaceStore";
 import { ConflictModal } from "./components/ConflictModal";
+import { DeletionModal } from "./components/DeletionModal";
+import { AppLockDialog } from "./components/AppLockDialog";
+import { LockScreen } from "./components/LockScreen";
+import {
+  getUpdateStatus,
+  installUpdate,
+  resolveWorkspacePath,
+  saveSession,
+  saveWebClipping,
+  whenAppUnlocked,
+} from "./lib/api";
+import { listen } from "./lib/app-events";
+import { DEEP_LINK_PARAM, listenForLaunches, parseDeepLink } from "./lib/launch-queue";
+import { parseClipLink, stripClipParams } from "./lib/web-clipper";
+import { EDITOR_CONFIG } from "./config/editor";
+import type { UpdateStatus } from "./types";
 
 type DeferredInstallPrompt = Event & {
   prompt: () => Promise<void>;
@@ -32,18 +49,142 @@ function isStandaloneMode(): boolean {
   return window.matchMedia("(display-mode: standalone)").matches;
 }
 
+/**
+ * Resolve once the file tree has loaded a workspace
+ * Launch files can arrive before the remembered workspace is restored
+ * @param previousRoot - Wait for a workspace other than this one
+ */
+function whenTreeLoaded(previousRoot: string | null = null): Promise<void> {
+  return new Promise((resolve) => {
+    const isLoaded = (root: string | null) => root !== null && root !== previousRoot;
+    if (isLoaded(useFileTreeStore.getState().workspaceRootPath)) {
+      resolve();
+      return;
+    }
+
+    const unsubscribe = useFileTreeStore.subscribe((state) => {
+      if (isLoaded(state.workspaceRootPath)) {
+        unsubscribe();
+        resolve();
+      }
+    });
+  });
+}
+
+async function revealHandle(handle: FileSystemFileHandle): Promise<boolean> {
+  const path = await resolveWorkspacePath(handle).catch(() => null);
+  return path !== null && useFileTreeStore.getState().revealFile(path);
+}
+
 function App() {
   const isDirty = useEditorStore((state) => state.isDirty);
   const loadWorkspace = useWorkspaceStore((state) => state.loadWorkspace);
   const cleanupStaleOperations = useFileTreeStore((state) => state.cleanupStaleOperations);
   const [installPromptEvent, setInstallPromptEvent] = useState<DeferredInstallPrompt | null>(null);
   const [isStandalone, setIsStandalone] = useState<boolean>(() => isStandaloneMode());
+  const [isAppLockOpen, setIsAppLockOpen] = useState(false);
 
-  // Load workspace from backend on app startup
+  // Load workspace from backend on app startup (after the password, if the app is locked)
   useEffect(() => {
-    loadWorkspace();
+    void whenAppUnlocked().then(loadWorkspace);
   }, [loadWorkspace]);
 
+  // Open files and web+mdx links handed to the app, including from later launches
+  // ("Open with" on a .md file, a second launch of the installed app)
+  useEffect(() => {
+    listenForLaunches(async ({ files, targetURL }) => {
+      // Pages sent by the web clipper extension or bookmarklet
+      const clip = targetURL ? parseClipLink(targetURL) : null;
+      if (clip) {
+        window.history.replaceState(null, "", stripClipParams(new URL(window.location.href)));
+        await whenTreeLoaded();
+        try {
+          const path = await saveWebClipping(clip.clipping, clip.token);
+          toast.success(`Clipped "${path}"`);
+          await useFileTreeStore.getState().revealFile(path);
+        } catch (error) {
+          toast.error(error instanceof Error ? error.message : String(error));
+        }
+        return;
+      }
+
+      const linkPath = targetURL ? parseDeepLink(targetURL) : null;
+      if (targetURL && new URL(targetURL).searchParams.has(DEEP_LINK_PARAM)) {
+        // Keep a reload from opening the link again
+        const url = new URL(window.location.href);
+        url.searchParams.delete(DEEP_LINK_PARAM);
+        window.history.replaceState(null, "", url);
+
+        if (!linkPath) {
+          toast.error("Ignored an invalid mdx link");
+        }
+      }
+
+      if (files.length === 0 && !linkPath) {
+        return;
+      }
+
+      await whenTreeLoaded();
+      if (linkPath) {
+        if (!(await useFileTreeStore.getState().revealFile(linkPath))) {
+          toast.warning(`"${linkPath}" was not found in the open workspace`);
+        }
+        return;
+      }
+
+      for (const handle of files) {
+        if (await revealHandle(handle)) {
+          return;
+        }
+      }
+
+      // Only the user can pick a folder; the file handle doesn't expose its parent
+      const [handle] = files;
+      toast.warning(`"${handle.name}" is not in the open workspace`, {
+        duration: Infinity,
+        action: {
+          label: "Open its folder",
+          onClick: async () => {
+            const previousRoot = useFileTreeStore.getState().workspaceRootPath;
+            const previousWorkspace = useWorkspaceStore.getState().workspacePath;
+            await useWorkspaceStore.getState().selectWorkspace();
+            if (useWorkspaceStore.getState().workspacePath === previousWorkspace) {
+              return;
+            }
+
+            await whenTreeLoaded(previousRoot);
+            if (!(await revealHandle(handle))) {
+              toast.warning(`"${handle.name}" is not inside the selected folder`);
+            }
+          },
+        },
+      });
+    });
+  }, []);
+
+  // Offer to restart once a new version has downloaded
+  useEffect(() => {
+    const offerRestart = (status: UpdateStatus) => {
+      if (status !== "ready") {
+        return;
+      }
+
+      toast.info("A new version of MDX Web is ready", {
+        id: "update-ready",
+        duration: Infinity,
+        action: {
+          label: "Restart",
+          onClick: () => {
+            installUpdate().catch((error) => toast.error(error instanceof Error ? error.message : String(error)));
+          },
+        },
+      });
+    };
+
+    offerRestart(getUpdateStatus());
+    return listen("update-status", offerRestart);
+  }, []);
+
   // Periodically clean up stale pending operations
   useEffect(() => {
     const interval = setInterval(() => {
@@ -53,6 +194,43 @@ function App() {
     return () => clearInterval(interval);
   }, [cleanupStaleOperations]);
 
+  // Persist the open file and expanded folders for the current workspace
+  useEffect(() => {
+    let saveTimeoutId: ReturnType<typeof setTimeout> | null = null;
+
+    const unsubscribe = useFileTreeStore.subscribe((state, previousState) => {
+      if (
+        state.activePath === previousState.activePath &&
+        state.expandedFolders === previousState.expandedFolders
+      ) {
+        return;
+      }
+
+      if (saveTimeoutId) {
+        clearTimeout(saveTimeoutId);
+      }
+
+      saveTimeoutId = setTimeout(() => {
+        const { workspaceRootPath, activePath, expandedFolders } = useFileTreeStore.getState();
+        if (!workspaceRootPath) {
+          return;
+        }
+
+        saveSession({
+          activePath,
+          expandedFolders: Array.from(expandedFolders),
+        }).catch((error) => console.error("Failed to persist session:", error));
+      }, EDITOR_CONFIG.SESSION_SAVE_DELAY_MS);
+    });
+
+    return () => {
+      unsubscribe();
+      if (saveTimeoutId) {
+        clearTimeout(saveTimeoutId);
+      }
+    };
+  }, []);
+
   // Keyboard shortcuts
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
@@ -144,6 +322,14 @@ function App() {
                 Install
               </Button>
             )}
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => setIsAppLockOpen(true)}
+              title="App lock"
+            >
+              <Lock className="w-4 h-4" />
+            </Button>
             <ThemeToggle />
           </div>
         </header>
@@ -176,6 +362,12 @@ function App() {
       
       {/* Conflict resolution modal */}
       <ConflictModal />
+      <DeletionModal />
+
+      <AppLockDialog open={isAppLockOpen} onOpenChange={setIsAppLockOpen} />
+
+      {/* Covers everything while the app is locked */}
+      <LockScreen />
     </>
   );
 }
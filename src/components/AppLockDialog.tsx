@@ -0,0 +1,195 @@
+/**
+ * App Lock Dialog Component
+ *
+ * Sets, changes or removes the app password and the auto-lock timeout,
+ * turns biometric unlock on or off, and locks the app on demand once a
+ * password is set.
+ */
+
+import { useEffect, useState } from "react";
+import type { FormEvent } from "react";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "./ui/dialog";
+import { Button } from "./ui/button";
+import {
+  disableBiometricUnlock,
+  enableBiometricUnlock,
+  getAppLockStatus,
+  isBiometricUnlockAvailable,
+  lockApp,
+  removeAppLockPassword,
+  setAppLockPassword,
+  setAutoLockTimeout,
+} from "../lib/api";
+import { toast } from "sonner";
+
+interface AppLockDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+const INPUT_CLASS =
+  "w-full px-3 py-1.5 text-sm border border-input rounded bg-background focus:outline-none focus:ring-2 focus:ring-ring";
+
+export function AppLockDialog({ open, onOpenChange }: AppLockDialogProps) {
+  const status = getAppLockStatus();
+  const [currentPassword, setCurrentPassword] = useState('');
+  const [password, setPassword] = useState('');
+  const [confirmPassword, setConfirmPassword] = useState('');
+  const [autoLockMinutes, setAutoLockMinutes] = useState(() => String(status.auto_lock_minutes ?? ''));
+  const [biometricAvailable, setBiometricAvailable] = useState(false);
+
+  useEffect(() => {
+    if (open) {
+      isBiometricUnlockAvailable().then(setBiometricAvailable);
+    }
+  }, [open]);
+
+  const close = () => {
+    setCurrentPassword('');
+    setPassword('');
+    setConfirmPassword('');
+    onOpenChange(false);
+  };
+
+  const handleSave = async (e: FormEvent) => {
+    e.preventDefault();
+    if (password !== confirmPassword) {
+      toast.error('Passwords do not match');
+      return;
+    }
+
+    const parsedMinutes = Number.parseInt(autoLockMinutes, 10);
+    const minutes = Number.isFinite(parsedMinutes) && parsedMinutes > 0 ? parsedMinutes : null;
+    try {
+      // Without a new password only the timeout changes
+      if (status.enabled && !password) {
+        setAutoLockTimeout(minutes);
+        toast.success('Auto-lock updated');
+        close();
+        return;
+      }
+
+      await setAppLockPassword(password, status.enabled ? currentPassword : null, minutes);
+      toast.success(status.enabled ? 'Password changed' : 'App lock enabled');
+      close();
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : String(error));
+    }
+  };
+
+  const handleRemove = async () => {
+    try {
+      await removeAppLockPassword(currentPassword);
+      toast.success('App lock disabled');
+      close();
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : String(error));
+    }
+  };
+
+  const handleBiometric = async () => {
+    try {
+      if (status.biometric) {
+        disableBiometricUnlock();
+        toast.success('Biometric unlock turned off');
+      } else {
+        await enableBiometricUnlock(currentPassword);
+        toast.success('Biometric unlock turned on');
+      }
+      close();
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : String(error));
+    }
+  };
+
+  const handleLockNow = () => {
+    close();
+    lockApp();
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={(isOpen) => (isOpen ? onOpenChange(true) : close())}>
+      <DialogContent className="sm:max-w-[420px]">
+        <form onSubmit={handleSave} className="grid gap-4">
+          <DialogHeader>
+            <DialogTitle>App Lock</DialogTitle>
+            <DialogDescription>
+              Hides your notes behind a password. The files on disk are not encrypted.
+            </DialogDescription>
+          </DialogHeader>
+
+          <div className="grid gap-2">
+            {status.enabled && (
+              <input
+                type="password"
+                value={currentPassword}
+                onChange={(e) => setCurrentPassword(e.target.value)}
+                placeholder="Current password"
+                className={INPUT_CLASS}
+              />
+            )}
+            <input
+              type="password"
+              value={password}
+              onChange={(e) => setPassword(e.target.value)}
+              placeholder={status.enabled ? 'New password' : 'Password'}
+              className={INPUT_CLASS}
+            />
+            <input
+              type="password"
+              value={confirmPassword}
+              onChange={(e) => setConfirmPassword(e.target.value)}
+              placeholder="Confirm password"
+              className={INPUT_CLASS}
+            />
+            <label className="flex items-center gap-2 text-sm text-muted-foreground">
+              Lock after
+              <input
+                type="number"
+                min={1}
+                value={autoLockMinutes}
+                onChange={(e) => setAutoLockMinutes(e.target.value)}
+                placeholder="never"
+                className={`${INPUT_CLASS} w-20`}
+              />
+              minutes of inactivity
+            </label>
+            {status.enabled && biometricAvailable && (
+              <Button
+                type="button"
+                variant="outline"
+                onClick={handleBiometric}
+                disabled={!status.biometric && !currentPassword}
+              >
+                {status.biometric ? 'Turn Off Touch ID / Windows Hello' : 'Turn On Touch ID / Windows Hello'}
+              </Button>
+            )}
+          </div>
+
+          <DialogFooter className="gap-2 sm:gap-0">
+            {status.enabled && (
+              <>
+                <Button type="button" variant="outline" onClick={handleRemove} disabled={!currentPassword}>
+                  Remove Password
+                </Button>
+                <Button type="button" variant="outline" onClick={handleLockNow}>
+                  Lock Now
+                </Button>
+              </>
+            )}
+            <Button type="submit" disabled={!status.enabled && !password}>
+              {!status.enabled ? 'Set Password' : password ? 'Change Password' : 'Save'}
+            </Button>
+          </DialogFooter>
+        </form>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -15,29 +15,36 @@ import {
 } from "./ui/dialog";
 import { Button } from "./ui/button";
 import { useEditorStore } from "../stores/editorStore";
+import { showSaveDialog, writeFileHandle } from "../lib/api";
 import { FileX } from "lucide-react";
 import { toast } from "sonner";
 
 export function DeletionModal() {
   const showDeletionModal = useEditorStore((state) => state.showDeletionModal);
   const currentPath = useEditorStore((state) => state.currentPath);
-  // const content = useEditorStore((state) => state.content); // TODO: Use for Save As feature
+  const content = useEditorStore((state) => state.content);
   const closeDeletionModal = useEditorStore((state) => state.closeDeletionModal);
 
   // Extract filename from path for display
   const filename = currentPath?.split('/').pop() || 'this file';
 
   const handleSaveAs = async () => {
-    // In a real implementation, we'd show a save dialog
-    // For now, we'll just show a toast
-    toast.info('Save As feature coming soon. For now, copy your content before closing.');
-    
-    // TODO: Implement save dialog using Tauri's dialog API
-    // const newPath = await invoke('show_save_dialog');
-    // if (newPath && content) {
-    //   await writeFile(newPath, content);
-    //   closeDeletionModal();
-    // }
+    if (content === null) return;
+
+    try {
+      const target = await showSaveDialog({
+        suggestedName: filename,
+        filters: [{ name: 'Markdown', extensions: ['md', 'mdx'] }],
+      });
+      if (!target) return; // User cancelled
+
+      await writeFileHandle(target.handle, content);
+      toast.success(`Saved ${target.name}`);
+      closeDeletionModal();
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      toast.error(errorMessage);
+    }
   };
 
   return (
@@ -71,9 +78,9 @@ export function DeletionModal() {
           </Button>
           <Button
             onClick={handleSaveAs}
-            disabled
+            disabled={content === null}
           >
-            Save As... (Coming Soon)
+            Save As...
           </Button>
         </DialogFooter>
       </DialogContent>
@@ -43,7 +43,8 @@ import {
 import "@mdxeditor/editor/style.css";
 import { useEditorStore } from "../../stores/editorStore";
 import { useThemeStore } from "../../stores/themeStore";
-import { resolveImagePreviewSource, uploadImage } from "../../lib/api";
+import { getWorkspaceSettings, resolveImagePreviewSource, uploadImage } from "../../lib/api";
+import { formatBytes } from "../../lib/utils";
 import { toast } from "sonner";
 import { useRef, useEffect, useState, useMemo } from "react";
 import { oneDark } from "@codemirror/theme-one-dark";
@@ -140,9 +141,16 @@ export function MarkdownEditor({}: MarkdownEditorProps = {}) {
   // Image upload handler for paste/drop
   const imageUploadHandler = async (file: File): Promise<string> => {
     try {
-      const assetUrl = await uploadImage(file);
-      toast.success("Image uploaded successfully");
-      return assetUrl;
+      const { assets } = await getWorkspaceSettings();
+      const image = await uploadImage(file, { processing: assets.processing, notePath: currentPath });
+      toast.success(
+        image.reused
+          ? "Image already in workspace, reusing it"
+          : image.stored_size < image.original_size
+          ? `Image uploaded (${formatBytes(image.original_size)} → ${formatBytes(image.stored_size)})`
+          : "Image uploaded successfully"
+      );
+      return image.path;
     } catch (error) {
       console.error("Failed to upload image:", error);
       const errorMessage = error instanceof Error ? error.message : "Failed to upload image";
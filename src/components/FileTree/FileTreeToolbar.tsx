@@ -3,9 +3,12 @@
  * Provides quick access to New File, New Folder, and Delete actions
  */
 
-import { FilePlus, FolderPlus, Trash2, RefreshCw } from "lucide-react";
+import { Archive, FilePlus, FolderPlus, Link2Off, Trash2, RefreshCw } from "lucide-react";
+import { toast } from "sonner";
 import { Button } from "../ui/button";
 import { useFileTreeStore } from "../../stores/fileTreeStore";
+import { cancelOperation, checkLinks, createOperationId, exportWorkspaceZip } from "../../lib/api";
+import { isAppError } from "../../lib/errors";
 
 export function FileTreeToolbar() {
   const {
@@ -94,6 +97,64 @@ export function FileTreeToolbar() {
     }
   };
 
+  const handleExportZip = async () => {
+    // Created on first progress update so nothing shows while the save dialog is open
+    let toastId: string | number | undefined;
+
+    try {
+      const savedName = await exportWorkspaceZip({}, ({ processed, total }) => {
+        toastId = toast.loading(`Exporting workspace... ${processed}/${total}`, { id: toastId });
+      });
+
+      if (savedName) {
+        toast.success(`Exported ${savedName}`, { id: toastId });
+      } else if (toastId !== undefined) {
+        toast.dismiss(toastId);
+      }
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error);
+      toast.error(message, { id: toastId });
+    }
+  };
+
+  const handleCheckLinks = async () => {
+    const operationId = createOperationId();
+    const toastId = toast.loading('Checking links...', {
+      action: {
+        label: 'Cancel',
+        onClick: () => cancelOperation(operationId),
+      },
+    });
+
+    try {
+      const report = await checkLinks('.', operationId);
+      if (report.broken.length === 0) {
+        toast.success(`All ${report.checked_links} links in ${report.checked_notes} notes are valid`, { id: toastId });
+        return;
+      }
+
+      console.warn(
+        '[LinkChecker] Broken links:\n' +
+          report.broken
+            .map((link) =>
+              `${link.path}:${link.line} ${link.target} (${link.reason})` +
+              (link.suggestion ? ` - did you mean ${link.suggestion}?` : '')
+            )
+            .join('\n')
+      );
+      toast.warning(`Found ${report.broken.length} broken link${report.broken.length === 1 ? '' : 's'} (see console)`, {
+        id: toastId,
+      });
+    } catch (error) {
+      if (isAppError(error, 'cancelled')) {
+        toast.info('Link check cancelled', { id: toastId });
+        return;
+      }
+      const message = error instanceof Error ? error.message : String(error);
+      toast.error(message, { id: toastId });
+    }
+  };
+
   return (
     <div className="flex flex-col border-b bg-background">
       {/* Loading indicator */}
@@ -135,6 +196,24 @@ export function FileTreeToolbar() {
           <Trash2 className="w-4 h-4" />
         </Button>
         <div className="flex-1" />
+        <Button
+          size="sm"
+          variant="ghost"
+          onClick={handleCheckLinks}
+          title="Check for broken links"
+          className="h-8 px-2"
+        >
+          <Link2Off className="w-4 h-4" />
+        </Button>
+        <Button
+          size="sm"
+          variant="ghost"
+          onClick={handleExportZip}
+          title="Export workspace as ZIP"
+          className="h-8 px-2"
+        >
+          <Archive className="w-4 h-4" />
+        </Button>
         <Button
           size="sm"
           variant="ghost"
@@ -3,7 +3,7 @@
  * Uses @tanstack/react-virtual for optimal performance with large trees
  */
 
-import { useEffect, useRef, useMemo, useCallback } from "react";
+import { useEffect, useRef, useMemo, useCallback, useState } from "react";
 import { useVirtualizer } from "@tanstack/react-virtual";
 import { Loader2 } from "lucide-react";
 import { useFileTreeStore } from "../../stores/fileTreeStore";
@@ -11,7 +11,9 @@ import { useWorkspaceStore } from "../../stores/workspaceStore";
 import { TreeNodeFlat } from "./TreeNodeFlat";
 import { InlineCreationInput } from "./InlineCreationInput";
 import { toast } from "sonner";
-import { flattenTree } from "../../lib/treeUtils";
+import { compareNodes, flattenTree } from "../../lib/treeUtils";
+import { getViewPreferences } from "../../lib/api";
+import { listen } from "../../lib/app-events";
 
 export function FileTreeVirtualized() {
   // Use individual selectors for proper reactivity
@@ -20,6 +22,7 @@ export function FileTreeVirtualized() {
   const isLoading = useFileTreeStore((state) => state.isLoading);
   const error = useFileTreeStore((state) => state.error);
   const loadRootDirectory = useFileTreeStore((state) => state.loadRootDirectory);
+  const restoreSession = useFileTreeStore((state) => state.restoreSession);
   const clearError = useFileTreeStore((state) => state.clearError);
   const activePath = useFileTreeStore((state) => state.activePath);
   const toggleFolder = useFileTreeStore((state) => state.toggleFolder);
@@ -30,16 +33,19 @@ export function FileTreeVirtualized() {
   const cancelInlineCreation = useFileTreeStore((state) => state.cancelInlineCreation);
   
   const workspacePath = useWorkspaceStore((state) => state.workspacePath);
+  const [viewPreferences, setViewPreferences] = useState(getViewPreferences);
+
+  useEffect(() => listen("view-preferences-changed", setViewPreferences), []);
 
   // Reference to scrollable container
   const parentRef = useRef<HTMLDivElement>(null);
 
-  // Load tree when workspace changes
+  // Load tree and restore the saved session when workspace changes
   useEffect(() => {
     if (workspacePath) {
-      loadRootDirectory();
+      loadRootDirectory().then(() => restoreSession());
     }
-  }, [workspacePath, loadRootDirectory]);
+  }, [workspacePath, loadRootDirectory, restoreSession]);
 
   // Show toast on error
   useEffect(() => {
@@ -56,8 +62,8 @@ export function FileTreeVirtualized() {
 
   // Memoize flat nodes based on actual state dependencies
   const flatNodes = useMemo(() => {
-    return flattenTree(nodes, expandedFolders);
-  }, [nodes, expandedFolders]);
+    return flattenTree(nodes, expandedFolders, compareNodes(viewPreferences));
+  }, [nodes, expandedFolders, viewPreferences]);
 
   // Extended flat nodes with inline creation input injected
   const extendedFlatNodes = useMemo(() => {
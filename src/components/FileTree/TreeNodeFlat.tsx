@@ -4,10 +4,14 @@
  */
 
 import { useState } from "react";
-import { ChevronRight, ChevronDown, Folder, File } from "lucide-react";
+import { ChevronRight, ChevronDown, Folder, File, FileCode, FileText, Image as ImageIcon } from "lucide-react";
 import type { FlatNode } from "../../lib/treeUtils";
-import { cn } from "../../lib/utils";
+import { cn, formatRelativeTime } from "../../lib/utils";
 import { useFileTreeStore } from "../../stores/fileTreeStore";
+import { useEditorStore } from "../../stores/editorStore";
+import { exportNote, importFolder, importNotionExport, importObsidianVault, listExportFormats, localizeRemoteImages, showOpenFilesDialog } from "../../lib/api";
+import type { ExportFormatId, FileKind, ImportReport } from "../../types";
+import { toast } from "sonner";
 import {
   ContextMenu,
   ContextMenuContent,
@@ -17,6 +21,13 @@ import {
 } from "../ui/context-menu";
 import { RenameInput } from "./RenameInput";
 
+const FILE_KIND_ICONS: Partial<Record<FileKind, typeof File>> = {
+  directory: Folder,
+  markdown: FileText,
+  image: ImageIcon,
+  code: FileCode,
+};
+
 interface TreeNodeFlatProps {
   /** Flattened node data with rendering metadata */
   flatNode: FlatNode;
@@ -31,6 +42,7 @@ export function TreeNodeFlat({ flatNode }: TreeNodeFlatProps) {
     startInlineCreation,
     renameNodeOptimistic,
     deleteNodeOptimistic,
+    refreshNode,
   } = useFileTreeStore();
   
   const isActive = activePath === node.path;
@@ -70,6 +82,90 @@ export function TreeNodeFlat({ flatNode }: TreeNodeFlatProps) {
     startInlineCreation('folder', parentPath, node.path);
   };
 
+  const handleImportFiles = async () => {
+    try {
+      const imported = await showOpenFilesDialog(node.path);
+      if (imported.length === 0) return; // User cancelled
+
+      await refreshNode(node.path);
+      toast.success(`Imported ${imported.length} file${imported.length === 1 ? '' : 's'}`);
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error);
+      toast.error(message);
+    }
+  };
+
+  const runImport = async (label: string, importer: (path: string) => Promise<ImportReport | null>) => {
+    try {
+      const report = await importer(node.path);
+      if (!report) return; // User cancelled
+
+      await refreshNode(node.path);
+      if (report.issues.length > 0) {
+        console.warn(`[${label}] Unconverted constructs:`, report.issues);
+        toast.warning(
+          `Imported ${report.imported_notes} notes with ${report.issues.length} unconverted item${report.issues.length === 1 ? '' : 's'} (see console)`
+        );
+      } else {
+        toast.success(`Imported ${report.imported_notes} notes into ${report.destination}`);
+      }
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error);
+      toast.error(message);
+    }
+  };
+
+  const handleImportFolder = () => runImport('FolderImport', (path) => importFolder(path));
+
+  const handleImportObsidian = () => runImport('ObsidianImport', importObsidianVault);
+
+  const handleImportNotion = () => runImport('NotionImport', importNotionExport);
+
+  const handleExport = async (format: ExportFormatId) => {
+    try {
+      const savedName = await exportNote(node.path, format);
+      if (savedName) {
+        toast.success(`Exported ${savedName}`);
+      }
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error);
+      toast.error(message);
+    }
+  };
+
+  const handleLocalizeImages = async () => {
+    const toastId = toast.loading('Downloading remote images...');
+
+    try {
+      // Save pending edits first so the rewrite starts from what the user sees
+      const editor = useEditorStore.getState();
+      const isOpen = editor.currentPath === node.path;
+      if (isOpen && editor.isDirty) {
+        await editor.manualSave();
+      }
+
+      const report = await localizeRemoteImages(node.path);
+      if (isOpen && report.localized > 0) {
+        await editor.loadFile(node.path);
+      }
+
+      if (report.failed.length > 0) {
+        console.warn('[RemoteImages] Failed downloads:', report.failed);
+        toast.warning(
+          `Downloaded ${report.localized} image${report.localized === 1 ? '' : 's'}, ${report.failed.length} failed (see console)`,
+          { id: toastId }
+        );
+      } else if (report.localized === 0) {
+        toast.info('No remote images found', { id: toastId });
+      } else {
+        toast.success(`Downloaded ${report.localized} image${report.localized === 1 ? '' : 's'}`, { id: toastId });
+      }
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error);
+      toast.error(message, { id: toastId });
+    }
+  };
+
   const handleRename = () => {
     setIsRenaming(true);
   };
@@ -90,7 +186,7 @@ export function TreeNodeFlat({ flatNode }: TreeNodeFlatProps) {
   };
 
   // Determine icon based on node type
-  const Icon = node.is_file ? File : Folder;
+  const Icon = FILE_KIND_ICONS[node.kind] ?? File;
   const ChevronIcon = isExpanded ? ChevronDown : ChevronRight;
 
   return (
@@ -106,6 +202,7 @@ export function TreeNodeFlat({ flatNode }: TreeNodeFlatProps) {
             node.isPending && "opacity-60 italic", // Visual indicator for pending operations
           )}
           style={{ paddingLeft: `${depth * 12 + 8}px` }}
+          title={node.modified !== null ? `Modified ${formatRelativeTime(node.modified)}` : undefined}
           onClick={handleClick}
         >
           {/* Chevron for folders (or spacer for files) */}
@@ -143,6 +240,31 @@ export function TreeNodeFlat({ flatNode }: TreeNodeFlatProps) {
             <ContextMenuItem onClick={handleNewFolder}>
               New Folder
             </ContextMenuItem>
+            <ContextMenuItem onClick={handleImportFiles}>
+              Import Files...
+            </ContextMenuItem>
+            <ContextMenuItem onClick={handleImportFolder}>
+              Import Folder...
+            </ContextMenuItem>
+            <ContextMenuItem onClick={handleImportObsidian}>
+              Import Obsidian Vault...
+            </ContextMenuItem>
+            <ContextMenuItem onClick={handleImportNotion}>
+              Import Notion Export...
+            </ContextMenuItem>
+            <ContextMenuSeparator />
+          </>
+        )}
+        {node.is_file && (
+          <>
+            {listExportFormats().map((format) => (
+              <ContextMenuItem key={format.id} onClick={() => handleExport(format.id)}>
+                Export as {format.name}...
+              </ContextMenuItem>
+            ))}
+            <ContextMenuItem onClick={handleLocalizeImages}>
+              Download Remote Images
+            </ContextMenuItem>
             <ContextMenuSeparator />
           </>
         )}
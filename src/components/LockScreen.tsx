@@ -0,0 +1,94 @@
+/**
+ * Lock Screen Component
+ *
+ * Covers the whole app while it is locked and asks for the password, or
+ * offers Touch ID / Windows Hello when biometric unlock is set up.
+ */
+
+import { useEffect, useState } from "react";
+import type { FormEvent } from "react";
+import { Fingerprint, Lock } from "lucide-react";
+import { Button } from "./ui/button";
+import { getAppLockStatus, unlockApp, unlockAppWithBiometrics } from "../lib/api";
+import { listen } from "../lib/app-events";
+
+export function LockScreen() {
+  const [isLocked, setIsLocked] = useState(() => getAppLockStatus().locked);
+  const [hasBiometric, setHasBiometric] = useState(() => getAppLockStatus().biometric);
+  const [password, setPassword] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [isChecking, setIsChecking] = useState(false);
+
+  useEffect(
+    () =>
+      listen("app-lock-changed", (status) => {
+        setIsLocked(status.locked);
+        setHasBiometric(status.biometric);
+      }),
+    []
+  );
+
+  if (!isLocked) return null;
+
+  const handleSubmit = async (e: FormEvent) => {
+    e.preventDefault();
+    setIsChecking(true);
+    setError(null);
+
+    try {
+      if (await unlockApp(password)) {
+        setPassword('');
+      } else {
+        setError('Incorrect password');
+      }
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsChecking(false);
+    }
+  };
+
+  const handleBiometric = async () => {
+    setIsChecking(true);
+    setError(null);
+
+    try {
+      if (await unlockAppWithBiometrics()) {
+        setPassword('');
+      } else {
+        setError('Not verified, use your password');
+      }
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsChecking(false);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 z-[100] flex items-center justify-center bg-background">
+      <form onSubmit={handleSubmit} className="flex w-72 flex-col items-center gap-3">
+        <Lock className="h-8 w-8 text-muted-foreground" />
+        <h2 className="text-lg font-semibold">MDX Web is locked</h2>
+        <input
+          type="password"
+          autoFocus
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          placeholder="Password"
+          className="w-full px-3 py-1.5 text-sm border border-input rounded bg-background focus:outline-none focus:ring-2 focus:ring-ring"
+        />
+        {error && <p className="text-sm text-destructive">{error}</p>}
+        <Button type="submit" className="w-full" disabled={isChecking || !password}>
+          {isChecking ? 'Unlocking...' : 'Unlock'}
+        </Button>
+        {hasBiometric && (
+          <Button type="button" variant="outline" className="w-full" onClick={handleBiometric} disabled={isChecking}>
+            <Fingerprint className="mr-2 h-4 w-4" />
+            Unlock with Touch ID / Windows Hello
+          </Button>
+        )}
+      </form>
+    </div>
+  );
+}
@@ -2,4 +2,5 @@ export const EDITOR_CONFIG = {
   AUTO_SAVE_DELAY_MS: 300,
   SAVE_STATUS_DISPLAY_DURATION_MS: 2000,
   MAX_FILE_SIZE_MB: 5,
+  SESSION_SAVE_DELAY_MS: 500,
 } as const;
@@ -1,12 +1,209 @@
-import type { DirectoryPage, FileNode } from "../types";
+import type {
+  AppLockStatus,
+  AuditEntry,
+  Citation,
+  CodeBlock,
+  ConflictedCopy,
+  CrashReport,
+  DiffBase,
+  DictionaryScope,
+  DiffHunk,
+  DirectoryCursorPage,
+  DirectoryPage,
+  DuplicateGroup,
+  ExportFormat,
+  ExportFormatId,
+  FileChangeKind,
+  FileEntryKind,
+  FileNode,
+  FolderImportOptions,
+  FormatOptions,
+  FilenameProblem,
+  GraphOptions,
+  HtmlExportOptions,
+  ImageTextMatch,
+  ImportReport,
+  IoPolicy,
+  LinkCheckReport,
+  LinkCompletion,
+  LinkConversion,
+  LinkConversionChange,
+  LocalizeImagesReport,
+  MergeResult,
+  NoteGraph,
+  NoteMetadata,
+  NoteQueryOptions,
+  NoteReference,
+  OpenFilesDialogOptions,
+  OperationProgress,
+  OperationProgressEvent,
+  OutlineHeading,
+  PathCopyKind,
+  RenderMarkdownOptions,
+  ResolvedNote,
+  SaveDialogOptions,
+  SaveTarget,
+  SelectionExportFormat,
+  SelectionExportOptions,
+  SelectionExportReport,
+  SiteExportOptions,
+  SiteExportReport,
+  StorageNode,
+  TableAlignment,
+  TagCompletion,
+  Task,
+  TaskFilters,
+  TextEdit,
+  UpdateStatus,
+  UploadImageOptions,
+  UploadedImage,
+  UrlMetadata,
+  ViewPreferences,
+  WebClipping,
+  WorkspaceLockStatus,
+  WritingStats,
+  WorkspaceSettings,
+  WorkspaceSession,
+  ZipExportOptions,
+} from "../types";
+import { emit } from "./app-events";
+import * as appLock from "./app-lock";
+import * as auditLog from "./audit-log";
+import * as bibliography from "./bibliography";
+import * as copyPath from "./copy-path";
+import * as crashReports from "./crash-reports";
+import * as crdtDocument from "./crdt-document";
+import * as diff from "./diff";
+import * as docxExport from "./docx-export";
+import * as duplicates from "./duplicates";
+import * as dictionary from "./dictionary";
+import * as emptyFolders from "./empty-folders";
+import { toAppError } from "./errors";
+import * as exporters from "./exporters";
+import * as filenameUtils from "./filenameUtils";
+import * as folderImport from "./folder-import";
+import * as formatter from "./formatter";
 import * as fsService from "./fs-service";
+import * as graph from "./graph";
+import * as htmlExport from "./html-export";
+import * as imageText from "./image-text";
+import * as ioPolicy from "./io-policy";
+import * as linkChecker from "./link-checker";
+import * as linkConvert from "./link-convert";
+import * as linkRebase from "./link-rebase";
+import * as markdownRender from "./markdown-render";
+import * as markdownUtils from "./markdownUtils";
+import * as merge from "./merge";
+import type { MergeLabels } from "./merge";
+import * as noteIndex from "./note-index";
+import * as noteQuery from "./note-query";
+import * as notionImport from "./notion-import";
+import * as obsidianImport from "./obsidian-import";
+import * as operations from "./operations";
+import * as outline from "./outline";
+import * as remoteImages from "./remote-images";
+import * as selectionExport from "./selection-export";
+import * as session from "./session";
+import * as siteExport from "./site-export";
+import * as storageReport from "./storage-report";
+import * as tables from "./tables";
+import * as tasks from "./tasks";
+import * as thumbnails from "./thumbnails";
+import * as updater from "./updater";
+import * as urlMetadata from "./url-metadata";
+import * as viewPreferences from "./view-preferences";
+import * as webClipper from "./web-clipper";
+import * as workspaceExport from "./workspace-export";
+import * as workspaceLock from "./workspace-lock";
+import * as writingStats from "./writing-stats";
 
-function toErrorMessage(error: unknown): string {
-  if (error instanceof Error) {
-    return error.message;
+const DEFAULT_SCAN_BATCH_SIZE = 500;
+
+/** Quiet time after a note's last save before other windows are told about it */
+const SAVE_BROADCAST_DELAY_MS = 1000;
+
+const pendingSaveBroadcasts = new Map<string, ReturnType<typeof setTimeout>>();
+
+/**
+ * Progress callback that broadcasts "operation-progress" events for an operation
+ */
+function emitProgress(
+  operationId: string | undefined,
+  kind: OperationProgressEvent["kind"]
+): ((progress: OperationProgress) => void) | undefined {
+  if (operationId === undefined) {
+    return undefined;
+  }
+  return (progress) => emit("operation-progress", { ...progress, operation_id: operationId, kind });
+}
+
+/**
+ * Broadcast a "file-changed" event so other windows of the workspace can catch up
+ * Never throws: a failed broadcast must not fail the change itself
+ * @param entryKind - Whether the entry is a file or folder; looked up from the
+ *   entry's current path when not given, so deletions have to pass it
+ */
+async function emitFileChange(
+  kind: FileChangeKind,
+  path: string,
+  newPath: string | null = null,
+  entryKind?: FileEntryKind
+): Promise<void> {
+  try {
+    const workspacePath = await fsService.restoreWorkspace();
+    if (!workspacePath) {
+      return;
+    }
+
+    emit("file-changed", {
+      workspace_path: workspacePath,
+      kind,
+      entry_kind: entryKind ?? (await fsService.getEntryKind(newPath ?? path)) ?? "file",
+      path: await fsService.getRelativeWorkspacePath(path),
+      new_path: newPath === null ? null : await fsService.getRelativeWorkspacePath(newPath),
+    });
+  } catch (error) {
+    console.warn("[Api] Failed to broadcast file change:", error);
+  }
+}
+
+/**
+ * Broadcast an editor save once saves to the note pause
+ * Autosave writes every few hundred milliseconds; a broadcast per save would
+ * have every other window re-read the note just as often.
+ */
+function scheduleSaveBroadcast(path: string): void {
+  clearTimeout(pendingSaveBroadcasts.get(path));
+  pendingSaveBroadcasts.set(
+    path,
+    setTimeout(() => {
+      pendingSaveBroadcasts.delete(path);
+      void emitFileChange("modified", path);
+    }, SAVE_BROADCAST_DELAY_MS)
+  );
+}
+
+/**
+ * Forget waiting save broadcasts for an entry and everything inside it, once it has moved or gone
+ */
+function dropSaveBroadcasts(path: string): void {
+  for (const [pending, timer] of pendingSaveBroadcasts) {
+    if (pending === path || pending.startsWith(`${path}/`)) {
+      clearTimeout(timer);
+      pendingSaveBroadcasts.delete(pending);
+    }
   }
+}
 
-  return String(error);
+if (typeof window !== "undefined") {
+  // Send what is still waiting before the window goes away
+  window.addEventListener("pagehide", () => {
+    for (const [path, timer] of pendingSaveBroadcasts) {
+      clearTimeout(timer);
+      void emitFileChange("modified", path);
+    }
+    pendingSaveBroadcasts.clear();
+  });
 }
 
 export async function showOpenDialog(): Promise<string | null> {
@@ -14,7 +211,51 @@ export async function showOpenDialog(): Promise<string | null> {
     return await fsService.openWorkspace();
   } catch (error) {
     console.error("Failed to open workspace:", error);
-    throw new Error(`Failed to open folder dialog: ${toErrorMessage(error)}`);
+    throw toAppError(error, "Failed to open folder dialog");
+  }
+}
+
+export async function showSaveDialog(options: SaveDialogOptions = {}): Promise<SaveTarget | null> {
+  try {
+    return await fsService.showSaveDialog(options);
+  } catch (error) {
+    console.error("Failed to show save dialog:", error);
+    throw toAppError(error, "Failed to open save dialog");
+  }
+}
+
+export async function showOpenFilesDialog(
+  destinationPath: string,
+  options: OpenFilesDialogOptions = {}
+): Promise<string[]> {
+  try {
+    const imported = await fsService.showOpenFilesDialog(destinationPath, options);
+    for (const path of imported) {
+      await auditLog.recordAudit("import", path);
+      await emitFileChange("created", path);
+    }
+    return imported;
+  } catch (error) {
+    console.error("Failed to import files:", error);
+    throw toAppError(error, "Failed to import files");
+  }
+}
+
+export async function writeFileHandle(handle: FileSystemFileHandle, data: string | Blob): Promise<void> {
+  try {
+    await fsService.writeFileHandle(handle, data);
+  } catch (error) {
+    console.error("Failed to write file:", error);
+    throw toAppError(error, `Failed to write file "${handle.name}"`);
+  }
+}
+
+export async function resolveWorkspacePath(handle: FileSystemHandle): Promise<string | null> {
+  try {
+    return await fsService.resolveWorkspacePath(handle);
+  } catch (error) {
+    console.error("Failed to resolve handle:", error);
+    throw toAppError(error, `Failed to resolve "${handle.name}"`);
   }
 }
 
@@ -23,7 +264,7 @@ export async function getWorkspace(): Promise<string | null> {
     return await fsService.restoreWorkspace();
   } catch (error) {
     console.error("Failed to restore workspace:", error);
-    throw new Error(`Failed to get workspace: ${toErrorMessage(error)}`);
+    throw toAppError(error, "Failed to get workspace");
   }
 }
 
@@ -32,7 +273,34 @@ export async function requestWorkspacePermission(): Promise<string | null> {
     return await fsService.requestWorkspacePermission();
   } catch (error) {
     console.error("Failed to request workspace permission:", error);
-    throw new Error(`Failed to request workspace permission: ${toErrorMessage(error)}`);
+    throw toAppError(error, "Failed to request workspace permission");
+  }
+}
+
+export async function acquireWorkspaceLock(): Promise<WorkspaceLockStatus> {
+  try {
+    return await workspaceLock.acquireWorkspaceLock();
+  } catch (error) {
+    console.error("Failed to lock workspace:", error);
+    throw toAppError(error, "Failed to lock workspace");
+  }
+}
+
+export async function forceUnlockWorkspace(): Promise<void> {
+  try {
+    await workspaceLock.forceUnlock();
+  } catch (error) {
+    console.error("Failed to unlock workspace:", error);
+    throw toAppError(error, "Failed to unlock workspace");
+  }
+}
+
+export async function releaseWorkspaceLock(): Promise<void> {
+  try {
+    await workspaceLock.releaseWorkspaceLock();
+  } catch (error) {
+    console.error("Failed to release workspace lock:", error);
+    throw toAppError(error, "Failed to release workspace lock");
   }
 }
 
@@ -41,33 +309,164 @@ export async function hasStoredWorkspace(): Promise<boolean> {
     return await fsService.hasStoredWorkspace();
   } catch (error) {
     console.error("Failed to check stored workspace:", error);
-    throw new Error(`Failed to check stored workspace: ${toErrorMessage(error)}`);
+    throw toAppError(error, "Failed to check stored workspace");
+  }
+}
+
+/**
+ * Find notes that duplicate each other: identical content, or titles that only
+ * differ in case, punctuation or a copy suffix
+ * @returns Groups of notes, identical ones first
+ */
+export async function findDuplicateNotes(operationId?: string): Promise<DuplicateGroup[]> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    return await duplicates.findDuplicateNotes(signal);
+  } catch (error) {
+    console.error("Failed to find duplicate notes:", error);
+    throw toAppError(error, "Failed to find duplicate notes");
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+/**
+ * Add up disk usage per folder and per kind of file (notes, images, other)
+ * @returns Tree of folders, each with its totals and its subfolders largest first
+ */
+export async function getStorageBreakdown(includeHidden: boolean = false, operationId?: string): Promise<StorageNode> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    return await storageReport.getStorageBreakdown(includeHidden, signal);
+  } catch (error) {
+    console.error("Failed to get storage breakdown:", error);
+    throw toAppError(error, "Failed to get storage breakdown");
+  } finally {
+    operations.endOperation(operationId, signal);
   }
 }
 
 export async function readDirectory(
   path: string,
-  includeHidden: boolean = false
+  includeHidden: boolean = false,
+  operationId?: string
 ): Promise<FileNode> {
+  let signal: AbortSignal | undefined;
   try {
-    return await fsService.readDirectory(path, includeHidden);
+    signal = operations.beginOperation(operationId);
+    return await fsService.readDirectory(path, includeHidden, signal);
   } catch (error) {
     console.error("Failed to read directory:", error);
-    throw new Error(`Failed to read directory "${path}": ${toErrorMessage(error)}`);
+    throw toAppError(error, `Failed to read directory "${path}"`, path);
+  } finally {
+    operations.endOperation(operationId, signal);
   }
 }
 
+/**
+ * @param filter - Only list entries whose name matches, ignoring case
+ */
 export async function getDirectoryPage(
   path: string,
   offset: number,
   limit: number,
-  includeHidden: boolean = false
+  includeHidden: boolean = false,
+  filter: string = "",
+  operationId?: string
 ): Promise<DirectoryPage> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    return await fsService.getDirectoryPage(path, offset, limit, includeHidden, filter, signal);
+  } catch (error) {
+    console.error("Failed to get directory page:", error);
+    throw toAppError(error, `Failed to get directory page "${path}"`, path);
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+/**
+ * Count a directory's entries, cheaper than listing it
+ */
+export async function countDirectoryItems(
+  path: string,
+  includeHidden: boolean = false,
+  operationId?: string
+): Promise<number> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    return await fsService.countDirectoryItems(path, includeHidden, signal);
+  } catch (error) {
+    console.error("Failed to count directory items:", error);
+    throw toAppError(error, `Failed to count items in "${path}"`, path);
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+/**
+ * Page through a directory listing that stays fixed between pages
+ * @param cursor - next_cursor of the previous page, or null to start
+ */
+export async function getDirectoryPageByCursor(
+  path: string,
+  cursor: string | null,
+  limit: number,
+  includeHidden: boolean = false,
+  operationId?: string
+): Promise<DirectoryCursorPage> {
+  let signal: AbortSignal | undefined;
   try {
-    return await fsService.getDirectoryPage(path, offset, limit, includeHidden);
+    signal = operations.beginOperation(operationId);
+    return await fsService.getDirectoryPageByCursor(path, cursor, limit, includeHidden, signal);
   } catch (error) {
     console.error("Failed to get directory page:", error);
-    throw new Error(`Failed to get directory page "${path}": ${toErrorMessage(error)}`);
+    throw toAppError(error, `Failed to get directory page "${path}"`, path);
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+/**
+ * Read a directory as a stream of "directory-scan-batch" events followed by a
+ * "directory-scan-complete" event, so the tree can render a huge folder as
+ * it is read. Events carry the operation id, which also cancels the scan.
+ * @returns Number of entries scanned
+ */
+export async function scanDirectoryStreaming(
+  path: string,
+  operationId: string,
+  batchSize: number = DEFAULT_SCAN_BATCH_SIZE,
+  includeHidden: boolean = false
+): Promise<number> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    let totalCount = 0;
+    for await (const nodes of fsService.scanDirectory(path, batchSize, includeHidden, signal)) {
+      totalCount += nodes.length;
+      emit("directory-scan-batch", { operation_id: operationId, path, nodes });
+    }
+    emit("directory-scan-complete", { operation_id: operationId, path, total_count: totalCount });
+    return totalCount;
+  } catch (error) {
+    console.error("Failed to scan directory:", error);
+    throw toAppError(error, `Failed to scan directory "${path}"`, path);
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+export async function openWithDefaultApp(path: string): Promise<void> {
+  try {
+    await fsService.openWithDefaultApp(path);
+  } catch (error) {
+    console.error("Failed to open file:", error);
+    throw toAppError(error, `Failed to open "${path}"`, path);
   }
 }
 
@@ -76,52 +475,276 @@ export async function readFile(path: string): Promise<string> {
     return await fsService.readFile(path);
   } catch (error) {
     console.error("Failed to read file:", error);
-    throw new Error(`Failed to read file "${path}": ${toErrorMessage(error)}`);
+    throw toAppError(error, `Failed to read file "${path}"`, path);
   }
 }
 
 export async function writeFile(path: string, content: string): Promise<void> {
   try {
+    const previous = writingStats.isWritingStatsEnabled() ? await fsService.readFile(path).catch(() => "") : null;
     await fsService.writeFile(path, content);
+    await auditLog.recordSave(path);
+    scheduleSaveBroadcast(path);
+    if (previous !== null) {
+      await writingStats.recordNoteEdited(path, previous, content);
+    }
   } catch (error) {
     console.error("Failed to write file:", error);
-    throw new Error(`Failed to write file "${path}": ${toErrorMessage(error)}`);
+    throw toAppError(error, `Failed to write file "${path}"`, path);
+  }
+}
+
+/**
+ * Apply ranged edits to a file in one atomic write
+ * @param expectedHash - Refuse with a "conflict" error unless the file still has this hash
+ * @returns Content hash after the edit, usable as the next edit's precondition
+ */
+export async function applyEdits(path: string, edits: TextEdit[], expectedHash?: string): Promise<string> {
+  try {
+    const hash = await fsService.applyEdits(path, edits, expectedHash);
+    await auditLog.recordAudit("write", path);
+    await emitFileChange("modified", path);
+    return hash;
+  } catch (error) {
+    console.error("Failed to apply edits:", error);
+    throw toAppError(error, `Failed to edit file "${path}"`, path);
+  }
+}
+
+/**
+ * Align the columns of a note's table
+ * @param tableIndex - 0-based index of the table among the note's tables
+ */
+export async function tableFormat(path: string, tableIndex: number): Promise<void> {
+  try {
+    await tables.formatTableInNote(path, tableIndex);
+    await auditLog.recordAudit("write", path);
+    await emitFileChange("modified", path);
+  } catch (error) {
+    console.error("Failed to format table:", error);
+    throw toAppError(error, `Failed to format table in "${path}"`, path);
+  }
+}
+
+/**
+ * Insert a column into a note's table, aligning the table afterwards
+ * @param column - 0-based position of the new column
+ */
+export async function tableInsertColumn(
+  path: string,
+  tableIndex: number,
+  column: number,
+  header?: string,
+  alignment?: TableAlignment
+): Promise<void> {
+  try {
+    await tables.insertTableColumn(path, tableIndex, column, header, alignment);
+    await auditLog.recordAudit("write", path);
+    await emitFileChange("modified", path);
+  } catch (error) {
+    console.error("Failed to insert table column:", error);
+    throw toAppError(error, `Failed to edit table in "${path}"`, path);
+  }
+}
+
+/**
+ * Sort the rows of a note's table by a 0-based column, aligning the table afterwards
+ */
+export async function tableSortByColumn(
+  path: string,
+  tableIndex: number,
+  column: number,
+  descending?: boolean
+): Promise<void> {
+  try {
+    await tables.sortTableByColumn(path, tableIndex, column, descending);
+    await auditLog.recordAudit("write", path);
+    await emitFileChange("modified", path);
+  } catch (error) {
+    console.error("Failed to sort table:", error);
+    throw toAppError(error, `Failed to edit table in "${path}"`, path);
+  }
+}
+
+export async function getFileHash(path: string): Promise<string> {
+  try {
+    return await fsService.getFileHash(path);
+  } catch (error) {
+    console.error("Failed to hash file:", error);
+    throw toAppError(error, `Failed to read file "${path}"`, path);
+  }
+}
+
+/**
+ * Open a note as a CRDT document shared by every window editing it
+ * Edits are stored as they happen and written back to the file shortly after
+ * @returns Current text of the document
+ */
+export async function openDocument(path: string): Promise<string> {
+  try {
+    return await crdtDocument.openDocument(path);
+  } catch (error) {
+    console.error("Failed to open document:", error);
+    throw toAppError(error, `Failed to open document "${path}"`, path);
+  }
+}
+
+/**
+ * Apply ranged edits to an open document as CRDT operations
+ * @returns Text after the edits
+ */
+export async function applyDocumentEdits(path: string, edits: TextEdit[]): Promise<string> {
+  try {
+    return await crdtDocument.applyDocumentEdits(path, edits);
+  } catch (error) {
+    console.error("Failed to edit document:", error);
+    throw toAppError(error, `Failed to edit document "${path}"`, path);
+  }
+}
+
+export async function getDocumentText(path: string): Promise<string> {
+  try {
+    return await crdtDocument.getDocumentText(path);
+  } catch (error) {
+    console.error("Failed to read document:", error);
+    throw toAppError(error, `Failed to read document "${path}"`, path);
+  }
+}
+
+/**
+ * Call a handler with the full text whenever an open document changes here or in another window
+ * @returns Function that removes the handler
+ */
+export async function observeDocument(path: string, handler: (text: string) => void): Promise<() => void> {
+  try {
+    return await crdtDocument.observeDocument(path, handler);
+  } catch (error) {
+    console.error("Failed to observe document:", error);
+    throw toAppError(error, `Failed to observe document "${path}"`, path);
+  }
+}
+
+/**
+ * Release a handle from openDocument, saving the document when it was the last
+ */
+export async function closeDocument(path: string): Promise<void> {
+  try {
+    await crdtDocument.closeDocument(path);
+  } catch (error) {
+    console.error("Failed to close document:", error);
+    throw toAppError(error, `Failed to close document "${path}"`, path);
   }
 }
 
 export async function createFile(path: string): Promise<void> {
   try {
     await fsService.createFile(path);
+    await auditLog.recordAudit("create-file", path);
+    await emitFileChange("created", path);
+    await writingStats.recordNoteCreated(path);
   } catch (error) {
     console.error("Failed to create file:", error);
-    throw new Error(toErrorMessage(error));
+    throw toAppError(error, undefined, path);
+  }
+}
+
+export async function generateUntitledPath(
+  directoryPath: string,
+  baseName: string = "Untitled",
+  extension: string = "md"
+): Promise<string> {
+  try {
+    const path = await fsService.generateUntitledPath(directoryPath, baseName, extension);
+    await auditLog.recordAudit("create-file", path);
+    await emitFileChange("created", path);
+    await writingStats.recordNoteCreated(path);
+    return path;
+  } catch (error) {
+    console.error("Failed to create untitled file:", error);
+    throw toAppError(error, `Failed to create untitled file in "${directoryPath}"`, directoryPath);
   }
 }
 
 export async function createFolder(path: string): Promise<void> {
   try {
     await fsService.createFolder(path);
+    await auditLog.recordAudit("create-folder", path);
+    await emitFileChange("created", path);
   } catch (error) {
     console.error("Failed to create folder:", error);
-    throw new Error(toErrorMessage(error));
+    throw toAppError(error, undefined, path);
   }
 }
 
-export async function renamePath(oldPath: string, newPath: string): Promise<void> {
+export async function renamePath(oldPath: string, newPath: string, operationId?: string): Promise<void> {
+  let signal: AbortSignal | undefined;
   try {
-    await fsService.renamePath(oldPath, newPath);
+    signal = operations.beginOperation(operationId);
+    await fsService.renamePath(oldPath, newPath, signal, emitProgress(operationId, "copy"));
+    dropSaveBroadcasts(oldPath);
+    await auditLog.recordAudit("rename", oldPath, newPath);
+    await emitFileChange("renamed", oldPath, newPath);
+
+    // The move is done; a note whose links can't be updated is left as it was
+    const rebased = await linkRebase.rebaseMovedLinks(oldPath, newPath).catch((error) => {
+      console.error("Failed to update links in moved notes:", error);
+      return [];
+    });
+    for (const path of rebased) {
+      await auditLog.recordAudit("write", path);
+      await emitFileChange("modified", path);
+    }
   } catch (error) {
     console.error("Failed to rename:", error);
-    throw new Error(toErrorMessage(error));
+    throw toAppError(error, undefined, oldPath);
+  } finally {
+    operations.endOperation(operationId, signal);
   }
 }
 
-export async function deletePath(path: string): Promise<void> {
+export async function deletePath(path: string, operationId?: string): Promise<void> {
+  let signal: AbortSignal | undefined;
   try {
-    await fsService.deletePath(path);
+    signal = operations.beginOperation(operationId);
+    const entryKind = await fsService.getEntryKind(path);
+    await fsService.deletePath(path, signal, emitProgress(operationId, "delete"));
+    dropSaveBroadcasts(path);
+    await auditLog.recordAudit("delete", path);
+    await emitFileChange("deleted", path, null, entryKind ?? "file");
   } catch (error) {
     console.error("Failed to delete:", error);
-    throw new Error(toErrorMessage(error));
+    throw toAppError(error, undefined, path);
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+/**
+ * Remove folders left empty below a scope (e.g. after notes were moved out)
+ * @param dryRun - Only list the folders that would be removed
+ * @returns Outermost empty folders; empty folders inside them go with them
+ */
+export async function removeEmptyDirs(
+  scope: string = ".",
+  dryRun: boolean = false,
+  operationId?: string
+): Promise<string[]> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    const folders = await emptyFolders.removeEmptyDirs(scope, dryRun, signal);
+    if (!dryRun) {
+      for (const folder of folders) {
+        await auditLog.recordAudit("delete", folder);
+        await emitFileChange("deleted", folder, null, "directory");
+      }
+    }
+    return folders;
+  } catch (error) {
+    console.error("Failed to remove empty folders:", error);
+    throw toAppError(error, "Failed to remove empty folders", scope);
+  } finally {
+    operations.endOperation(operationId, signal);
   }
 }
 
@@ -130,16 +753,41 @@ export async function clearWorkspace(): Promise<void> {
     await fsService.clearWorkspace();
   } catch (error) {
     console.error("Failed to clear workspace:", error);
-    throw new Error(`Failed to clear workspace: ${toErrorMessage(error)}`);
+    throw toAppError(error, "Failed to clear workspace");
   }
 }
 
-export async function uploadImage(file: File): Promise<string> {
+export async function uploadImage(file: File, options: UploadImageOptions = {}): Promise<UploadedImage> {
   try {
-    return await fsService.uploadImage(file);
+    const image = await fsService.uploadImage(file, options);
+    if (!image.reused) {
+      await auditLog.recordAudit("upload-image", image.path);
+      await emitFileChange("created", image.path);
+    }
+
+    // Text is recognized in the background; failing to read it only leaves the image unsearchable
+    if (!image.reused) {
+      void fsService
+        .getWorkspaceSettings()
+        .then(({ assets }) => (assets.ocr ? imageText.indexImageText(image.path) : ""))
+        .catch((error) => console.warn("Failed to recognize image text:", error));
+    }
+    return image;
   } catch (error) {
     console.error("Failed to upload image:", error);
-    throw new Error(toErrorMessage(error));
+    throw toAppError(error);
+  }
+}
+
+/**
+ * Find images by the text recognized in them
+ */
+export async function searchImageText(query: string): Promise<ImageTextMatch[]> {
+  try {
+    return await imageText.searchImageText(query);
+  } catch (error) {
+    console.error("Failed to search image text:", error);
+    throw toAppError(error, "Failed to search image text");
   }
 }
 
@@ -151,6 +799,764 @@ export async function resolveImagePreviewSource(
     return await fsService.resolveImagePreviewSource(imageSource, currentDocumentPath);
   } catch (error) {
     console.error("Failed to resolve image preview source:", error);
-    throw new Error(toErrorMessage(error));
+    throw toAppError(error);
+  }
+}
+
+export async function getThumbnail(path: string, maxSize: number): Promise<string> {
+  try {
+    return await thumbnails.getThumbnail(path, maxSize);
+  } catch (error) {
+    console.error("Failed to get thumbnail:", error);
+    throw toAppError(error, `Failed to get thumbnail for "${path}"`, path);
+  }
+}
+
+export async function getWorkspaceSettings(): Promise<WorkspaceSettings> {
+  try {
+    return await fsService.getWorkspaceSettings();
+  } catch (error) {
+    console.error("Failed to read workspace settings:", error);
+    throw toAppError(error, "Failed to read workspace settings");
+  }
+}
+
+export async function saveWorkspaceSettings(settings: WorkspaceSettings): Promise<void> {
+  try {
+    await fsService.saveWorkspaceSettings(settings);
+    await auditLog.recordAudit("settings", ".mdx/settings.json");
+  } catch (error) {
+    console.error("Failed to save workspace settings:", error);
+    throw toAppError(error, "Failed to save workspace settings");
+  }
+}
+
+/**
+ * Turn on web clipper links with a new token, replacing any earlier one
+ * @returns The token a browser extension or bookmarklet has to send
+ */
+export async function createClipperToken(): Promise<string> {
+  try {
+    const token = await webClipper.createClipperToken();
+    await auditLog.recordAudit("settings", ".mdx/settings.json");
+    return token;
+  } catch (error) {
+    console.error("Failed to create clipper token:", error);
+    throw toAppError(error, "Failed to create clipper token");
+  }
+}
+
+/**
+ * Save a page sent by the web clipper as a new note
+ * @returns Path of the new note
+ */
+export async function saveWebClipping(clipping: WebClipping, token: string): Promise<string> {
+  try {
+    const path = await webClipper.saveClipping(clipping, token);
+    await auditLog.recordAudit("create-file", path);
+    await emitFileChange("created", path);
+    return path;
+  } catch (error) {
+    console.error("Failed to save clipping:", error);
+    throw toAppError(error, "Failed to save clipping");
+  }
+}
+
+export async function saveSession(state: WorkspaceSession): Promise<void> {
+  try {
+    session.saveSession(await fsService.getWorkspaceId(), state);
+  } catch (error) {
+    console.error("Failed to save session:", error);
+    throw toAppError(error, "Failed to save session");
+  }
+}
+
+export async function loadSession(): Promise<WorkspaceSession | null> {
+  try {
+    return session.loadSession(await fsService.getWorkspaceId());
+  } catch (error) {
+    console.error("Failed to load session:", error);
+    throw toAppError(error, "Failed to load session");
+  }
+}
+
+export async function exportHtml(path: string, options: HtmlExportOptions = {}): Promise<string | null> {
+  try {
+    return await htmlExport.exportHtml(path, options);
+  } catch (error) {
+    console.error("Failed to export HTML:", error);
+    throw toAppError(error, `Failed to export "${path}" to HTML`, path);
+  }
+}
+
+/**
+ * Copy a note (or unsaved markdown for it) to the clipboard as HTML and plain text
+ */
+export async function copyAsHtml(path: string, content?: string): Promise<void> {
+  try {
+    await htmlExport.copyAsHtml(path, content);
+  } catch (error) {
+    console.error("Failed to copy as HTML:", error);
+    throw toAppError(error, `Failed to copy "${path}" as HTML`, path);
+  }
+}
+
+/**
+ * Copy a path as an absolute or workspace-relative path, or as a markdown link
+ * @param fromPath - Note a markdown link is meant for; the link is relative to it
+ * @returns The copied text
+ */
+export async function copyPathToClipboard(path: string, kind: PathCopyKind, fromPath?: string): Promise<string> {
+  try {
+    return await copyPath.copyPathToClipboard(path, kind, fromPath);
+  } catch (error) {
+    console.error("Failed to copy path:", error);
+    throw toAppError(error, `Failed to copy the path of "${path}"`, path);
+  }
+}
+
+export async function exportDocx(path: string): Promise<string | null> {
+  try {
+    return await docxExport.exportDocx(path);
+  } catch (error) {
+    console.error("Failed to export DOCX:", error);
+    throw toAppError(error, `Failed to export "${path}" to Word`, path);
+  }
+}
+
+export function listExportFormats(): ExportFormat[] {
+  return exporters.listExportFormats();
+}
+
+export async function exportNote(path: string, format: ExportFormatId): Promise<string | null> {
+  try {
+    return await exporters.exportNote(path, format);
+  } catch (error) {
+    console.error("Failed to export note:", error);
+    throw toAppError(error, `Failed to export "${path}"`, path);
+  }
+}
+
+export async function renderMarkdown(content: string, options: RenderMarkdownOptions = {}): Promise<string> {
+  try {
+    return await markdownRender.renderMarkdown(content, options);
+  } catch (error) {
+    console.error("Failed to render markdown:", error);
+    throw toAppError(error, "Failed to render markdown");
+  }
+}
+
+export async function exportWorkspaceZip(
+  options: ZipExportOptions = {},
+  onProgress?: (progress: OperationProgress) => void,
+  signal?: AbortSignal
+): Promise<string | null> {
+  try {
+    return await workspaceExport.exportWorkspaceZip(options, onProgress, signal);
+  } catch (error) {
+    console.error("Failed to export workspace:", error);
+    throw toAppError(error, "Failed to export workspace");
+  }
+}
+
+/**
+ * Export chosen notes with the files they link to into a folder or ZIP file the user picks
+ * With an operation id, progress is broadcast as "operation-progress" events
+ * @returns Summary of the export, or null if the picker was cancelled
+ */
+export async function exportSelection(
+  paths: string[],
+  format: SelectionExportFormat,
+  options: SelectionExportOptions = {},
+  operationId?: string
+): Promise<SelectionExportReport | null> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    return await selectionExport.exportSelection(paths, format, options, emitProgress(operationId, "export"), signal);
+  } catch (error) {
+    console.error("Failed to export selection:", error);
+    throw toAppError(error, "Failed to export selection");
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+/**
+ * Publish notes as a static HTML site into a folder the user picks
+ * With an operation id, progress is broadcast as "operation-progress" events
+ * @returns Summary of the export, or null if the picker was cancelled
+ */
+export async function publishStaticSite(
+  options: SiteExportOptions = {},
+  operationId?: string
+): Promise<SiteExportReport | null> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    return await siteExport.publishStaticSite(options, emitProgress(operationId, "publish"), signal);
+  } catch (error) {
+    console.error("Failed to publish site:", error);
+    throw toAppError(error, "Failed to publish site");
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+export async function importObsidianVault(destinationPath: string): Promise<ImportReport | null> {
+  try {
+    const report = await obsidianImport.importObsidianVault(destinationPath);
+    if (report) {
+      await auditLog.recordAudit("import", report.destination);
+      await emitFileChange("created", report.destination);
+    }
+    return report;
+  } catch (error) {
+    console.error("Failed to import Obsidian vault:", error);
+    throw toAppError(error, "Failed to import Obsidian vault");
+  }
+}
+
+export async function importNotionExport(destinationPath: string): Promise<ImportReport | null> {
+  try {
+    const report = await notionImport.importNotionExport(destinationPath);
+    if (report) {
+      await auditLog.recordAudit("import", report.destination);
+      await emitFileChange("created", report.destination);
+    }
+    return report;
+  } catch (error) {
+    console.error("Failed to import Notion export:", error);
+    throw toAppError(error, "Failed to import Notion export");
+  }
+}
+
+/**
+ * Copy a folder the user picks into a workspace folder, keeping its structure
+ * With an operation id, progress is broadcast as "operation-progress" events
+ * @returns Import report, or null if the picker was cancelled
+ */
+export async function importFolder(
+  destinationPath: string,
+  options: FolderImportOptions = {},
+  operationId?: string
+): Promise<ImportReport | null> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    const onProgress = emitProgress(operationId, "import");
+    const report = await folderImport.importFolder(destinationPath, options, onProgress, signal);
+    if (report) {
+      await auditLog.recordAudit("import", report.destination);
+      await emitFileChange("created", report.destination);
+    }
+    return report;
+  } catch (error) {
+    console.error("Failed to import folder:", error);
+    throw toAppError(error, "Failed to import folder");
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+export async function checkLinks(scope: string, operationId?: string): Promise<LinkCheckReport> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    return await linkChecker.checkLinks(scope, signal);
+  } catch (error) {
+    console.error("Failed to check links:", error);
+    throw toAppError(error, `Failed to check links in "${scope}"`, scope);
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+/**
+ * Rewrite links between notes from [[wikilinks]] to relative markdown links or back
+ * @param dryRun - Only report what would change
+ * @returns Changed notes with a diff of each
+ */
+export async function convertLinks(
+  scope: string,
+  direction: LinkConversion,
+  dryRun: boolean = false,
+  operationId?: string
+): Promise<LinkConversionChange[]> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    const changes = await linkConvert.convertLinks(scope, direction, dryRun, signal);
+    if (!dryRun) {
+      for (const { path } of changes) {
+        await auditLog.recordAudit("write", path);
+        await emitFileChange("modified", path);
+      }
+    }
+    return changes;
+  } catch (error) {
+    console.error("Failed to convert links:", error);
+    throw toAppError(error, `Failed to convert links in "${scope}"`, scope);
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+export async function getOutline(path: string): Promise<OutlineHeading[]> {
+  try {
+    return await outline.getOutline(path);
+  } catch (error) {
+    console.error("Failed to get outline:", error);
+    throw toAppError(error, `Failed to get outline for "${path}"`, path);
+  }
+}
+
+/**
+ * List a note's footnotes and link reference definitions with where they are used
+ * Definitions without usages are unused; references without a definition are missing
+ */
+export async function getReferences(path: string): Promise<NoteReference[]> {
+  try {
+    return await outline.getReferences(path);
+  } catch (error) {
+    console.error("Failed to get references:", error);
+    throw toAppError(error, `Failed to get references for "${path}"`, path);
+  }
+}
+
+/**
+ * List a note's fenced code blocks with their language, code and line range
+ */
+export async function getCodeBlocks(path: string): Promise<CodeBlock[]> {
+  try {
+    return await outline.getCodeBlocks(path);
+  } catch (error) {
+    console.error("Failed to get code blocks:", error);
+    throw toAppError(error, `Failed to get code blocks for "${path}"`, path);
+  }
+}
+
+/**
+ * Normalize a note's markdown: list markers and numbering, table alignment
+ * and optionally paragraph wrapping
+ */
+export function formatDocument(content: string, options: FormatOptions = {}): string {
+  return formatter.formatDocument(content, options);
+}
+
+/**
+ * Build the GitHub-style anchor for a heading's text
+ */
+export function slugify(text: string): string {
+  return markdownUtils.slugifyHeading(text);
+}
+
+/**
+ * Get the #anchors of a note's headings in document order
+ * Repeated headings get the same -1, -2, ... suffixes the renderer gives them
+ */
+export async function getHeadingAnchors(path: string): Promise<string[]> {
+  try {
+    const headings = await outline.getOutline(path);
+    return headings.map((heading) => heading.slug);
+  } catch (error) {
+    console.error("Failed to get heading anchors:", error);
+    throw toAppError(error, `Failed to get heading anchors for "${path}"`, path);
+  }
+}
+
+export async function getLinkCompletions(prefix: string, limit?: number): Promise<LinkCompletion[]> {
+  try {
+    return await noteIndex.getLinkCompletions(prefix, limit);
+  } catch (error) {
+    console.error("Failed to get link completions:", error);
+    throw toAppError(error, "Failed to get link completions");
+  }
+}
+
+export async function getTagCompletions(prefix: string, limit?: number): Promise<TagCompletion[]> {
+  try {
+    return await noteIndex.getTagCompletions(prefix, limit);
+  } catch (error) {
+    console.error("Failed to get tag completions:", error);
+    throw toAppError(error, "Failed to get tag completions");
+  }
+}
+
+/**
+ * Suggest bibliography entries for a [@key] citation being typed
+ * @param prefix - Text typed after @ (matches keys, author names and titles)
+ */
+export async function getCitations(prefix: string, limit?: number): Promise<Citation[]> {
+  try {
+    return await bibliography.getCitations(prefix, limit);
+  } catch (error) {
+    console.error("Failed to get citations:", error);
+    throw toAppError(error, "Failed to get citations");
+  }
+}
+
+export async function resolveNote(name: string): Promise<ResolvedNote | null> {
+  try {
+    return await noteIndex.resolveNote(name);
+  } catch (error) {
+    console.error("Failed to resolve note:", error);
+    throw toAppError(error, `Failed to resolve note "${name}"`);
+  }
+}
+
+export async function queryNotes(filter: string, options: NoteQueryOptions = {}): Promise<NoteMetadata[]> {
+  try {
+    return await noteQuery.queryNotes(filter, options);
+  } catch (error) {
+    console.error("Failed to query notes:", error);
+    throw toAppError(error, "Failed to query notes");
+  }
+}
+
+export async function getTasks(scope: string, filters: TaskFilters = {}): Promise<Task[]> {
+  try {
+    return await tasks.getTasks(scope, filters);
+  } catch (error) {
+    console.error("Failed to get tasks:", error);
+    throw toAppError(error, `Failed to get tasks in "${scope}"`, scope);
+  }
+}
+
+export async function getGraph(options: GraphOptions = {}): Promise<NoteGraph> {
+  try {
+    return await graph.getGraph(options);
+  } catch (error) {
+    console.error("Failed to build graph:", error);
+    throw toAppError(error, "Failed to build graph");
+  }
+}
+
+export async function diffContent(path: string, newContent: string, base: DiffBase = "file"): Promise<DiffHunk[]> {
+  try {
+    return await diff.diffContent(path, newContent, base);
+  } catch (error) {
+    console.error("Failed to diff content:", error);
+    throw toAppError(error, `Failed to diff "${path}"`, path);
+  }
+}
+
+export async function diffFiles(pathA: string, pathB: string): Promise<DiffHunk[]> {
+  try {
+    return await diff.diffFiles(pathA, pathB);
+  } catch (error) {
+    console.error("Failed to diff files:", error);
+    throw toAppError(error, `Failed to compare "${pathA}" with "${pathB}"`, pathA);
+  }
+}
+
+/**
+ * Merge two edited versions of a text with their common ancestor
+ */
+export function mergeThreeWay(base: string, ours: string, theirs: string, labels?: MergeLabels): MergeResult {
+  return merge.mergeThreeWay(base, ours, theirs, labels);
+}
+
+export async function findConflictedCopies(operationId?: string): Promise<ConflictedCopy[]> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    return await merge.findConflictedCopies(signal);
+  } catch (error) {
+    console.error("Failed to find conflicted copies:", error);
+    throw toAppError(error, "Failed to find conflicted copies");
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+export async function localizeRemoteImage(url: string, notePath: string | null = null): Promise<string> {
+  try {
+    return await remoteImages.localizeRemoteImage(url, notePath);
+  } catch (error) {
+    console.error("Failed to download image:", error);
+    throw toAppError(error, `Failed to download image ${url}`);
+  }
+}
+
+export async function localizeRemoteImages(path: string): Promise<LocalizeImagesReport> {
+  try {
+    const report = await remoteImages.localizeRemoteImages(path);
+    if (report.localized > 0) {
+      await auditLog.recordAudit("write", path);
+      await emitFileChange("modified", path);
+    }
+    return report;
+  } catch (error) {
+    console.error("Failed to download remote images:", error);
+    throw toAppError(error, `Failed to download remote images in "${path}"`, path);
+  }
+}
+
+/**
+ * Read a web page's title, description and preview image, e.g. to turn a pasted URL into a link
+ */
+export async function fetchUrlMetadata(url: string, operationId?: string): Promise<UrlMetadata> {
+  let signal: AbortSignal | undefined;
+  try {
+    signal = operations.beginOperation(operationId);
+    return await urlMetadata.fetchUrlMetadata(url, signal);
+  } catch (error) {
+    console.error("Failed to fetch page metadata:", error);
+    throw toAppError(error, `Failed to read ${url}`);
+  } finally {
+    operations.endOperation(operationId, signal);
+  }
+}
+
+export async function getAuditLog(limit?: number): Promise<AuditEntry[]> {
+  try {
+    return await auditLog.getAuditLog(limit);
+  } catch (error) {
+    console.error("Failed to read audit log:", error);
+    throw toAppError(error, "Failed to read audit log");
+  }
+}
+
+export async function getDictionary(): Promise<string[]> {
+  try {
+    return await dictionary.getDictionary();
+  } catch (error) {
+    console.error("Failed to read dictionary:", error);
+    throw toAppError(error, "Failed to read dictionary");
+  }
+}
+
+export async function addToDictionary(word: string, scope?: DictionaryScope): Promise<boolean> {
+  try {
+    return await dictionary.addToDictionary(word, scope);
+  } catch (error) {
+    console.error("Failed to add word to dictionary:", error);
+    throw toAppError(error, `Failed to add "${word}" to the dictionary`);
+  }
+}
+
+export async function removeFromDictionary(word: string): Promise<boolean> {
+  try {
+    return await dictionary.removeFromDictionary(word);
+  } catch (error) {
+    console.error("Failed to remove word from dictionary:", error);
+    throw toAppError(error, `Failed to remove "${word}" from the dictionary`);
+  }
+}
+
+export function createOperationId(): string {
+  return operations.createOperationId();
+}
+
+/**
+ * Cancel a running directory scan, link check, folder move or delete
+ * @returns False if the operation already finished
+ */
+export function cancelOperation(id: string): boolean {
+  return operations.cancelOperation(id);
+}
+
+/**
+ * Check a proposed name for portability problems on any platform
+ */
+export function validateFilename(name: string): FilenameProblem[] {
+  return filenameUtils.validateFilename(name);
+}
+
+export function getAppLockStatus(): AppLockStatus {
+  return appLock.getAppLockStatus();
+}
+
+/**
+ * Resolve once the app is unlocked (immediately if it is not locked)
+ */
+export function whenAppUnlocked(): Promise<void> {
+  return appLock.whenUnlocked();
+}
+
+export function lockApp(): void {
+  appLock.lockApp();
+}
+
+/**
+ * @returns false if the password is wrong
+ */
+export async function unlockApp(password: string): Promise<boolean> {
+  try {
+    return await appLock.unlockApp(password);
+  } catch (error) {
+    console.error("Failed to unlock app:", error);
+    throw toAppError(error, "Failed to unlock");
+  }
+}
+
+export async function isBiometricUnlockAvailable(): Promise<boolean> {
+  return appLock.isBiometricUnlockAvailable();
+}
+
+/**
+ * @returns false if the user cancelled or couldn't be verified
+ */
+export async function unlockAppWithBiometrics(): Promise<boolean> {
+  try {
+    return await appLock.unlockAppWithBiometrics();
+  } catch (error) {
+    console.error("Failed to unlock app with biometrics:", error);
+    throw toAppError(error, "Failed to unlock");
+  }
+}
+
+export async function enableBiometricUnlock(password: string): Promise<void> {
+  try {
+    await appLock.enableBiometricUnlock(password);
+  } catch (error) {
+    console.error("Failed to enable biometric unlock:", error);
+    throw toAppError(error, "Failed to turn on biometric unlock");
+  }
+}
+
+export function disableBiometricUnlock(): void {
+  appLock.disableBiometricUnlock();
+}
+
+export async function setAppLockPassword(
+  password: string,
+  currentPassword: string | null,
+  autoLockMinutes: number | null
+): Promise<void> {
+  try {
+    await appLock.setAppLockPassword(password, currentPassword, autoLockMinutes);
+  } catch (error) {
+    console.error("Failed to set app password:", error);
+    throw toAppError(error, "Failed to set the password");
+  }
+}
+
+export async function removeAppLockPassword(password: string): Promise<void> {
+  try {
+    await appLock.removeAppLockPassword(password);
+  } catch (error) {
+    console.error("Failed to remove app password:", error);
+    throw toAppError(error, "Failed to remove the password");
+  }
+}
+
+export function setAutoLockTimeout(minutes: number | null): void {
+  appLock.setAutoLockTimeout(minutes);
+}
+
+export function getViewPreferences(): ViewPreferences {
+  return viewPreferences.getViewPreferences();
+}
+
+/**
+ * Change some view preferences; other windows follow
+ * @returns The preferences now in effect
+ */
+export function setViewPreferences(changes: Partial<ViewPreferences>): ViewPreferences {
+  return viewPreferences.setViewPreferences(changes);
+}
+
+export function getIoPolicy(): IoPolicy {
+  return ioPolicy.getIoPolicy();
+}
+
+/**
+ * Change how workspace reads and writes retry and time out on this device
+ * @returns The policy now in effect
+ */
+export function setIoPolicy(changes: Partial<IoPolicy>): IoPolicy {
+  return ioPolicy.setIoPolicy(changes);
+}
+
+export function isCrashReportingEnabled(): boolean {
+  return crashReports.isCrashReportingEnabled();
+}
+
+/**
+ * Opt in to or out of crash reporting; opting out deletes kept reports
+ */
+export async function setCrashReportingEnabled(enabled: boolean): Promise<void> {
+  try {
+    await crashReports.setCrashReportingEnabled(enabled);
+  } catch (error) {
+    console.error("Failed to change crash reporting:", error);
+    throw toAppError(error, "Failed to change crash reporting");
+  }
+}
+
+export async function getCrashReports(): Promise<CrashReport[]> {
+  try {
+    return await crashReports.getCrashReports();
+  } catch (error) {
+    console.error("Failed to read crash reports:", error);
+    throw toAppError(error, "Failed to read crash reports");
+  }
+}
+
+export async function submitCrashReport(id: string): Promise<void> {
+  try {
+    await crashReports.submitCrashReport(id);
+  } catch (error) {
+    console.error("Failed to submit crash report:", error);
+    throw toAppError(error, "Failed to submit the crash report");
+  }
+}
+
+export function isWritingStatsEnabled(): boolean {
+  return writingStats.isWritingStatsEnabled();
+}
+
+/**
+ * Opt in to or out of local writing stats; opting out deletes them
+ */
+export async function setWritingStatsEnabled(enabled: boolean): Promise<void> {
+  try {
+    await writingStats.setWritingStatsEnabled(enabled);
+  } catch (error) {
+    console.error("Failed to change writing stats:", error);
+    throw toAppError(error, "Failed to change writing stats");
+  }
+}
+
+/**
+ * @param from - First day, YYYY-MM-DD
+ * @param to - Last day, YYYY-MM-DD
+ */
+export async function getWritingStats(from: string, to: string): Promise<WritingStats> {
+  try {
+    return await writingStats.getWritingStats(from, to);
+  } catch (error) {
+    console.error("Failed to read writing stats:", error);
+    throw toAppError(error, "Failed to read writing stats");
+  }
+}
+
+export function getUpdateStatus(): UpdateStatus {
+  return updater.getUpdateStatus();
+}
+
+/**
+ * @returns The status after the check ("idle" when already up to date)
+ */
+export async function checkForUpdates(): Promise<UpdateStatus> {
+  try {
+    return await updater.checkForUpdates();
+  } catch (error) {
+    console.error("Failed to check for updates:", error);
+    throw toAppError(error, "Failed to check for updates");
+  }
+}
+
+/**
+ * Restart into the downloaded update
+ */
+export async function installUpdate(): Promise<void> {
+  try {
+    await updater.installUpdate();
+  } catch (error) {
+    console.error("Failed to install update:", error);
+    throw toAppError(error, "Failed to install the update");
   }
 }
@@ -0,0 +1,67 @@
+/**
+ * App event bus - Tauri-style emit/listen shared across windows
+ * Events reach listeners in this window and, via BroadcastChannel,
+ * every other window of the app open on the same origin
+ */
+
+import type { AppEventMap } from "../types";
+
+const CHANNEL_NAME = "mdx-app-events";
+
+export interface AppEventMeta {
+  /** True when the event was emitted by another window */
+  remote: boolean;
+}
+
+type AppEventName = keyof AppEventMap;
+type AppEventHandler<K extends AppEventName> = (payload: AppEventMap[K], meta: AppEventMeta) => void;
+type AnyHandler = (payload: unknown, meta: AppEventMeta) => void;
+
+interface AppEventEnvelope {
+  name: AppEventName;
+  payload: unknown;
+}
+
+const handlers = new Map<AppEventName, Set<AnyHandler>>();
+
+const channel = typeof BroadcastChannel !== "undefined" ? new BroadcastChannel(CHANNEL_NAME) : null;
+
+function dispatch(name: AppEventName, payload: unknown, meta: AppEventMeta): void {
+  const registered = handlers.get(name);
+  if (!registered) {
+    return;
+  }
+
+  for (const handler of registered) {
+    try {
+      handler(payload, meta);
+    } catch (error) {
+      console.error(`[AppEvents] Listener for "${name}" failed:`, error);
+    }
+  }
+}
+
+channel?.addEventListener("message", (event: MessageEvent<AppEventEnvelope>) => {
+  dispatch(event.data.name, event.data.payload, { remote: true });
+});
+
+export function emit<K extends AppEventName>(name: K, payload: AppEventMap[K]): void {
+  dispatch(name, payload, { remote: false });
+  channel?.postMessage({ name, payload } satisfies AppEventEnvelope);
+}
+
+/**
+ * Register a listener for an app event
+ * @returns Function that removes the listener
+ */
+export function listen<K extends AppEventName>(name: K, handler: AppEventHandler<K>): () => void {
+  const registered = handlers.get(name) ?? new Set<AnyHandler>();
+  handlers.set(name, registered);
+
+  const anyHandler = handler as AnyHandler;
+  registered.add(anyHandler);
+
+  return () => {
+    registered.delete(anyHandler);
+  };
+}
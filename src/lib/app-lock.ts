@@ -0,0 +1,404 @@
+/**
+ * App lock - hides the workspace behind a password
+ * Only a PBKDF2 verifier of the password is stored (localStorage), and the
+ * app starts locked whenever one is set. Lock state and activity are shared
+ * with the other windows, so locking one locks them all and typing in any
+ * window keeps the auto-lock from firing.
+ *
+ * Touch ID, Windows Hello and other platform authenticators can unlock the
+ * app too, through a WebAuthn credential created for it; the password always
+ * works as the fallback.
+ *
+ * This keeps the notes out of view in the app; the files themselves stay
+ * readable by anything else with access to the folder.
+ */
+
+import type { AppLockStatus } from "../types";
+import { emit, listen } from "./app-events";
+import { AppError } from "./errors";
+
+const CONFIG_KEY = "mdx-app-lock";
+const LAST_ACTIVITY_KEY = "mdx-last-activity";
+const PBKDF2_ITERATIONS = 600_000;
+const ACTIVITY_WRITE_INTERVAL_MS = 10_000;
+const AUTO_LOCK_CHECK_INTERVAL_MS = 15_000;
+const BIOMETRIC_TIMEOUT_MS = 60_000;
+
+/** COSE algorithm ids: ES256 (Touch ID, most platforms) and RS256 (Windows Hello) */
+const COSE_ES256 = -7;
+const COSE_RS256 = -257;
+
+/** Authenticator data flag set when the user was verified, not just present */
+const USER_VERIFIED_FLAG = 0x04;
+
+/** A platform authenticator credential that unlocks the app */
+interface BiometricCredential {
+  id: string;
+
+  /** SPKI public key the credential's signatures are checked against */
+  public_key: string;
+
+  algorithm: number;
+}
+
+interface AppLockConfig {
+  salt: string;
+  verifier: string;
+  iterations: number;
+  auto_lock_minutes: number | null;
+  biometric: BiometricCredential | null;
+}
+
+let locked = readConfig() !== null;
+let lastActivityWrite = 0;
+const unlockWaiters = new Set<() => void>();
+
+function readConfig(): AppLockConfig | null {
+  try {
+    const parsed = JSON.parse(localStorage.getItem(CONFIG_KEY) ?? "null") as Partial<AppLockConfig> | null;
+    return parsed && typeof parsed.salt === "string" && typeof parsed.verifier === "string"
+      ? {
+          salt: parsed.salt,
+          verifier: parsed.verifier,
+          iterations: typeof parsed.iterations === "number" ? parsed.iterations : PBKDF2_ITERATIONS,
+          auto_lock_minutes: typeof parsed.auto_lock_minutes === "number" ? parsed.auto_lock_minutes : null,
+          biometric: parsed.biometric && typeof parsed.biometric.id === "string" ? parsed.biometric : null,
+        }
+      : null;
+  } catch {
+    return null;
+  }
+}
+
+function toBase64(bytes: Uint8Array): string {
+  return btoa(String.fromCharCode(...bytes));
+}
+
+function fromBase64(text: string): Uint8Array {
+  return Uint8Array.from(atob(text), (char) => char.charCodeAt(0));
+}
+
+async function deriveVerifier(password: string, salt: Uint8Array, iterations: number): Promise<string> {
+  const key = await crypto.subtle.importKey("raw", new TextEncoder().encode(password), "PBKDF2", false, ["deriveBits"]);
+  const bits = await crypto.subtle.deriveBits({ name: "PBKDF2", hash: "SHA-256", salt, iterations }, key, 256);
+  return toBase64(new Uint8Array(bits));
+}
+
+async function verifyPassword(config: AppLockConfig, password: string): Promise<boolean> {
+  return (await deriveVerifier(password, fromBase64(config.salt), config.iterations)) === config.verifier;
+}
+
+function setLocked(value: boolean, broadcast = true): void {
+  locked = value;
+  if (!locked) {
+    recordActivity(true);
+    for (const resolve of unlockWaiters) {
+      resolve();
+    }
+    unlockWaiters.clear();
+  }
+  if (broadcast) {
+    emit("app-lock-changed", getAppLockStatus());
+  }
+}
+
+function recordActivity(force = false): void {
+  const now = Date.now();
+  if (force || now - lastActivityWrite >= ACTIVITY_WRITE_INTERVAL_MS) {
+    lastActivityWrite = now;
+    localStorage.setItem(LAST_ACTIVITY_KEY, String(now));
+  }
+}
+
+export function getAppLockStatus(): AppLockStatus {
+  const config = readConfig();
+  return {
+    enabled: config !== null,
+    locked: config !== null && locked,
+    auto_lock_minutes: config?.auto_lock_minutes ?? null,
+    biometric: config?.biometric != null,
+  };
+}
+
+export function isAppLocked(): boolean {
+  return getAppLockStatus().locked;
+}
+
+/**
+ * Throw a 'locked' AppError while the app is locked
+ */
+export function assertUnlocked(): void {
+  if (isAppLocked()) {
+    throw new AppError("locked", "The app is locked");
+  }
+}
+
+/**
+ * Resolve as soon as the app is unlocked (immediately if it is not locked)
+ */
+export function whenUnlocked(): Promise<void> {
+  if (!isAppLocked()) {
+    return Promise.resolve();
+  }
+  return new Promise((resolve) => unlockWaiters.add(resolve));
+}
+
+/**
+ * Set or change the password
+ * @param currentPassword - Required when a password is already set
+ */
+export async function setAppLockPassword(
+  password: string,
+  currentPassword: string | null,
+  autoLockMinutes: number | null
+): Promise<void> {
+  if (!password) {
+    throw new AppError("invalid-argument", "The password cannot be empty");
+  }
+
+  const existing = readConfig();
+  if (existing && (currentPassword === null || !(await verifyPassword(existing, currentPassword)))) {
+    throw new AppError("permission-denied", "The current password is incorrect");
+  }
+
+  const salt = crypto.getRandomValues(new Uint8Array(16));
+  const config: AppLockConfig = {
+    salt: toBase64(salt),
+    verifier: await deriveVerifier(password, salt, PBKDF2_ITERATIONS),
+    iterations: PBKDF2_ITERATIONS,
+    auto_lock_minutes: autoLockMinutes,
+    biometric: existing?.biometric ?? null,
+  };
+  localStorage.setItem(CONFIG_KEY, JSON.stringify(config));
+  setLocked(false);
+}
+
+/**
+ * Change how long the app may sit idle before locking itself
+ * @param minutes - Whole minutes, at least 1 (null: never lock when idle)
+ */
+export function setAutoLockTimeout(minutes: number | null): void {
+  if (minutes !== null && !(Number.isInteger(minutes) && minutes > 0)) {
+    throw new AppError("invalid-argument", "The auto-lock timeout must be a whole number of minutes");
+  }
+  const config = readConfig();
+  if (!config) {
+    throw new AppError("invalid-argument", "Set a password before configuring auto-lock");
+  }
+  localStorage.setItem(CONFIG_KEY, JSON.stringify({ ...config, auto_lock_minutes: minutes }));
+  emit("app-lock-changed", getAppLockStatus());
+}
+
+/**
+ * Remove the password, turning the lock off
+ */
+export async function removeAppLockPassword(password: string): Promise<void> {
+  const config = readConfig();
+  if (config && !(await verifyPassword(config, password))) {
+    throw new AppError("permission-denied", "The password is incorrect");
+  }
+  localStorage.removeItem(CONFIG_KEY);
+  setLocked(false);
+}
+
+export function lockApp(): void {
+  if (!readConfig()) {
+    throw new AppError("invalid-argument", "Set a password before locking the app");
+  }
+  setLocked(true);
+}
+
+/**
+ * @returns false if the password is wrong
+ */
+export async function unlockApp(password: string): Promise<boolean> {
+  const config = readConfig();
+  if (config && !(await verifyPassword(config, password))) {
+    return false;
+  }
+  setLocked(false);
+  return true;
+}
+
+/**
+ * Whether this device has a platform authenticator that verifies the user
+ * (Touch ID, Windows Hello, a phone's fingerprint reader)
+ */
+export async function isBiometricUnlockAvailable(): Promise<boolean> {
+  if (typeof PublicKeyCredential === "undefined" || !navigator.credentials) {
+    return false;
+  }
+  return PublicKeyCredential.isUserVerifyingPlatformAuthenticatorAvailable().catch(() => false);
+}
+
+/**
+ * Let the platform authenticator unlock the app, replacing any credential
+ * set up before. The system asks the user to verify themselves once.
+ * @param password - The app password, required to turn this on
+ */
+export async function enableBiometricUnlock(password: string): Promise<void> {
+  const config = readConfig();
+  if (!config) {
+    throw new AppError("invalid-argument", "Set a password before turning on biometric unlock");
+  }
+  if (!(await verifyPassword(config, password))) {
+    throw new AppError("permission-denied", "The password is incorrect");
+  }
+  if (!(await isBiometricUnlockAvailable())) {
+    throw new AppError("unsupported", "This device has no Touch ID, Windows Hello or similar authenticator");
+  }
+
+  const credential = (await navigator.credentials
+    .create({
+      publicKey: {
+        challenge: crypto.getRandomValues(new Uint8Array(32)),
+        rp: { name: "MDX Web" },
+        user: { id: crypto.getRandomValues(new Uint8Array(16)), name: "app-lock", displayName: "App lock" },
+        pubKeyCredParams: [
+          { type: "public-key", alg: COSE_ES256 },
+          { type: "public-key", alg: COSE_RS256 },
+        ],
+        authenticatorSelection: { authenticatorAttachment: "platform", userVerification: "required" },
+        timeout: BIOMETRIC_TIMEOUT_MS,
+      },
+    })
+    .catch((error: unknown) => {
+      throw new AppError("cancelled", "Biometric setup was cancelled", { cause: error });
+    })) as PublicKeyCredential | null;
+
+  const response = credential?.response as AuthenticatorAttestationResponse | undefined;
+  const publicKey = response?.getPublicKey();
+  if (!credential || !response || !publicKey) {
+    throw new AppError("unsupported", "The authenticator didn't return a usable credential");
+  }
+
+  const biometric: BiometricCredential = {
+    id: toBase64(new Uint8Array(credential.rawId)),
+    public_key: toBase64(new Uint8Array(publicKey)),
+    algorithm: response.getPublicKeyAlgorithm(),
+  };
+  localStorage.setItem(CONFIG_KEY, JSON.stringify({ ...config, biometric }));
+  emit("app-lock-changed", getAppLockStatus());
+}
+
+/**
+ * Stop accepting the platform authenticator; only the password unlocks the app
+ */
+export function disableBiometricUnlock(): void {
+  const config = readConfig();
+  if (config?.biometric) {
+    localStorage.setItem(CONFIG_KEY, JSON.stringify({ ...config, biometric: null }));
+    emit("app-lock-changed", getAppLockStatus());
+  }
+}
+
+/**
+ * Convert a DER-encoded ECDSA signature to the r || s form WebCrypto verifies
+ */
+function derToRawSignature(der: Uint8Array): Uint8Array {
+  const raw = new Uint8Array(64);
+  let offset = 2;
+  for (const target of [0, 32]) {
+    const length = der[offset + 1];
+    let integer = der.subarray(offset + 2, offset + 2 + length);
+    while (integer.length > 32 && integer[0] === 0) {
+      integer = integer.subarray(1);
+    }
+    raw.set(integer, target + 32 - integer.length);
+    offset += 2 + length;
+  }
+  return raw;
+}
+
+async function verifyAssertion(
+  biometric: BiometricCredential,
+  challenge: Uint8Array,
+  response: AuthenticatorAssertionResponse
+): Promise<boolean> {
+  const clientData = JSON.parse(new TextDecoder().decode(response.clientDataJSON)) as {
+    type?: string;
+    challenge?: string;
+  };
+  const expectedChallenge = toBase64(challenge).replace(/\+/g, "-").replace(/\//g, "_").replace(/=+$/, "");
+  const authenticatorData = new Uint8Array(response.authenticatorData);
+  if (
+    clientData.type !== "webauthn.get" ||
+    clientData.challenge !== expectedChallenge ||
+    (authenticatorData[32] & USER_VERIFIED_FLAG) === 0
+  ) {
+    return false;
+  }
+
+  const ecdsa = biometric.algorithm === COSE_ES256;
+  const algorithm = ecdsa ? { name: "ECDSA", namedCurve: "P-256" } : { name: "RSASSA-PKCS1-v1_5", hash: "SHA-256" };
+  const key = await crypto.subtle.importKey("spki", fromBase64(biometric.public_key), algorithm, false, ["verify"]);
+
+  const clientDataHash = new Uint8Array(await crypto.subtle.digest("SHA-256", response.clientDataJSON));
+  const signed = new Uint8Array(authenticatorData.length + clientDataHash.length);
+  signed.set(authenticatorData);
+  signed.set(clientDataHash, authenticatorData.length);
+
+  const signature = new Uint8Array(response.signature);
+  return crypto.subtle.verify(
+    ecdsa ? { name: "ECDSA", hash: "SHA-256" } : algorithm,
+    key,
+    ecdsa ? derToRawSignature(signature) : signature,
+    signed
+  );
+}
+
+/**
+ * Unlock with Touch ID, Windows Hello or the device's other authenticator
+ * @returns false if the user cancelled or couldn't be verified; the password still works
+ */
+export async function unlockAppWithBiometrics(): Promise<boolean> {
+  const biometric = readConfig()?.biometric;
+  if (!biometric) {
+    throw new AppError("invalid-argument", "Biometric unlock is not set up");
+  }
+
+  const challenge = crypto.getRandomValues(new Uint8Array(32));
+  const credential = (await navigator.credentials
+    .get({
+      publicKey: {
+        challenge,
+        allowCredentials: [{ type: "public-key", id: fromBase64(biometric.id) }],
+        userVerification: "required",
+        timeout: BIOMETRIC_TIMEOUT_MS,
+      },
+    })
+    .catch(() => null)) as PublicKeyCredential | null;
+  if (!credential) {
+    return false;
+  }
+
+  if (!(await verifyAssertion(biometric, challenge, credential.response as AuthenticatorAssertionResponse))) {
+    return false;
+  }
+  setLocked(false);
+  return true;
+}
+
+listen("app-lock-changed", (status, { remote }) => {
+  if (remote) {
+    setLocked(status.locked, false);
+  }
+});
+
+if (typeof window !== "undefined") {
+  for (const eventName of ["pointerdown", "keydown", "wheel"]) {
+    window.addEventListener(eventName, () => recordActivity(), { passive: true, capture: true });
+  }
+
+  setInterval(() => {
+    const { enabled, locked: isLocked, auto_lock_minutes: minutes } = getAppLockStatus();
+    if (!enabled || isLocked || minutes === null) {
+      return;
+    }
+
+    const lastActivity = Number(localStorage.getItem(LAST_ACTIVITY_KEY) ?? Date.now());
+    if (Date.now() - lastActivity >= minutes * 60_000) {
+      setLocked(true);
+    }
+  }, AUTO_LOCK_CHECK_INTERVAL_MS);
+}
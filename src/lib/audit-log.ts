@@ -0,0 +1,154 @@
+/**
+ * Audit log - records every mutating operation as JSON lines in the origin
+ * private file system, so "what deleted my file" can be answered later.
+ * The log rotates at MAX_LOG_BYTES and keeps MAX_ROTATED_LOGS old files.
+ * Logging never fails the operation being logged.
+ */
+
+import type { AuditEntry, AuditOperation } from "../types";
+import * as fsService from "./fs-service";
+
+const LOG_DIRECTORY = "audit";
+const LOG_FILE = "audit.jsonl";
+const MAX_LOG_BYTES = 1024 * 1024;
+const MAX_ROTATED_LOGS = 3;
+const DEFAULT_LOG_LIMIT = 500;
+
+/** Workspace and path of every note whose saves were already recorded by this window */
+const recordedSaves = new Set<string>();
+
+const windowId = typeof crypto !== "undefined" && "randomUUID" in crypto ? crypto.randomUUID() : String(Date.now());
+
+function getRotatedName(index: number): string {
+  return index === 0 ? LOG_FILE : `audit.${index}.jsonl`;
+}
+
+async function getLogDirectory(): Promise<FileSystemDirectoryHandle | null> {
+  if (typeof navigator === "undefined" || !navigator.storage?.getDirectory) {
+    return null;
+  }
+
+  const root = await navigator.storage.getDirectory();
+  return root.getDirectoryHandle(LOG_DIRECTORY, { create: true });
+}
+
+async function withLogLock<T>(task: () => Promise<T>): Promise<T> {
+  if (!navigator.locks) {
+    return task();
+  }
+  return navigator.locks.request("mdx:audit-log", task);
+}
+
+async function readLogFile(directory: FileSystemDirectoryHandle, name: string): Promise<string> {
+  try {
+    return await (await (await directory.getFileHandle(name)).getFile()).text();
+  } catch (error) {
+    if (error instanceof DOMException && error.name === "NotFoundError") {
+      return "";
+    }
+    throw error;
+  }
+}
+
+async function rotateLogs(directory: FileSystemDirectoryHandle): Promise<void> {
+  await directory.removeEntry(getRotatedName(MAX_ROTATED_LOGS)).catch(() => undefined);
+
+  for (let index = MAX_ROTATED_LOGS - 1; index >= 0; index -= 1) {
+    const text = await readLogFile(directory, getRotatedName(index));
+    if (!text) {
+      continue;
+    }
+
+    const target = await directory.getFileHandle(getRotatedName(index + 1), { create: true });
+    await fsService.writeFileHandle(target, text);
+    await directory.removeEntry(getRotatedName(index));
+  }
+}
+
+/**
+ * Record an editor save, once per note per window
+ * Autosave writes every few hundred milliseconds; logging each one would
+ * rotate the delete and rename entries out of the log.
+ */
+export async function recordSave(path: string): Promise<void> {
+  const key = `${await fsService.restoreWorkspace().catch(() => null)}:${path}`;
+  if (recordedSaves.has(key)) {
+    return;
+  }
+  recordedSaves.add(key);
+  await recordAudit("write", path);
+}
+
+/**
+ * Append an operation to the audit log
+ */
+export async function recordAudit(
+  operation: AuditOperation,
+  path: string,
+  newPath: string | null = null
+): Promise<void> {
+  try {
+    const directory = await getLogDirectory();
+    if (!directory) {
+      return;
+    }
+
+    const entry: AuditEntry = {
+      timestamp: new Date().toISOString(),
+      window_id: windowId,
+      workspace: await fsService.restoreWorkspace(),
+      operation,
+      path,
+      new_path: newPath,
+    };
+
+    await withLogLock(async () => {
+      let handle = await directory.getFileHandle(LOG_FILE, { create: true });
+      let file = await handle.getFile();
+
+      if (file.size >= MAX_LOG_BYTES) {
+        await rotateLogs(directory);
+        handle = await directory.getFileHandle(LOG_FILE, { create: true });
+        file = await handle.getFile();
+      }
+
+      const writable = await handle.createWritable({ keepExistingData: true });
+      try {
+        await writable.write({ type: "write", position: file.size, data: `${JSON.stringify(entry)}\n` });
+      } finally {
+        await writable.close();
+      }
+    });
+  } catch (error) {
+    console.warn("[AuditLog] Failed to record operation:", error);
+  }
+}
+
+/**
+ * Read the most recent audit entries, newest first
+ */
+export async function getAuditLog(limit: number = DEFAULT_LOG_LIMIT): Promise<AuditEntry[]> {
+  const directory = await getLogDirectory();
+  if (!directory) {
+    return [];
+  }
+
+  const entries: AuditEntry[] = [];
+
+  for (let index = 0; index <= MAX_ROTATED_LOGS && entries.length < limit; index += 1) {
+    const lines = (await readLogFile(directory, getRotatedName(index))).split("\n").filter(Boolean).reverse();
+
+    for (const line of lines) {
+      try {
+        entries.push(JSON.parse(line) as AuditEntry);
+      } catch {
+        // Skip a line torn by a crash mid-write
+      }
+      if (entries.length >= limit) {
+        break;
+      }
+    }
+  }
+
+  return entries;
+}
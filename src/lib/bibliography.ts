@@ -0,0 +1,480 @@
+/**
+ * Bibliography - BibTeX entries for [@key] citation completion and for
+ * resolving citations into author-date text and a reference list on export
+ * The workspace's .bib file is parsed on first use and again whenever it changes.
+ */
+
+import type { Citation } from "../types";
+import { listen } from "./app-events";
+import * as fsService from "./fs-service";
+import { getProseLines } from "./markdownUtils";
+
+const BIB_PATTERN = /\.bib$/i;
+const DEFAULT_CITATION_LIMIT = 50;
+const REFERENCES_HEADING = "References";
+
+/** Bracketed pandoc-style citation, not followed by a link destination */
+const CITATION_PATTERN = /\[([^[\]]*@[^[\]]*)\](?![(:])/g;
+const CITATION_ITEM_PATTERN = /^(.*?)(-?)@([\w:.#$%&+?<>~/-]*\w)(.*)$/s;
+
+const MONTH_MACROS: Record<string, string> = {
+  jan: "January",
+  feb: "February",
+  mar: "March",
+  apr: "April",
+  may: "May",
+  jun: "June",
+  jul: "July",
+  aug: "August",
+  sep: "September",
+  oct: "October",
+  nov: "November",
+  dec: "December",
+};
+
+/** Combining marks for LaTeX accent commands (\'e -> é); letter commands need a space or brace */
+const ACCENTS: Record<string, string> = {
+  "`": "̀",
+  "'": "́",
+  "^": "̂",
+  "~": "̃",
+  "=": "̄",
+  u: "̆",
+  ".": "̇",
+  '"': "̈",
+  r: "̊",
+  H: "̋",
+  v: "̌",
+  c: "̧",
+  k: "̨",
+};
+
+interface BibAuthor {
+  name: string;
+
+  /** Family name, used in author-date citations */
+  family: string;
+}
+
+interface BibEntry {
+  key: string;
+  type: string;
+  fields: Record<string, string>;
+  authors: BibAuthor[];
+}
+
+interface Bibliography {
+  /** Workspace name and .bib path, joined */
+  key: string;
+  modified: number;
+  entries: Map<string, BibEntry>;
+}
+
+let cachedBibliography: Bibliography | null = null;
+
+/** .bib file found when settings name none, until files are added, moved or removed */
+let discovered: { workspacePath: string; path: string | null } | null = null;
+
+function addAccent(_match: string, accent: string, letter: string): string {
+  return `${letter}${ACCENTS[accent]}`;
+}
+
+/**
+ * Turn LaTeX markup in a field into plain text
+ */
+function cleanLatex(value: string): string {
+  return value
+    .replace(/\\([`'^~=."])\s*\{?\\?([A-Za-z])\}?/g, addAccent)
+    .replace(/\\([urHvck])(?:\s+|\{\\?)([A-Za-z])\}?/g, addAccent)
+    .replace(/\\([&%$#_{}])/g, "$1")
+    .replace(/\\[A-Za-z]+\s*/g, "")
+    .replace(/---/g, "—")
+    .replace(/--/g, "–")
+    .replace(/~/g, " ")
+    .replace(/[{}]/g, "")
+    .replace(/\s+/g, " ")
+    .trim()
+    .normalize("NFC");
+}
+
+/**
+ * Split a value on a separator, ignoring separators inside braces
+ */
+function splitTopLevel(value: string, separator: RegExp): string[] {
+  const parts: string[] = [];
+  let depth = 0;
+  let start = 0;
+
+  for (let index = 0; index < value.length; index += 1) {
+    const char = value[index];
+    if (char === "{") {
+      depth += 1;
+    } else if (char === "}") {
+      depth = Math.max(0, depth - 1);
+    } else if (depth === 0) {
+      const match = separator.exec(value.slice(index));
+      if (match?.index === 0) {
+        parts.push(value.slice(start, index));
+        index += match[0].length - 1;
+        start = index + 1;
+      }
+    }
+  }
+
+  parts.push(value.slice(start));
+  return parts.map((part) => part.trim()).filter(Boolean);
+}
+
+/**
+ * Parse a BibTeX name list ("Doe, Jane and John Smith and {Acme Corp}")
+ */
+function parseAuthors(raw: string): BibAuthor[] {
+  return splitTopLevel(raw, /^\s+and\s+/i).map((rawName) => {
+    const name = cleanLatex(rawName);
+    if (/^\{.*\}$/s.test(rawName)) {
+      // Braced names are institutions, kept whole
+      return { name, family: name };
+    }
+
+    const [family, given] = splitTopLevel(rawName, /^,/);
+    if (given !== undefined) {
+      return { name: `${cleanLatex(given)} ${cleanLatex(family)}`, family: cleanLatex(family) };
+    }
+
+    const words = splitTopLevel(rawName, /^\s+/);
+    return { name, family: cleanLatex(words[words.length - 1] ?? rawName) };
+  });
+}
+
+/**
+ * Parse the entries of a .bib file
+ * @string macros and # concatenation are supported; @comment and @preamble are skipped
+ */
+function parseBibtex(text: string): Map<string, BibEntry> {
+  const entries = new Map<string, BibEntry>();
+  const macros = new Map(Object.entries(MONTH_MACROS));
+  let position = 0;
+
+  // Sticky patterns match at the current position without copying the rest of the file
+  const matchAt = (pattern: RegExp): RegExpExecArray | null => {
+    pattern.lastIndex = position;
+    const match = pattern.exec(text);
+    if (match) {
+      position += match[0].length;
+    }
+    return match;
+  };
+
+  const skipSpace = () => {
+    while (position < text.length && /\s/.test(text[position])) {
+      position += 1;
+    }
+  };
+
+  // Read a {...} or "..." group, returning its contents
+  const readDelimited = (close: string): string => {
+    const start = position + 1;
+    let depth = 0;
+    position += 1;
+    while (position < text.length) {
+      const char = text[position];
+      if (char === "{") {
+        depth += 1;
+      } else if (char === "}" && depth > 0) {
+        depth -= 1;
+      } else if (char === close && depth === 0) {
+        position += 1;
+        return text.slice(start, position - 1);
+      } else if (char === "\\") {
+        position += 1;
+      }
+      position += 1;
+    }
+    return text.slice(start);
+  };
+
+  const readValue = (): string => {
+    const parts: string[] = [];
+    for (;;) {
+      skipSpace();
+      const char = text[position];
+      if (char === "{") {
+        parts.push(readDelimited("}"));
+      } else if (char === '"') {
+        parts.push(readDelimited('"'));
+      } else {
+        const word = matchAt(/[^\s,#}=)]+/y)?.[0] ?? "";
+        parts.push(/^\d+$/.test(word) ? word : (macros.get(word.toLowerCase()) ?? word));
+      }
+
+      skipSpace();
+      if (text[position] !== "#") {
+        return parts.join("");
+      }
+      position += 1;
+    }
+  };
+
+  while (position < text.length) {
+    const at = text.indexOf("@", position);
+    if (at === -1) {
+      break;
+    }
+
+    position = at + 1;
+    const typeMatch = matchAt(/([A-Za-z]+)\s*([{(])/y);
+    if (!typeMatch) {
+      continue;
+    }
+
+    const type = typeMatch[1].toLowerCase();
+    const close = typeMatch[2] === "{" ? "}" : ")";
+
+    if (type === "comment" || type === "preamble") {
+      position -= 1;
+      readDelimited(close);
+      continue;
+    }
+
+    if (type === "string") {
+      skipSpace();
+      const nameMatch = matchAt(/([^\s=]+)\s*=/y);
+      if (nameMatch) {
+        macros.set(nameMatch[1].toLowerCase(), readValue());
+      }
+      skipSpace();
+      if (text[position] === close) {
+        position += 1;
+      }
+      continue;
+    }
+
+    skipSpace();
+    const keyMatch = matchAt(/([^\s,]+)\s*,/y);
+    if (!keyMatch) {
+      continue;
+    }
+
+    const fields: Record<string, string> = {};
+    for (;;) {
+      skipSpace();
+      if (position >= text.length || text[position] === close) {
+        position += 1;
+        break;
+      }
+
+      const fieldMatch = matchAt(/([\w-]+)\s*=/y);
+      if (!fieldMatch) {
+        // Malformed field: resume at the next entry
+        break;
+      }
+      fields[fieldMatch[1].toLowerCase()] = readValue();
+
+      skipSpace();
+      if (text[position] === ",") {
+        position += 1;
+      }
+    }
+
+    const names = fields.author ?? fields.editor;
+    entries.set(keyMatch[1], {
+      key: keyMatch[1],
+      type,
+      fields: Object.fromEntries(Object.entries(fields).map(([name, value]) => [name, cleanLatex(value)])),
+      authors: names === undefined ? [] : parseAuthors(names),
+    });
+  }
+
+  return entries;
+}
+
+/**
+ * Find the .bib file to use: the one named in workspace settings, otherwise
+ * the shallowest .bib file in the workspace
+ */
+async function findBibliographyPath(workspacePath: string): Promise<string | null> {
+  const { bibliography } = await fsService.getWorkspaceSettings();
+  if (bibliography !== null) {
+    return fsService.getRelativeWorkspacePath(bibliography);
+  }
+
+  if (discovered?.workspacePath !== workspacePath) {
+    const candidates: string[] = [];
+    for await (const entry of fsService.walkDirectory(".")) {
+      if (entry.handle.kind === "file" && BIB_PATTERN.test(entry.relativePath)) {
+        candidates.push(entry.relativePath);
+      }
+    }
+    candidates.sort(
+      (left, right) => left.split("/").length - right.split("/").length || left.localeCompare(right)
+    );
+    discovered = { workspacePath, path: candidates[0] ?? null };
+  }
+
+  return discovered.path;
+}
+
+/**
+ * Load the workspace bibliography, re-parsing it only when the file changed
+ * @returns Entries by key (empty when the workspace has no .bib file)
+ */
+async function getBibliography(): Promise<Map<string, BibEntry>> {
+  const workspacePath = await fsService.restoreWorkspace();
+  const path = workspacePath ? await findBibliographyPath(workspacePath) : null;
+  if (path === null) {
+    return new Map();
+  }
+
+  let file: File;
+  try {
+    file = await fsService.getFile(path);
+  } catch (error) {
+    console.warn(`[Bibliography] Could not read ${path}:`, error);
+    return new Map();
+  }
+
+  const key = `${workspacePath}/${path}`;
+  if (cachedBibliography?.key !== key || cachedBibliography.modified !== file.lastModified) {
+    cachedBibliography = { key, modified: file.lastModified, entries: parseBibtex(await file.text()) };
+  }
+  return cachedBibliography.entries;
+}
+
+// A new, moved or deleted .bib file may change which one is found
+listen("file-changed", (change) => {
+  if (change.kind !== "modified") {
+    discovered = null;
+  }
+});
+
+function toCitation(entry: BibEntry): Citation {
+  return {
+    key: entry.key,
+    entry_type: entry.type,
+    title: entry.fields.title ?? null,
+    authors: entry.authors.map((author) => author.name),
+    year: entry.fields.year ?? entry.fields.date?.slice(0, 4) ?? null,
+  };
+}
+
+/**
+ * Lowercase and drop accents, so "godel" finds Gödel
+ */
+function foldCase(text: string): string {
+  return text.normalize("NFD").replace(/\p{M}/gu, "").toLowerCase();
+}
+
+/**
+ * Suggest entries for a [@key] citation being typed
+ * Key matches rank before author matches, and those before title matches
+ * @param prefix - Text typed after @
+ */
+export async function getCitations(prefix: string, limit: number = DEFAULT_CITATION_LIMIT): Promise<Citation[]> {
+  const entries = await getBibliography();
+  const query = foldCase(prefix.trim().replace(/^@/, ""));
+
+  const ranked: Array<{ entry: BibEntry; rank: number }> = [];
+  for (const entry of entries.values()) {
+    const key = foldCase(entry.key);
+    const families = entry.authors.map((author) => foldCase(author.family));
+    const title = foldCase(entry.fields.title ?? "");
+
+    let rank: number | null = null;
+    if (key.startsWith(query)) {
+      rank = 0;
+    } else if (key.includes(query)) {
+      rank = 1;
+    } else if (families.some((family) => family.startsWith(query))) {
+      rank = 2;
+    } else if (title.includes(query)) {
+      rank = 3;
+    }
+
+    if (rank !== null) {
+      ranked.push({ entry, rank });
+    }
+  }
+
+  ranked.sort((left, right) => left.rank - right.rank || left.entry.key.localeCompare(right.entry.key));
+  return ranked.slice(0, limit).map(({ entry }) => toCitation(entry));
+}
+
+function formatAuthorLabel(entry: BibEntry): string {
+  const families = entry.authors.map((author) => author.family);
+  if (families.length === 0) {
+    return entry.fields.title ?? entry.key;
+  }
+  if (families.length === 1) {
+    return families[0];
+  }
+  return families.length === 2 ? `${families[0]} and ${families[1]}` : `${families[0]} et al.`;
+}
+
+function formatReference(entry: BibEntry): string {
+  const { year, title } = toCitation(entry);
+  const container = entry.fields.journal ?? entry.fields.booktitle ?? entry.fields.publisher;
+  const parts = [
+    `${entry.authors.map((author) => author.name).join(", ") || entry.key} (${year ?? "n.d."}).`,
+    title ? `*${title}*.` : "",
+    container ? `${container}.` : "",
+    entry.fields.doi ? `https://doi.org/${entry.fields.doi}` : (entry.fields.url ?? ""),
+  ];
+  return `- ${parts.filter(Boolean).join(" ")}`;
+}
+
+/**
+ * Replace [@key] citations with author-date text and append a reference list
+ * Supports prefixes, locators, several keys ([see @a, p. 4; @b]) and
+ * suppressed authors ([-@a]). Citations naming an unknown key are left as
+ * written; so is everything when the workspace has no bibliography.
+ */
+export async function resolveCitations(content: string): Promise<string> {
+  if (!content.includes("@")) {
+    return content;
+  }
+
+  const entries = await getBibliography();
+  if (entries.size === 0) {
+    return content;
+  }
+
+  const cited = new Map<string, BibEntry>();
+  const formatCitation = (match: string, inner: string): string => {
+    const items = inner.split(";").map((item) => CITATION_ITEM_PATTERN.exec(item.trim()));
+    if (items.some((item) => item === null || !entries.has(item[3]))) {
+      return match;
+    }
+
+    const parts = items.map((item) => {
+      const [, prefix, suppress, key, locator] = item as RegExpExecArray;
+      const entry = entries.get(key) as BibEntry;
+      cited.set(key, entry);
+
+      const year = toCitation(entry).year ?? "n.d.";
+      const author = suppress ? "" : `${formatAuthorLabel(entry)} `;
+      const suffix = locator.trim().replace(/^,\s*/, "");
+      return `${prefix}${author}${year}${suffix ? `, ${suffix}` : ""}`;
+    });
+    return `(${parts.join("; ")})`;
+  };
+
+  const lines = content.split(/\r?\n/);
+  for (const { text, line } of getProseLines(content)) {
+    // Leave code spans alone
+    lines[line - 1] = text
+      .split(/(`[^`]*`)/)
+      .map((part, index) => (index % 2 === 1 ? part : part.replace(CITATION_PATTERN, formatCitation)))
+      .join("");
+  }
+
+  if (cited.size === 0) {
+    return content;
+  }
+
+  const references = [...cited.values()].sort(
+    (left, right) =>
+      formatAuthorLabel(left).localeCompare(formatAuthorLabel(right)) ||
+      (toCitation(left).year ?? "").localeCompare(toCitation(right).year ?? "")
+  );
+  return `${lines.join("\n").trimEnd()}\n\n## ${REFERENCES_HEADING}\n\n${references.map(formatReference).join("\n")}\n`;
+}
@@ -0,0 +1,56 @@
+/**
+ * Path copying - formats a workspace path for the clipboard, as a path or
+ * as a markdown link ready to paste into a note
+ */
+
+import type { PathCopyKind } from "../types";
+import { isNotePath } from "./filenameUtils";
+import * as fsService from "./fs-service";
+import { getNoteTitle } from "./markdownUtils";
+import { getNoteIndex } from "./note-index";
+import { formatLinkTarget, getRelativePath } from "./pathUtils";
+
+const IMAGE_PATTERN = /\.(png|jpe?g|gif|webp|svg|avif)$/i;
+
+/**
+ * Get the text a link to a path shows: a note's frontmatter title, otherwise its name
+ */
+async function getLinkText(path: string): Promise<string> {
+  if (isNotePath(path)) {
+    const note = (await getNoteIndex()).notes.get(path);
+    return note?.title ?? getNoteTitle(path);
+  }
+  return path.split("/").pop() ?? path;
+}
+
+/**
+ * Format a path the way it would be copied
+ * @param fromPath - Note the markdown link will be pasted into; links are
+ *   relative to it, or to the workspace root without one
+ */
+export async function formatPathForCopy(path: string, kind: PathCopyKind, fromPath?: string): Promise<string> {
+  const relativePath = await fsService.getRelativeWorkspacePath(path);
+  if (kind === "relative") {
+    return relativePath;
+  }
+  if (kind === "absolute") {
+    const workspacePath = await fsService.restoreWorkspace();
+    return relativePath === "." ? (workspacePath ?? ".") : `${workspacePath}/${relativePath}`;
+  }
+
+  const destination = fromPath
+    ? getRelativePath(await fsService.getRelativeWorkspacePath(fromPath), relativePath)
+    : relativePath;
+  const text = (await getLinkText(relativePath)).replace(/([[\]\\])/g, "\\$1");
+  return `${IMAGE_PATTERN.test(relativePath) ? "!" : ""}[${text}](${formatLinkTarget(destination)})`;
+}
+
+/**
+ * Copy a path to the clipboard
+ * @returns The copied text
+ */
+export async function copyPathToClipboard(path: string, kind: PathCopyKind, fromPath?: string): Promise<string> {
+  const text = await formatPathForCopy(path, kind, fromPath);
+  await navigator.clipboard.writeText(text);
+  return text;
+}
@@ -0,0 +1,190 @@
+/**
+ * Crash reports - uncaught errors and unhandled rejections are written as
+ * structured reports to the origin private file system, with the app
+ * version, the browser and the last console messages before the crash.
+ * Nothing is captured until the user opts in, and a report only leaves the
+ * device when the user submits it to the server this build was made with
+ * (VITE_CRASH_REPORT_URL).
+ */
+
+import type { CrashBreadcrumb, CrashReport } from "../types";
+import { AppError } from "./errors";
+import * as fsService from "./fs-service";
+
+const SETTINGS_KEY = "mdx-crash-reporting";
+const REPORT_DIRECTORY = "crash-reports";
+const MAX_REPORTS = 20;
+const MAX_BREADCRUMBS = 50;
+const MAX_BREADCRUMB_LENGTH = 500;
+const SUBMIT_URL = import.meta.env.VITE_CRASH_REPORT_URL;
+
+const BREADCRUMB_LEVELS = ["log", "info", "warn", "error"] as const;
+
+let enabled = typeof localStorage !== "undefined" && localStorage.getItem(SETTINGS_KEY) === "on";
+const breadcrumbs: CrashBreadcrumb[] = [];
+
+function formatValue(value: unknown): string {
+  if (value instanceof Error) {
+    return `${value.name}: ${value.message}`;
+  }
+  if (typeof value === "string") {
+    return value;
+  }
+  try {
+    return JSON.stringify(value) ?? String(value);
+  } catch {
+    return String(value);
+  }
+}
+
+function addBreadcrumb(level: CrashBreadcrumb["level"], values: unknown[]): void {
+  breadcrumbs.push({
+    timestamp: new Date().toISOString(),
+    level,
+    message: values.map(formatValue).join(" ").slice(0, MAX_BREADCRUMB_LENGTH),
+  });
+  if (breadcrumbs.length > MAX_BREADCRUMBS) {
+    breadcrumbs.shift();
+  }
+}
+
+async function getReportDirectory(): Promise<FileSystemDirectoryHandle | null> {
+  if (!navigator.storage?.getDirectory) {
+    return null;
+  }
+
+  const root = await navigator.storage.getDirectory();
+  return root.getDirectoryHandle(REPORT_DIRECTORY, { create: true });
+}
+
+async function writeReport(directory: FileSystemDirectoryHandle, report: CrashReport): Promise<void> {
+  const handle = await directory.getFileHandle(`${report.id}.json`, { create: true });
+  await fsService.writeFileHandle(handle, `${JSON.stringify(report, null, 2)}\n`);
+}
+
+async function readReports(directory: FileSystemDirectoryHandle): Promise<CrashReport[]> {
+  const reports: CrashReport[] = [];
+  for await (const [, handle] of directory.entries()) {
+    if (handle.kind !== "file" || !handle.name.endsWith(".json")) {
+      continue;
+    }
+    try {
+      reports.push(JSON.parse(await (await handle.getFile()).text()) as CrashReport);
+    } catch {
+      // Skip a report torn by a crash mid-write
+    }
+  }
+  return reports.sort((left, right) => right.timestamp.localeCompare(left.timestamp));
+}
+
+async function recordCrash(kind: CrashReport["kind"], error: unknown): Promise<void> {
+  try {
+    const directory = await getReportDirectory();
+    if (!directory) {
+      return;
+    }
+
+    const report: CrashReport = {
+      id: crypto.randomUUID(),
+      timestamp: new Date().toISOString(),
+      app_version: __APP_VERSION__,
+      user_agent: navigator.userAgent,
+      kind,
+      message: formatValue(error),
+      stack: error instanceof Error ? (error.stack ?? null) : null,
+      breadcrumbs: [...breadcrumbs],
+      submitted: false,
+    };
+    await writeReport(directory, report);
+
+    for (const stale of (await readReports(directory)).slice(MAX_REPORTS)) {
+      await directory.removeEntry(`${stale.id}.json`).catch(() => undefined);
+    }
+  } catch (writeError) {
+    // Never let reporting a crash cause another
+    console.warn("[CrashReports] Failed to record crash:", writeError);
+  }
+}
+
+export function isCrashReportingEnabled(): boolean {
+  return enabled;
+}
+
+/**
+ * Opt in to or out of crash reporting
+ * Opting out deletes the reports kept so far.
+ */
+export async function setCrashReportingEnabled(value: boolean): Promise<void> {
+  enabled = value;
+  localStorage.setItem(SETTINGS_KEY, value ? "on" : "off");
+  if (!value) {
+    breadcrumbs.length = 0;
+    const root = await navigator.storage?.getDirectory?.();
+    await root?.removeEntry(REPORT_DIRECTORY, { recursive: true }).catch(() => undefined);
+  }
+}
+
+/**
+ * Read the crash reports kept on this device, newest first
+ */
+export async function getCrashReports(): Promise<CrashReport[]> {
+  const directory = enabled ? await getReportDirectory() : null;
+  return directory ? readReports(directory) : [];
+}
+
+/**
+ * Send a crash report to the crash report server
+ */
+export async function submitCrashReport(id: string): Promise<void> {
+  if (!enabled) {
+    throw new AppError("permission-denied", "Crash reporting is turned off");
+  }
+  if (!SUBMIT_URL) {
+    throw new AppError("unsupported", "This build has no crash report server");
+  }
+
+  const directory = await getReportDirectory();
+  const report = directory ? (await readReports(directory)).find((candidate) => candidate.id === id) : undefined;
+  if (!directory || !report) {
+    throw new AppError("not-found", "The crash report no longer exists");
+  }
+
+  let response: Response;
+  try {
+    response = await fetch(SUBMIT_URL, {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify({ ...report, submitted: undefined }),
+    });
+  } catch (error) {
+    throw new AppError("io", "Couldn't reach the crash report server", { cause: error });
+  }
+  if (!response.ok) {
+    throw new AppError("io", `The crash report server answered ${response.status}`);
+  }
+
+  await writeReport(directory, { ...report, submitted: true });
+}
+
+if (typeof window !== "undefined") {
+  for (const level of BREADCRUMB_LEVELS) {
+    const original = console[level].bind(console);
+    console[level] = (...values: unknown[]) => {
+      if (enabled) {
+        addBreadcrumb(level, values);
+      }
+      original(...values);
+    };
+  }
+
+  window.addEventListener("error", (event) => {
+    if (enabled) {
+      void recordCrash("error", event.error ?? event.message);
+    }
+  });
+  window.addEventListener("unhandledrejection", (event) => {
+    if (enabled) {
+      void recordCrash("rejection", event.reason);
+    }
+  });
+}
@@ -0,0 +1,428 @@
+/**
+ * CRDT documents - notes loaded into Yjs for conflict-free concurrent editing
+ * Every window with a document open holds a replica. Edits are applied as
+ * Yjs operations, travel to the other windows as "document-update" events
+ * and are appended to IndexedDB as they happen, so a crash loses nothing.
+ * The markdown file stays the source of truth for the rest of the app and is
+ * rewritten shortly after edits settle.
+ */
+
+import * as Y from "yjs";
+import type { TextEdit } from "../types";
+import { emit, listen } from "./app-events";
+import { AppError } from "./errors";
+import * as fsService from "./fs-service";
+import { mergeThreeWay } from "./merge";
+
+const DB_NAME = "mdx-documents";
+const DB_VERSION = 1;
+const UPDATES_STORE = "updates";
+const BASES_STORE = "bases";
+const DOCUMENT_INDEX = "document";
+
+const SAVE_DELAY_MS = 1000;
+
+/** Stored updates are merged into one when a document opens with more than this */
+const COMPACT_THRESHOLD = 200;
+
+/** Transaction origins for updates that are already persisted elsewhere */
+const REMOTE_ORIGIN = "remote";
+const LOAD_ORIGIN = "load";
+
+interface OpenDocument {
+  /** Workspace name and relative path, joined */
+  key: string;
+  workspacePath: string;
+  path: string;
+  doc: Y.Doc;
+  text: Y.Text;
+  refCount: number;
+  saveTimer: ReturnType<typeof setTimeout> | null;
+
+  /** Releases the shared lock that tells other windows the document is open here */
+  releaseOpenLock: () => void;
+}
+
+interface StoredUpdate {
+  document: string;
+  update: Uint8Array;
+}
+
+const openDocuments = new Map<string, OpenDocument>();
+const openingDocuments = new Map<string, Promise<OpenDocument>>();
+
+function openDatabase(): Promise<IDBDatabase> {
+  return new Promise((resolve, reject) => {
+    const request = indexedDB.open(DB_NAME, DB_VERSION);
+
+    request.onupgradeneeded = () => {
+      const database = request.result;
+      if (!database.objectStoreNames.contains(UPDATES_STORE)) {
+        const updates = database.createObjectStore(UPDATES_STORE, { autoIncrement: true });
+        updates.createIndex(DOCUMENT_INDEX, "document");
+      }
+      if (!database.objectStoreNames.contains(BASES_STORE)) {
+        database.createObjectStore(BASES_STORE);
+      }
+    };
+
+    request.onsuccess = () => resolve(request.result);
+    request.onerror = () => reject(request.error ?? new Error("Failed to open document database"));
+  });
+}
+
+/**
+ * Run a task in one IndexedDB transaction and wait for it to commit
+ */
+async function withStores<T>(
+  mode: IDBTransactionMode,
+  task: (updates: IDBObjectStore, bases: IDBObjectStore) => Promise<T>
+): Promise<T> {
+  const database = await openDatabase();
+  try {
+    const transaction = database.transaction([UPDATES_STORE, BASES_STORE], mode);
+    const committed = new Promise<void>((resolve, reject) => {
+      transaction.oncomplete = () => resolve();
+      transaction.onerror = () => reject(transaction.error ?? new Error("Document transaction failed"));
+      transaction.onabort = () => reject(transaction.error ?? new Error("Document transaction aborted"));
+    });
+
+    const result = await task(transaction.objectStore(UPDATES_STORE), transaction.objectStore(BASES_STORE));
+    await committed;
+    return result;
+  } finally {
+    database.close();
+  }
+}
+
+function requestResult<T>(request: IDBRequest<T>): Promise<T> {
+  return new Promise((resolve, reject) => {
+    request.onsuccess = () => resolve(request.result);
+    request.onerror = () => reject(request.error ?? new Error("Document request failed"));
+  });
+}
+
+/**
+ * Load a document's stored updates, merging them into one record when
+ * there are many (atomically, so updates appended meanwhile are kept)
+ */
+function loadUpdates(key: string): Promise<{ updates: Uint8Array[]; base: string | null }> {
+  return withStores("readwrite", async (updates, bases) => {
+    const index = updates.index(DOCUMENT_INDEX);
+    const [records, recordKeys, base] = await Promise.all([
+      requestResult(index.getAll(key) as IDBRequest<StoredUpdate[]>),
+      requestResult(index.getAllKeys(key)),
+      requestResult(bases.get(key) as IDBRequest<string | undefined>),
+    ]);
+
+    const stored = records.map((record) => record.update);
+    if (stored.length > COMPACT_THRESHOLD) {
+      const merged = Y.mergeUpdates(stored);
+      for (const recordKey of recordKeys) {
+        updates.delete(recordKey);
+      }
+      updates.add({ document: key, update: merged } satisfies StoredUpdate);
+      return { updates: [merged], base: base ?? null };
+    }
+
+    return { updates: stored, base: base ?? null };
+  });
+}
+
+function appendUpdate(key: string, update: Uint8Array): Promise<void> {
+  return withStores("readwrite", async (updates) => {
+    updates.add({ document: key, update } satisfies StoredUpdate);
+  });
+}
+
+function saveBase(key: string, text: string): Promise<void> {
+  return withStores("readwrite", async (_updates, bases) => {
+    bases.put(text, key);
+  });
+}
+
+/**
+ * Forget a document's stored state once everything in it is on disk
+ */
+function clearStoredDocument(key: string): Promise<void> {
+  return withStores("readwrite", async (updates, bases) => {
+    const recordKeys = await requestResult(updates.index(DOCUMENT_INDEX).getAllKeys(key));
+    for (const recordKey of recordKeys) {
+      updates.delete(recordKey);
+    }
+    bases.delete(key);
+  });
+}
+
+/**
+ * Hold a shared lock for as long as the document is open in this window
+ * @returns Function that releases it
+ */
+function holdOpenLock(key: string): () => void {
+  if (typeof navigator === "undefined" || !navigator.locks) {
+    return () => {};
+  }
+  let release = () => {};
+  const released = new Promise<void>((resolve) => {
+    release = resolve;
+  });
+  void navigator.locks.request(`mdx:document-open:${key}`, { mode: "shared" }, () => released);
+  return release;
+}
+
+function withDocumentLock<T>(key: string, task: () => Promise<T>): Promise<T> {
+  if (typeof navigator === "undefined" || !navigator.locks) {
+    return task();
+  }
+  return navigator.locks.request(`mdx:document:${key}`, task);
+}
+
+/**
+ * Turn the document's text into the target with one replacement of the
+ * span between their common prefix and suffix
+ */
+function replaceText(text: Y.Text, target: string): void {
+  const current = text.toString();
+  if (current === target) {
+    return;
+  }
+
+  let prefix = 0;
+  while (prefix < current.length && prefix < target.length && current[prefix] === target[prefix]) {
+    prefix += 1;
+  }
+  let suffix = 0;
+  while (
+    suffix < current.length - prefix &&
+    suffix < target.length - prefix &&
+    current[current.length - 1 - suffix] === target[target.length - 1 - suffix]
+  ) {
+    suffix += 1;
+  }
+
+  text.doc?.transact(() => {
+    text.delete(prefix, current.length - prefix - suffix);
+    text.insert(prefix, target.slice(prefix, target.length - suffix));
+  });
+}
+
+async function saveDocument(document: OpenDocument): Promise<void> {
+  document.saveTimer = null;
+  const content = document.text.toString();
+
+  await withDocumentLock(document.key, async () => {
+    await fsService.writeFile(document.path, content);
+    await saveBase(document.key, content);
+  });
+  emit("file-changed", {
+    workspace_path: document.workspacePath,
+    kind: "modified",
+    entry_kind: "file",
+    path: document.path,
+    new_path: null,
+  });
+}
+
+function scheduleSave(document: OpenDocument): void {
+  if (document.saveTimer !== null) {
+    clearTimeout(document.saveTimer);
+  }
+  document.saveTimer = setTimeout(() => {
+    saveDocument(document).catch((error) => {
+      console.error(`[CrdtDocument] Failed to save ${document.path}:`, error);
+    });
+  }, SAVE_DELAY_MS);
+}
+
+async function getDocumentKey(path: string): Promise<{ key: string; workspacePath: string; path: string }> {
+  const workspacePath = await fsService.restoreWorkspace();
+  if (!workspacePath) {
+    throw new AppError("no-workspace", "No workspace selected");
+  }
+  const relativePath = await fsService.getRelativeWorkspacePath(path);
+  return { key: `${workspacePath}/${relativePath}`, workspacePath, path: relativePath };
+}
+
+/**
+ * Load a document from its stored updates and the file, reconciling the two:
+ * whichever side changed since the last save wins, and when both did the
+ * changes are merged line by line
+ */
+async function loadDocument(key: string, workspacePath: string, path: string): Promise<OpenDocument> {
+  const doc = new Y.Doc();
+  const text = doc.getText("content");
+  const document: OpenDocument = {
+    key,
+    workspacePath,
+    path,
+    doc,
+    text,
+    refCount: 0,
+    saveTimer: null,
+    releaseOpenLock: holdOpenLock(key),
+  };
+
+  await withDocumentLock(key, async () => {
+    const fileContent = await fsService.readFile(path);
+    const { updates, base } = await loadUpdates(key);
+    for (const update of updates) {
+      Y.applyUpdate(doc, update, LOAD_ORIGIN);
+    }
+
+    // Later local changes persist and broadcast themselves
+    doc.on("update", (update: Uint8Array, origin: unknown) => {
+      if (origin === REMOTE_ORIGIN || origin === LOAD_ORIGIN) {
+        return;
+      }
+      appendUpdate(key, update).catch((error) => {
+        console.error(`[CrdtDocument] Failed to store update for ${path}:`, error);
+      });
+      emit("document-update", { workspace_path: workspacePath, path, update });
+      scheduleSave(document);
+    });
+
+    const stored = text.toString();
+    if (base === null || stored === base) {
+      // Nothing unsaved: the file is current
+      replaceText(text, fileContent);
+    } else if (fileContent !== base) {
+      const merged = mergeThreeWay(base, stored, fileContent, { ours: "unsaved edits", theirs: "file on disk" });
+      if (merged.conflicts.length > 0) {
+        console.warn(`[CrdtDocument] ${merged.conflicts.length} conflicts merging ${path} with the file on disk`);
+      }
+      replaceText(text, merged.content);
+    } else if (stored !== fileContent) {
+      // Unsaved edits from a crashed session: write them out
+      scheduleSave(document);
+    }
+
+    await saveBase(key, fileContent);
+  });
+
+  return document;
+}
+
+/**
+ * Open a note as a CRDT document (shared with any other open handle)
+ * @returns Current text of the document
+ */
+export async function openDocument(path: string): Promise<string> {
+  const { key, workspacePath, path: relativePath } = await getDocumentKey(path);
+
+  let opening = openingDocuments.get(key);
+  if (!opening) {
+    const existing = openDocuments.get(key);
+    opening = existing ? Promise.resolve(existing) : loadDocument(key, workspacePath, relativePath);
+    openingDocuments.set(key, opening);
+  }
+
+  try {
+    const document = await opening;
+    openDocuments.set(key, document);
+    document.refCount += 1;
+    return document.text.toString();
+  } finally {
+    openingDocuments.delete(key);
+  }
+}
+
+async function getOpenDocument(path: string): Promise<OpenDocument> {
+  const { key } = await getDocumentKey(path);
+  const document = openDocuments.get(key);
+  if (!document) {
+    throw new AppError("invalid-argument", "Document is not open", { path });
+  }
+  return document;
+}
+
+/**
+ * Get the current text of an open document
+ */
+export async function getDocumentText(path: string): Promise<string> {
+  return (await getOpenDocument(path)).text.toString();
+}
+
+/**
+ * Apply ranged edits to an open document as CRDT operations
+ * Edits address the text as it is before any of them apply and must not overlap
+ * @returns Text after the edits
+ */
+export async function applyDocumentEdits(path: string, edits: TextEdit[]): Promise<string> {
+  const document = await getOpenDocument(path);
+  const length = document.text.length;
+  const sorted = [...edits].sort((left, right) => left.start - right.start || left.end - right.end);
+
+  let previousEnd = 0;
+  for (const edit of sorted) {
+    if (edit.start < previousEnd || edit.end < edit.start || edit.end > length) {
+      throw new AppError("invalid-argument", `Invalid or overlapping edit range ${edit.start}-${edit.end}`, {
+        path: document.path,
+      });
+    }
+    previousEnd = edit.end;
+  }
+
+  // Back to front, so earlier offsets stay valid
+  document.doc.transact(() => {
+    for (const edit of sorted.reverse()) {
+      document.text.delete(edit.start, edit.end - edit.start);
+      document.text.insert(edit.start, edit.replacement);
+    }
+  });
+
+  return document.text.toString();
+}
+
+/**
+ * Call a handler with the full text whenever an open document changes,
+ * whether the edit came from this window or another
+ * @returns Function that removes the handler
+ */
+export async function observeDocument(path: string, handler: (text: string) => void): Promise<() => void> {
+  const document = await getOpenDocument(path);
+  const observer = () => handler(document.text.toString());
+  document.text.observe(observer);
+  return () => document.text.unobserve(observer);
+}
+
+/**
+ * Release a handle from openDocument; the last one saves and unloads the document
+ */
+export async function closeDocument(path: string): Promise<void> {
+  const document = await getOpenDocument(path);
+  document.refCount -= 1;
+  if (document.refCount > 0) {
+    return;
+  }
+
+  openDocuments.delete(document.key);
+  if (document.saveTimer !== null) {
+    clearTimeout(document.saveTimer);
+    await saveDocument(document);
+  }
+  document.doc.destroy();
+  document.releaseOpenLock();
+
+  // Another window may still be editing; its replica keeps the stored state
+  if (!(await isOpenElsewhere(document.key))) {
+    await clearStoredDocument(document.key);
+  }
+}
+
+async function isOpenElsewhere(key: string): Promise<boolean> {
+  if (typeof navigator === "undefined" || !navigator.locks) {
+    return false;
+  }
+  const { held = [] } = await navigator.locks.query();
+  return held.some((lock) => lock.name === `mdx:document-open:${key}`);
+}
+
+listen("document-update", (event, { remote }) => {
+  if (!remote) {
+    return;
+  }
+  const document = openDocuments.get(`${event.workspace_path}/${event.path}`);
+  if (document) {
+    Y.applyUpdate(document.doc, event.update, REMOTE_ORIGIN);
+  }
+});
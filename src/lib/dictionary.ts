@@ -0,0 +1,112 @@
+/**
+ * Custom dictionary - words the spellchecker should accept
+ * The workspace list lives in .mdx/dictionary.txt (one word per line) so it
+ * syncs with the folder; the app-level list is kept in localStorage and
+ * applies to every workspace.
+ */
+
+import type { DictionaryScope } from "../types";
+import { AppError } from "./errors";
+import * as fsService from "./fs-service";
+
+const DICTIONARY_PATH = ".mdx/dictionary.txt";
+const APP_DICTIONARY_KEY = "mdx-dictionary";
+
+function sortWords(words: Iterable<string>): string[] {
+  return [...new Set(words)].sort((a, b) => a.localeCompare(b));
+}
+
+function parseWords(text: string): string[] {
+  return text
+    .split(/\r?\n/)
+    .map((line) => line.trim())
+    .filter((line) => line && !line.startsWith("#"));
+}
+
+function normalizeWord(word: string): string {
+  const normalized = word.trim();
+  if (!normalized || /\s/.test(normalized)) {
+    throw new AppError("invalid-argument", `"${word}" is not a single word`);
+  }
+  return normalized;
+}
+
+async function withDictionaryLock<T>(task: () => Promise<T>): Promise<T> {
+  if (!navigator.locks) {
+    return task();
+  }
+  return navigator.locks.request("mdx:dictionary", task);
+}
+
+async function readWorkspaceWords(): Promise<string[]> {
+  try {
+    return parseWords(await fsService.readFile(DICTIONARY_PATH));
+  } catch (error) {
+    if (error instanceof DOMException && error.name === "NotFoundError") {
+      return [];
+    }
+    throw error;
+  }
+}
+
+function readAppWords(): string[] {
+  return parseWords(localStorage.getItem(APP_DICTIONARY_KEY) ?? "");
+}
+
+async function writeWords(scope: DictionaryScope, words: string[]): Promise<void> {
+  const text = words.length > 0 ? `${sortWords(words).join("\n")}\n` : "";
+  if (scope === "app") {
+    localStorage.setItem(APP_DICTIONARY_KEY, text);
+    return;
+  }
+  await fsService.writeFile(DICTIONARY_PATH, text);
+}
+
+/**
+ * Workspace and app-level words merged, sorted and without duplicates
+ * Only the app-level list is returned while no workspace is open
+ */
+export async function getDictionary(): Promise<string[]> {
+  const workspaceWords = (await fsService.restoreWorkspace()) ? await readWorkspaceWords() : [];
+  return sortWords([...workspaceWords, ...readAppWords()]);
+}
+
+/**
+ * Add a word to one of the lists
+ * @returns false if the list already had the word
+ */
+export async function addToDictionary(word: string, scope: DictionaryScope = "workspace"): Promise<boolean> {
+  const normalized = normalizeWord(word);
+
+  return withDictionaryLock(async () => {
+    const words = scope === "app" ? readAppWords() : await readWorkspaceWords();
+    if (words.includes(normalized)) {
+      return false;
+    }
+
+    await writeWords(scope, [...words, normalized]);
+    return true;
+  });
+}
+
+/**
+ * Remove a word from both lists, so it is flagged again wherever it was added
+ * @returns false if neither list had the word
+ */
+export async function removeFromDictionary(word: string): Promise<boolean> {
+  const normalized = word.trim();
+
+  return withDictionaryLock(async () => {
+    let removed = false;
+
+    for (const scope of ["workspace", "app"] as const) {
+      const words = scope === "app" ? readAppWords() : await readWorkspaceWords();
+      if (words.includes(normalized)) {
+        await writeWords(scope, words.filter((entry) => entry !== normalized));
+        removed = true;
+      }
+    }
+
+    return removed;
+  });
+}
@@ -0,0 +1,319 @@
+/**
+ * Line diff - Myers' algorithm grouped into unified-diff style hunks
+ * Word diffs run the same algorithm again over the words of each modified line
+ */
+
+import type { DiffBase, DiffHunk, DiffLine, DiffSegment } from "../types";
+import * as fsService from "./fs-service";
+
+const CONTEXT_LINES = 3;
+
+/** Beyond this many edits the remaining lines are reported as replaced */
+const MAX_EDIT_DISTANCE = 4000;
+
+/** Words, runs of whitespace and single punctuation marks */
+const WORD_PATTERN = /\s+|[\p{L}\p{N}_]+|[^\s\p{L}\p{N}_]/gu;
+
+export function splitLines(content: string): string[] {
+  return content === "" ? [] : content.replace(/\r\n/g, "\n").split("\n");
+}
+
+/**
+ * Shortest edit script between two line arrays
+ * Returns one entry per line in order: context, removed or added
+ */
+function diffLines(oldLines: string[], newLines: string[]): DiffLine[] {
+  // Common prefix and suffix never need the full search
+  let prefix = 0;
+  while (prefix < oldLines.length && prefix < newLines.length && oldLines[prefix] === newLines[prefix]) {
+    prefix += 1;
+  }
+  let suffix = 0;
+  while (
+    suffix < oldLines.length - prefix &&
+    suffix < newLines.length - prefix &&
+    oldLines[oldLines.length - 1 - suffix] === newLines[newLines.length - 1 - suffix]
+  ) {
+    suffix += 1;
+  }
+
+  const a = oldLines.slice(prefix, oldLines.length - suffix);
+  const b = newLines.slice(prefix, newLines.length - suffix);
+  const middle = myers(a, b);
+
+  const result: DiffLine[] = [];
+  const context = (oldIndex: number, newIndex: number) =>
+    result.push({ kind: "context", text: oldLines[oldIndex], old_line: oldIndex + 1, new_line: newIndex + 1 });
+
+  for (let index = 0; index < prefix; index += 1) {
+    context(index, index);
+  }
+  for (const edit of middle) {
+    if (edit.kind === "context") {
+      context(prefix + edit.oldIndex, prefix + edit.newIndex);
+    } else if (edit.kind === "removed") {
+      result.push({ kind: "removed", text: a[edit.oldIndex], old_line: prefix + edit.oldIndex + 1, new_line: null });
+    } else {
+      result.push({ kind: "added", text: b[edit.newIndex], old_line: null, new_line: prefix + edit.newIndex + 1 });
+    }
+  }
+  for (let index = 0; index < suffix; index += 1) {
+    context(oldLines.length - suffix + index, newLines.length - suffix + index);
+  }
+
+  return result;
+}
+
+/**
+ * Line correspondence of the shortest edit script
+ * @returns For each old line, the index of the identical new line it maps to, or -1
+ */
+export function matchLines(oldLines: string[], newLines: string[]): Int32Array {
+  const matches = new Int32Array(oldLines.length).fill(-1);
+  for (const line of diffLines(oldLines, newLines)) {
+    if (line.kind === "context" && line.old_line !== null && line.new_line !== null) {
+      matches[line.old_line - 1] = line.new_line - 1;
+    }
+  }
+  return matches;
+}
+
+interface Edit {
+  kind: DiffLine["kind"];
+  oldIndex: number;
+  newIndex: number;
+}
+
+function myers(a: string[], b: string[]): Edit[] {
+  const n = a.length;
+  const m = b.length;
+  const max = Math.min(n + m, MAX_EDIT_DISTANCE);
+  const offset = max + 1;
+  const v = new Int32Array(2 * max + 3);
+
+  // trace[d] holds v for diagonals -d-1..d+1 before round d
+  const trace: Int32Array[] = [];
+  let found = false;
+
+  for (let d = 0; d <= max && !found; d += 1) {
+    trace.push(v.slice(offset - d - 1, offset + d + 2));
+
+    for (let k = -d; k <= d; k += 2) {
+      const fromAbove = k === -d || (k !== d && v[offset + k - 1] < v[offset + k + 1]);
+      let x = fromAbove ? v[offset + k + 1] : v[offset + k - 1] + 1;
+      let y = x - k;
+      while (x < n && y < m && a[x] === b[y]) {
+        x += 1;
+        y += 1;
+      }
+      v[offset + k] = x;
+
+      if (x >= n && y >= m) {
+        found = true;
+        break;
+      }
+    }
+  }
+
+  if (!found) {
+    // Too different to diff cheaply: replace everything
+    return [
+      ...a.map((_, index): Edit => ({ kind: "removed", oldIndex: index, newIndex: -1 })),
+      ...b.map((_, index): Edit => ({ kind: "added", oldIndex: -1, newIndex: index })),
+    ];
+  }
+
+  const edits: Edit[] = [];
+  let x = n;
+  let y = m;
+
+  for (let d = trace.length - 1; d >= 0; d -= 1) {
+    const round = trace[d];
+    const at = (k: number) => round[k + d + 1];
+    const k = x - y;
+    const previousK = k === -d || (k !== d && at(k - 1) < at(k + 1)) ? k + 1 : k - 1;
+    const previousX = at(previousK);
+    const previousY = previousX - previousK;
+
+    while (x > previousX && y > previousY) {
+      x -= 1;
+      y -= 1;
+      edits.push({ kind: "context", oldIndex: x, newIndex: y });
+    }
+
+    if (d > 0) {
+      if (x === previousX) {
+        edits.push({ kind: "added", oldIndex: -1, newIndex: previousY });
+      } else {
+        edits.push({ kind: "removed", oldIndex: previousX, newIndex: -1 });
+      }
+    }
+
+    x = previousX;
+    y = previousY;
+  }
+
+  return edits.reverse();
+}
+
+function appendSegment(segments: DiffSegment[], kind: DiffSegment["kind"], text: string): void {
+  const last = segments[segments.length - 1];
+  if (last && last.kind === kind) {
+    last.text += text;
+  } else {
+    segments.push({ kind, text });
+  }
+}
+
+/**
+ * Word-level diff of a modified line
+ * @returns Segments of the old line (context and removed) and of the new line (context and added)
+ */
+function diffWords(oldText: string, newText: string): { removed: DiffSegment[]; added: DiffSegment[] } {
+  const a = oldText.match(WORD_PATTERN) ?? [];
+  const b = newText.match(WORD_PATTERN) ?? [];
+  const removed: DiffSegment[] = [];
+  const added: DiffSegment[] = [];
+
+  for (const edit of myers(a, b)) {
+    if (edit.kind === "context") {
+      appendSegment(removed, "context", a[edit.oldIndex]);
+      appendSegment(added, "context", b[edit.newIndex]);
+    } else if (edit.kind === "removed") {
+      appendSegment(removed, "removed", a[edit.oldIndex]);
+    } else {
+      appendSegment(added, "added", b[edit.newIndex]);
+    }
+  }
+
+  return { removed, added };
+}
+
+/**
+ * Attach word diffs to modified lines
+ * Within each change, removed lines are paired in order with the added lines
+ * that replace them; unpaired lines are wholly removed or added.
+ */
+function addWordDiffs(lines: DiffLine[]): DiffLine[] {
+  let index = 0;
+
+  while (index < lines.length) {
+    if (lines[index].kind !== "removed") {
+      index += 1;
+      continue;
+    }
+
+    const removedStart = index;
+    while (index < lines.length && lines[index].kind === "removed") {
+      index += 1;
+    }
+    const addedStart = index;
+    while (index < lines.length && lines[index].kind === "added") {
+      index += 1;
+    }
+
+    const pairs = Math.min(addedStart - removedStart, index - addedStart);
+    for (let pair = 0; pair < pairs; pair += 1) {
+      const oldLine = lines[removedStart + pair];
+      const newLine = lines[addedStart + pair];
+      const { removed, added } = diffWords(oldLine.text, newLine.text);
+      oldLine.words = removed;
+      newLine.words = added;
+    }
+  }
+
+  return lines;
+}
+
+/**
+ * Group a line diff into hunks with CONTEXT_LINES of context on each side
+ */
+function toHunks(lines: DiffLine[]): DiffHunk[] {
+  const hunks: DiffHunk[] = [];
+  let start = -1;
+  let end = -1;
+
+  const flush = () => {
+    const hunkLines = lines.slice(start, end + 1);
+    const oldBefore = lines.slice(0, start).filter((line) => line.kind !== "added").length;
+    const newBefore = lines.slice(0, start).filter((line) => line.kind !== "removed").length;
+    const oldCount = hunkLines.filter((line) => line.kind !== "added").length;
+    const newCount = hunkLines.filter((line) => line.kind !== "removed").length;
+
+    // As in unified diffs, an empty side starts at the line before the hunk
+    hunks.push({
+      old_start: oldCount === 0 ? oldBefore : oldBefore + 1,
+      old_lines: oldCount,
+      new_start: newCount === 0 ? newBefore : newBefore + 1,
+      new_lines: newCount,
+      lines: hunkLines,
+    });
+  };
+
+  lines.forEach((line, index) => {
+    if (line.kind === "context") {
+      return;
+    }
+
+    const hunkStart = Math.max(0, index - CONTEXT_LINES);
+    if (start !== -1 && hunkStart > end + 1) {
+      flush();
+      start = -1;
+    }
+    if (start === -1) {
+      start = hunkStart;
+    }
+    end = Math.min(lines.length - 1, index + CONTEXT_LINES);
+  });
+
+  if (start !== -1) {
+    flush();
+  }
+
+  return hunks;
+}
+
+/**
+ * Diff two texts line by line
+ */
+export function diffText(oldContent: string, newContent: string): DiffHunk[] {
+  return toHunks(diffLines(splitLines(oldContent), splitLines(newContent)));
+}
+
+/**
+ * Diff two texts line by line, with word-level changes for modified lines
+ */
+export function diffTextByWord(oldContent: string, newContent: string): DiffHunk[] {
+  return toHunks(addWordDiffs(diffLines(splitLines(oldContent), splitLines(newContent))));
+}
+
+/**
+ * Word-level diff between two notes, e.g. two versions of a spec
+ * @param pathA - Note shown as the old side
+ * @param pathB - Note shown as the new side
+ */
+export async function diffFiles(pathA: string, pathB: string): Promise<DiffHunk[]> {
+  const [oldContent, newContent] = await Promise.all([fsService.readFile(pathA), fsService.readFile(pathB)]);
+  return diffTextByWord(oldContent, newContent);
+}
+
+/**
+ * Diff unsaved content against the note on disk
+ * A note that does not exist yet diffs as empty
+ */
+export async function diffContent(path: string, newContent: string, base: DiffBase = "file"): Promise<DiffHunk[]> {
+  if (base === "head") {
+    throw new Error("Comparing against git HEAD is not available in the web build");
+  }
+
+  let oldContent = "";
+  try {
+    oldContent = await fsService.readFile(path);
+  } catch (error) {
+    if (!(error instanceof DOMException && error.name === "NotFoundError")) {
+      throw error;
+    }
+  }
+
+  return diffText(oldContent, newContent);
+}
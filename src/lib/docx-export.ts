@@ -0,0 +1,474 @@
+/**
+ * DOCX export - converts a note into a Word document
+ * The note is parsed with marked and written as WordprocessingML: headings
+ * map to Word's heading styles (so the navigation pane and TOC work), tables
+ * become real tables and workspace images are embedded. Headings carry
+ * bookmarks named after their anchors, so #heading links jump within the
+ * document. Lists are written as indented paragraphs with their markers
+ * rather than Word numbering.
+ */
+
+import { Lexer } from "marked";
+import type { Token, Tokens } from "marked";
+import { resolveCitations } from "./bibliography";
+import * as fsService from "./fs-service";
+import { createSlugger, getNoteTitle, splitFrontmatter, stripInlineMarkdown } from "./markdownUtils";
+import { createZipWriter } from "./zip";
+
+const EXTERNAL_LINK_PATTERN = /^(https?:|mailto:)/i;
+const REMOTE_SOURCE_PATTERN = /^(https?:\/\/|data:|blob:|\/\/)/i;
+
+/** Images wider than the page text column (6 inches) are scaled down */
+const MAX_IMAGE_WIDTH_EMU = 6 * 914400;
+const EMU_PER_PIXEL = 9525;
+
+/** Word reads these directly; anything else is converted to PNG */
+const NATIVE_IMAGE_TYPES: Record<string, string> = {
+  "image/png": "png",
+  "image/jpeg": "jpeg",
+  "image/gif": "gif",
+};
+
+const NAMESPACES =
+  'xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" ' +
+  'xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" ' +
+  'xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" ' +
+  'xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" ' +
+  'xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture"';
+
+const RELATIONSHIP_TYPES = {
+  document: "http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument",
+  styles: "http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles",
+  hyperlink: "http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink",
+  image: "http://schemas.openxmlformats.org/officeDocument/2006/relationships/image",
+};
+
+const XML_HEADER = '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>';
+
+const STYLES_XML = `${XML_HEADER}
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:eastAsia="Calibri" w:cs="Calibri"/><w:sz w:val="22"/></w:rPr></w:rPrDefault><w:pPrDefault><w:pPr><w:spacing w:after="160" w:line="276" w:lineRule="auto"/></w:pPr></w:pPrDefault></w:docDefaults>
+<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/><w:qFormat/></w:style>
+${[40, 32, 28, 24, 22, 22]
+  .map(
+    (size, index) =>
+      `<w:style w:type="paragraph" w:styleId="Heading${index + 1}"><w:name w:val="heading ${index + 1}"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="80"/><w:outlineLvl w:val="${index}"/></w:pPr><w:rPr><w:b/><w:sz w:val="${size}"/></w:rPr></w:style>`
+  )
+  .join("\n")}
+<w:style w:type="paragraph" w:styleId="Quote"><w:name w:val="Quote"/><w:basedOn w:val="Normal"/><w:qFormat/><w:pPr><w:pBdr><w:left w:val="single" w:sz="18" w:space="8" w:color="CCCCCC"/></w:pBdr><w:ind w:left="720"/></w:pPr><w:rPr><w:color w:val="595959"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="CodeBlock"><w:name w:val="Code Block"/><w:basedOn w:val="Normal"/><w:pPr><w:shd w:val="clear" w:color="auto" w:fill="F6F8FA"/><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr><w:rPr><w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:cs="Consolas"/><w:sz w:val="20"/></w:rPr></w:style>
+<w:style w:type="character" w:styleId="InlineCode"><w:name w:val="Inline Code"/><w:rPr><w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:cs="Consolas"/><w:shd w:val="clear" w:color="auto" w:fill="F6F8FA"/></w:rPr></w:style>
+<w:style w:type="character" w:styleId="Hyperlink"><w:name w:val="Hyperlink"/><w:rPr><w:color w:val="0563C1"/><w:u w:val="single"/></w:rPr></w:style>
+<w:style w:type="table" w:styleId="TableGrid"><w:name w:val="Table Grid"/><w:tblPr><w:tblBorders>${["top", "left", "bottom", "right", "insideH", "insideV"]
+  .map((side) => `<w:${side} w:val="single" w:sz="4" w:space="0" w:color="BFBFBF"/>`)
+  .join("")}</w:tblBorders><w:tblCellMar><w:left w:w="108" w:type="dxa"/><w:right w:w="108" w:type="dxa"/></w:tblCellMar></w:tblPr></w:style>
+</w:styles>`;
+
+interface RunFormat {
+  bold?: boolean;
+  italic?: boolean;
+  strike?: boolean;
+  code?: boolean;
+  link?: boolean;
+}
+
+interface EmbeddedImage {
+  relationshipId: string;
+  name: string;
+  data: Uint8Array;
+}
+
+interface ParagraphOptions {
+  style?: string;
+  indent?: number;
+}
+
+function escapeXml(text: string): string {
+  return text
+    .replace(/&/g, "&amp;")
+    .replace(/</g, "&lt;")
+    .replace(/>/g, "&gt;")
+    .replace(/"/g, "&quot;");
+}
+
+function textRun(text: string, format: RunFormat): string {
+  const properties = [
+    format.link ? '<w:rStyle w:val="Hyperlink"/>' : format.code ? '<w:rStyle w:val="InlineCode"/>' : "",
+    format.bold ? "<w:b/>" : "",
+    format.italic ? "<w:i/>" : "",
+    format.strike ? "<w:strike/>" : "",
+  ].join("");
+
+  return `<w:r>${properties ? `<w:rPr>${properties}</w:rPr>` : ""}<w:t xml:space="preserve">${escapeXml(text)}</w:t></w:r>`;
+}
+
+/**
+ * Word bookmark name for a heading anchor
+ * Names allow letters, digits and underscores up to 40 characters; the
+ * leading underscore keeps them out of Word's bookmark list
+ */
+function bookmarkName(slug: string): string {
+  return `_${slug.replace(/[^\p{L}\p{N}_]/gu, "_")}`.slice(0, 40);
+}
+
+function decodeAnchor(href: string): string {
+  try {
+    return decodeURIComponent(href.slice(1));
+  } catch {
+    return href.slice(1);
+  }
+}
+
+function paragraph(content: string, options: ParagraphOptions = {}): string {
+  const properties = [
+    options.style ? `<w:pStyle w:val="${options.style}"/>` : "",
+    options.indent ? `<w:ind w:left="${options.indent * 360}" w:hanging="360"/>` : "",
+  ].join("");
+
+  return `<w:p>${properties ? `<w:pPr>${properties}</w:pPr>` : ""}${content}</w:p>`;
+}
+
+async function loadImage(file: File): Promise<{ data: Uint8Array; extension: string; width: number; height: number }> {
+  const bitmap = await createImageBitmap(file);
+
+  try {
+    const { width, height } = bitmap;
+    const nativeExtension = NATIVE_IMAGE_TYPES[file.type];
+    if (nativeExtension) {
+      return { data: new Uint8Array(await file.arrayBuffer()), extension: nativeExtension, width, height };
+    }
+
+    const canvas = new OffscreenCanvas(width, height);
+    const context = canvas.getContext("2d");
+    if (!context) {
+      throw new Error("Canvas is not available");
+    }
+    context.drawImage(bitmap, 0, 0);
+    const png = await canvas.convertToBlob({ type: "image/png" });
+    return { data: new Uint8Array(await png.arrayBuffer()), extension: "png", width, height };
+  } finally {
+    bitmap.close();
+  }
+}
+
+/**
+ * Builds document.xml and collects the relationships and media it needs
+ */
+function createDocumentBuilder(notePath: string) {
+  const relationships = [`<Relationship Id="rId1" Type="${RELATIONSHIP_TYPES.styles}" Target="styles.xml"/>`];
+  const images: EmbeddedImage[] = [];
+  const warnings: string[] = [];
+  const slugify = createSlugger();
+  let bookmarkCount = 0;
+
+  function addRelationship(type: string, target: string, external = false): string {
+    const id = `rId${relationships.length + 1}`;
+    relationships.push(
+      `<Relationship Id="${id}" Type="${type}" Target="${escapeXml(target)}"${external ? ' TargetMode="External"' : ""}/>`
+    );
+    return id;
+  }
+
+  function getRelationshipsXml(): string {
+    return `${XML_HEADER}\n<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">${relationships.join("")}</Relationships>`;
+  }
+
+  async function renderImage(token: Tokens.Image): Promise<string> {
+    const alt = token.text || token.href;
+    if (REMOTE_SOURCE_PATTERN.test(token.href)) {
+      warnings.push(`Remote image left as a link: ${token.href}`);
+      return textRun(`[${alt}]`, { italic: true });
+    }
+
+    try {
+      const file = await fsService.readLinkedFile(decodeURI(token.href), notePath);
+      const { data, extension, width, height } = await loadImage(file);
+
+      const index = images.length + 1;
+      const name = `image${index}.${extension}`;
+      const relationshipId = addRelationship(RELATIONSHIP_TYPES.image, `media/${name}`);
+      images.push({ relationshipId, name, data });
+
+      const scale = Math.min(1, MAX_IMAGE_WIDTH_EMU / (width * EMU_PER_PIXEL));
+      const cx = Math.round(width * EMU_PER_PIXEL * scale);
+      const cy = Math.round(height * EMU_PER_PIXEL * scale);
+      const description = escapeXml(token.text);
+
+      return (
+        `<w:r><w:drawing><wp:inline distT="0" distB="0" distL="0" distR="0"><wp:extent cx="${cx}" cy="${cy}"/>` +
+        `<wp:docPr id="${index}" name="Picture ${index}" descr="${description}"/>` +
+        '<a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture"><pic:pic>' +
+        `<pic:nvPicPr><pic:cNvPr id="${index}" name="${name}"/><pic:cNvPicPr/></pic:nvPicPr>` +
+        `<pic:blipFill><a:blip r:embed="${relationshipId}"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>` +
+        `<pic:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="${cx}" cy="${cy}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></pic:spPr>` +
+        "</pic:pic></a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"
+      );
+    } catch (error) {
+      warnings.push(`Could not embed ${token.href}: ${error instanceof Error ? error.message : String(error)}`);
+      return textRun(`[${alt}]`, { italic: true });
+    }
+  }
+
+  async function inline(tokens: Token[] | undefined, format: RunFormat = {}): Promise<string> {
+    let xml = "";
+
+    for (const token of tokens ?? []) {
+      switch (token.type) {
+        case "strong":
+          xml += await inline((token as Tokens.Strong).tokens, { ...format, bold: true });
+          break;
+        case "em":
+          xml += await inline((token as Tokens.Em).tokens, { ...format, italic: true });
+          break;
+        case "del":
+          xml += await inline((token as Tokens.Del).tokens, { ...format, strike: true });
+          break;
+        case "codespan":
+          xml += textRun((token as Tokens.Codespan).text, { ...format, code: true });
+          break;
+        case "br":
+          xml += "<w:r><w:br/></w:r>";
+          break;
+        case "image":
+          xml += await renderImage(token as Tokens.Image);
+          break;
+        case "link": {
+          const link = token as Tokens.Link;
+          const runs = await inline(link.tokens, { ...format, link: true });
+          if (EXTERNAL_LINK_PATTERN.test(link.href)) {
+            xml += `<w:hyperlink r:id="${addRelationship(RELATIONSHIP_TYPES.hyperlink, link.href, true)}">${runs}</w:hyperlink>`;
+          } else if (link.href.length > 1 && link.href.startsWith("#")) {
+            xml += `<w:hyperlink w:anchor="${escapeXml(bookmarkName(decodeAnchor(link.href)))}">${runs}</w:hyperlink>`;
+          } else {
+            xml += runs;
+          }
+          break;
+        }
+        case "text": {
+          const text = token as Tokens.Text;
+          xml += text.tokens ? await inline(text.tokens, format) : textRun(text.text.replace(/\n/g, " "), format);
+          break;
+        }
+        case "html":
+          // Raw HTML has no Word equivalent; tags are dropped
+          break;
+        default:
+          if ("text" in token && typeof token.text === "string") {
+            xml += textRun(token.text, format);
+          }
+      }
+    }
+
+    return xml;
+  }
+
+  async function renderList(list: Tokens.List, depth: number): Promise<string> {
+    let xml = "";
+    let number = typeof list.start === "number" ? list.start : 1;
+
+    for (const item of list.items) {
+      const marker = item.task ? (item.checked ? "☒" : "☐") : list.ordered ? `${number}.` : "•";
+      number += 1;
+
+      // The first text block carries the marker; nested lists follow as their own paragraphs
+      let hasMarker = false;
+      for (const child of item.tokens) {
+        if (child.type === "list") {
+          xml += await renderList(child as Tokens.List, depth + 1);
+          continue;
+        }
+        if (child.type === "checkbox") {
+          continue;
+        }
+        if (child.type === "text" || child.type === "paragraph") {
+          const block = child as Tokens.Text | Tokens.Paragraph;
+          const runs = await inline(block.tokens ?? [block]);
+          xml += paragraph(`${hasMarker ? "" : textRun(`${marker}\t`, {})}${runs}`, { indent: depth });
+          hasMarker = true;
+          continue;
+        }
+        xml += await blocks([child], depth);
+      }
+
+      if (!hasMarker) {
+        xml = paragraph(textRun(marker, {}), { indent: depth }) + xml;
+      }
+    }
+
+    return xml;
+  }
+
+  async function renderTable(table: Tokens.Table): Promise<string> {
+    const cell = async (content: Tokens.TableCell, header: boolean, align: string | null) => {
+      const runs = await inline(content.tokens, { bold: header });
+      const justify = align === "center" || align === "right" ? `<w:pPr><w:jc w:val="${align}"/></w:pPr>` : "";
+      return `<w:tc><w:tcPr><w:tcW w:w="0" w:type="auto"/></w:tcPr><w:p>${justify}${runs}</w:p></w:tc>`;
+    };
+
+    const header = (await Promise.all(table.header.map((content, index) => cell(content, true, table.align[index])))).join("");
+    let rows = `<w:tr><w:trPr><w:tblHeader/></w:trPr>${header}</w:tr>`;
+    for (const row of table.rows) {
+      rows += `<w:tr>${(await Promise.all(row.map((content, index) => cell(content, false, table.align[index])))).join("")}</w:tr>`;
+    }
+
+    // Word needs a paragraph between two adjacent tables
+    return `<w:tbl><w:tblPr><w:tblStyle w:val="TableGrid"/><w:tblW w:w="0" w:type="auto"/></w:tblPr>${rows}</w:tbl>${paragraph("")}`;
+  }
+
+  async function blocks(tokens: Token[], depth = 0): Promise<string> {
+    let xml = "";
+
+    for (const token of tokens) {
+      switch (token.type) {
+        case "heading": {
+          const heading = token as Tokens.Heading;
+          const slug = slugify(stripInlineMarkdown(heading.text));
+          let runs = await inline(heading.tokens);
+          if (slug) {
+            const id = bookmarkCount;
+            bookmarkCount += 1;
+            runs = `<w:bookmarkStart w:id="${id}" w:name="${escapeXml(bookmarkName(slug))}"/>${runs}<w:bookmarkEnd w:id="${id}"/>`;
+          }
+          xml += paragraph(runs, { style: `Heading${heading.depth}` });
+          break;
+        }
+        case "paragraph":
+          xml += paragraph(await inline((token as Tokens.Paragraph).tokens));
+          break;
+        case "text": {
+          const text = token as Tokens.Text;
+          xml += paragraph(await inline(text.tokens ?? [text]));
+          break;
+        }
+        case "list":
+          xml += await renderList(token as Tokens.List, depth + 1);
+          break;
+        case "blockquote": {
+          const quote = token as Tokens.Blockquote;
+          for (const child of quote.tokens) {
+            const runs = "tokens" in child && child.tokens ? await inline(child.tokens) : "";
+            xml += paragraph(runs, { style: "Quote" });
+          }
+          break;
+        }
+        case "code":
+          for (const line of (token as Tokens.Code).text.split("\n")) {
+            xml += paragraph(textRun(line, {}), { style: "CodeBlock" });
+          }
+          xml += paragraph("");
+          break;
+        case "table":
+          xml += await renderTable(token as Tokens.Table);
+          break;
+        case "hr":
+          xml += '<w:p><w:pPr><w:pBdr><w:bottom w:val="single" w:sz="6" w:space="1" w:color="BFBFBF"/></w:pBdr></w:pPr></w:p>';
+          break;
+        default:
+          // Spaces, raw HTML blocks and definitions have nothing to write
+          break;
+      }
+    }
+
+    return xml;
+  }
+
+  return { blocks, getRelationshipsXml, images, warnings };
+}
+
+/**
+ * Render a note into the parts of a .docx package
+ */
+async function buildDocx(content: string, notePath: string) {
+  const builder = createDocumentBuilder(notePath);
+  const { body } = splitFrontmatter(content);
+  const tokens = new Lexer({ gfm: true }).lex(body);
+
+  const bodyXml = await builder.blocks(tokens);
+  const documentXml =
+    `${XML_HEADER}\n<w:document ${NAMESPACES}><w:body>${bodyXml}` +
+    '<w:sectPr><w:pgSz w:w="12240" w:h="15840"/><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:header="720" w:footer="720" w:gutter="0"/></w:sectPr>' +
+    "</w:body></w:document>";
+
+  const imageDefaults = [...new Set(builder.images.map((image) => image.name.split(".").pop()))]
+    .map((extension) => `<Default Extension="${extension}" ContentType="image/${extension}"/>`)
+    .join("");
+
+  const contentTypesXml =
+    `${XML_HEADER}\n<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">` +
+    '<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>' +
+    '<Default Extension="xml" ContentType="application/xml"/>' +
+    imageDefaults +
+    '<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>' +
+    '<Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>' +
+    '<Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>' +
+    "</Types>";
+
+  const packageRelationshipsXml =
+    `${XML_HEADER}\n<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">` +
+    `<Relationship Id="rId1" Type="${RELATIONSHIP_TYPES.document}" Target="word/document.xml"/>` +
+    '<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>' +
+    "</Relationships>";
+
+  const coreXml =
+    `${XML_HEADER}\n<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" ` +
+    'xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" ' +
+    'xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">' +
+    `<dc:title>${escapeXml(getNoteTitle(notePath))}</dc:title>` +
+    `<dcterms:created xsi:type="dcterms:W3CDTF">${new Date().toISOString().replace(/\.\d+Z$/, "Z")}</dcterms:created>` +
+    "</cp:coreProperties>";
+
+  const encoder = new TextEncoder();
+  const parts: Array<[string, Uint8Array]> = [
+    ["[Content_Types].xml", encoder.encode(contentTypesXml)],
+    ["_rels/.rels", encoder.encode(packageRelationshipsXml)],
+    ["docProps/core.xml", encoder.encode(coreXml)],
+    ["word/document.xml", encoder.encode(documentXml)],
+    ["word/styles.xml", encoder.encode(STYLES_XML)],
+    ["word/_rels/document.xml.rels", encoder.encode(builder.getRelationshipsXml())],
+  ];
+  for (const image of builder.images) {
+    parts.push([`word/media/${image.name}`, image.data]);
+  }
+
+  return { parts, warnings: builder.warnings };
+}
+
+/**
+ * Export a note to a Word document chosen through the save dialog
+ * Remote images and images that can't be decoded are left as their alt text
+ * @returns Name of the written file, or null if the dialog was cancelled
+ */
+export async function exportDocx(path: string): Promise<string | null> {
+  // Ask first: the picker needs the click's user activation, which image decoding could outlast
+  const target = await fsService.showSaveDialog({
+    suggestedName: `${getNoteTitle(path)}.docx`,
+    filters: [{ name: "Word document", extensions: ["docx"] }],
+  });
+  if (!target) {
+    return null;
+  }
+
+  const content = await resolveCitations(await fsService.readFile(path));
+  const { parts, warnings } = await buildDocx(content, path);
+
+  for (const warning of warnings) {
+    console.warn(`[DocxExport] ${warning}`);
+  }
+
+  const writable = await target.handle.createWritable();
+  const zip = createZipWriter({ write: (chunk) => writable.write(chunk) });
+
+  try {
+    const modified = new Date();
+    for (const [name, data] of parts) {
+      await zip.addFile(name, data, modified);
+    }
+    await zip.close();
+    await writable.close();
+  } catch (error) {
+    await writable.abort().catch(() => undefined);
+    throw error;
+  }
+
+  return target.name;
+}
@@ -0,0 +1,75 @@
+/**
+ * Duplicate notes - finds notes with identical content and notes whose titles
+ * only differ in ways imports and copies tend to introduce
+ */
+
+import type { DuplicateGroup } from "../types";
+import * as fsService from "./fs-service";
+import { getNoteIndex } from "./note-index";
+import type { IndexedNote } from "./note-index";
+import { runWithConcurrency } from "./operations";
+
+const HASH_CONCURRENCY = 8;
+
+/**
+ * Suffixes added when a file is copied or imported twice: "(1)", " copy", "- Copy 2"
+ * A bare trailing number isn't one, or "Chapter 1" and "Chapter 2" would match
+ */
+const COPY_SUFFIX_PATTERN = /(?:\s*\(\d+\)|(?:\s+|\s*[-_]\s*)copy(?:\s+\d+)?)+$/i;
+
+/**
+ * Reduce a title to what's left once case, accents, punctuation and copy suffixes are ignored
+ */
+function getTitleKey(title: string): string {
+  const folded = title.normalize("NFD").replace(/\p{M}/gu, "").toLowerCase();
+  const stripped = folded.replace(COPY_SUFFIX_PATTERN, "");
+  // A title that is all suffix ("(2)") is kept whole rather than matched with every other
+  return (stripped.trim() ? stripped : folded).replace(/[^\p{L}\p{N}]+/gu, " ").trim();
+}
+
+function groupBy(notes: IndexedNote[], getKey: (note: IndexedNote) => string): IndexedNote[][] {
+  const groups = new Map<string, IndexedNote[]>();
+  for (const note of notes) {
+    const key = getKey(note);
+    groups.set(key, [...(groups.get(key) ?? []), note]);
+  }
+  return [...groups.values()].filter((group) => group.length > 1);
+}
+
+function toGroup(reason: DuplicateGroup["reason"], notes: IndexedNote[]): DuplicateGroup {
+  const sorted = [...notes].sort((left, right) => left.path.localeCompare(right.path));
+  return { reason, paths: sorted.map((note) => note.path), title: sorted[0].title };
+}
+
+/**
+ * Find groups of duplicate notes in the workspace
+ * Only notes of the same size are hashed. A group of similar titles is left
+ * out when all its notes are already in one group of identical notes.
+ */
+export async function findDuplicateNotes(signal?: AbortSignal): Promise<DuplicateGroup[]> {
+  const notes = [...(await getNoteIndex()).notes.values()];
+
+  const hashes = new Map<string, string>();
+  const sameSize = groupBy(notes, (note) => String(note.size)).flat();
+  await runWithConcurrency(sameSize, HASH_CONCURRENCY, async (note) => {
+    signal?.throwIfAborted();
+    hashes.set(note.path, await fsService.getFileHash(note.path));
+  });
+
+  const identical = groupBy(
+    notes.filter((note) => hashes.has(note.path)),
+    (note) => hashes.get(note.path) ?? ""
+  );
+  const similar = groupBy(notes, (note) => getTitleKey(note.title)).filter((group) => {
+    if (!getTitleKey(group[0].title)) {
+      return false;
+    }
+    const hash = hashes.get(group[0].path);
+    return !hash || group.some((note) => hashes.get(note.path) !== hash);
+  });
+
+  return [
+    ...identical.map((group) => toGroup("identical", group)),
+    ...similar.map((group) => toGroup("similar-title", group)),
+  ].sort((left, right) => left.reason.localeCompare(right.reason) || left.paths[0].localeCompare(right.paths[0]));
+}
@@ -0,0 +1,70 @@
+/**
+ * Empty folder cleanup - finds folders left with nothing in them after notes
+ * were moved or deleted, and removes them
+ */
+
+import * as fsService from "./fs-service";
+import { getParentPath } from "./pathUtils";
+
+/**
+ * Find folders below a scope that hold no files, directly or further down
+ * Hidden files count as content, so a folder holding only .DS_Store is kept;
+ * hidden folders (.git, .mdx) are never removed or looked into.
+ * @returns Workspace-relative paths of the outermost empty folders; folders
+ * nested in them are removed along with them
+ */
+async function findEmptyFolders(scope: string, signal?: AbortSignal): Promise<string[]> {
+  const root = await fsService.getRelativeWorkspacePath(scope);
+  const folders: string[] = [];
+  const occupied = new Set<string>();
+
+  const markOccupied = (path: string) => {
+    for (let folder = getParentPath(path); !occupied.has(folder); folder = getParentPath(folder)) {
+      occupied.add(folder);
+      if (folder === root || folder === ".") {
+        break;
+      }
+    }
+  };
+
+  const exclude = (relativePath: string, kind: "file" | "directory") => {
+    const hidden = kind === "directory" && (relativePath.split("/").pop() ?? "").startsWith(".");
+    if (hidden) {
+      markOccupied(relativePath);
+    }
+    return hidden;
+  };
+
+  for await (const entry of fsService.walkDirectory(root, { includeHidden: true, exclude, signal })) {
+    if (entry.handle.kind === "directory") {
+      folders.push(entry.relativePath);
+    } else {
+      markOccupied(entry.relativePath);
+    }
+  }
+
+  const empty = new Set(folders.filter((folder) => !occupied.has(folder)));
+  return [...empty].filter((folder) => !empty.has(getParentPath(folder)));
+}
+
+/**
+ * Remove the empty folders below a scope
+ * @param dryRun - Only list the folders that would be removed
+ * @returns The outermost empty folders, removed unless this is a dry run
+ */
+export async function removeEmptyDirs(
+  scope: string = ".",
+  dryRun: boolean = false,
+  signal?: AbortSignal
+): Promise<string[]> {
+  const folders = await findEmptyFolders(scope, signal);
+  if (dryRun) {
+    return folders;
+  }
+
+  // Not cancellable past this point, so every folder reported was removed
+  for (const folder of folders) {
+    await fsService.deletePath(folder);
+  }
+  return folders;
+}
@@ -0,0 +1,74 @@
+/**
+ * AppError - errors with a stable code and context for the frontend
+ * Components branch on `code` instead of matching message text; `message`
+ * stays human-readable for toasts and logs.
+ */
+
+import type { AppErrorCode } from "../types";
+
+/** DOMException names thrown by the File System Access API */
+const DOM_ERROR_CODES: Record<string, AppErrorCode> = {
+  NotFoundError: "not-found",
+  NotAllowedError: "permission-denied",
+  SecurityError: "permission-denied",
+  NoModificationAllowedError: "permission-denied",
+  TypeMismatchError: "invalid-path",
+  InvalidModificationError: "invalid-path",
+  InvalidStateError: "io",
+  QuotaExceededError: "quota-exceeded",
+  AbortError: "cancelled",
+};
+
+interface AppErrorOptions {
+  /** Workspace path the error is about */
+  path?: string | null;
+
+  /** Name of the underlying DOMException (the browser's counterpart of an OS error) */
+  osError?: string | null;
+
+  cause?: unknown;
+}
+
+export class AppError extends Error {
+  readonly code: AppErrorCode;
+  readonly path: string | null;
+  readonly osError: string | null;
+  readonly cause: unknown;
+
+  constructor(code: AppErrorCode, message: string, options: AppErrorOptions = {}) {
+    super(message);
+    this.name = "AppError";
+    this.code = code;
+    this.path = options.path ?? null;
+    this.osError = options.osError ?? null;
+    this.cause = options.cause;
+  }
+}
+
+export function isAppError(error: unknown, code?: AppErrorCode): error is AppError {
+  return error instanceof AppError && (code === undefined || error.code === code);
+}
+
+/**
+ * Wrap any thrown value in an AppError, keeping the code and context of an
+ * existing AppError and deriving one from a DOMException
+ * @param context - Prefix for the message, e.g. 'Failed to read file "a.md"'
+ */
+export function toAppError(error: unknown, context?: string, path: string | null = null): AppError {
+  const detail = error instanceof Error ? error.message : String(error);
+  const message = context ? `${context}: ${detail}` : detail;
+
+  if (error instanceof AppError) {
+    return new AppError(error.code, message, { path: error.path ?? path, osError: error.osError, cause: error });
+  }
+
+  if (error instanceof DOMException) {
+    return new AppError(DOM_ERROR_CODES[error.name] ?? "io", message, {
+      path,
+      osError: error.name,
+      cause: error,
+    });
+  }
+
+  return new AppError("unknown", message, { path, cause: error });
+}
@@ -0,0 +1,36 @@
+/**
+ * Note exporters - the export formats the app offers, in menu order
+ * Every exporter converts in the browser and asks for its own save location;
+ * a new format only needs an entry here to show up in the menus.
+ */
+
+import type { ExportFormat, ExportFormatId } from "../types";
+import { exportDocx } from "./docx-export";
+import { AppError } from "./errors";
+import { exportHtml } from "./html-export";
+
+interface Exporter extends ExportFormat {
+  /** Convert and save the note, returning the saved file name (null if cancelled) */
+  run: (path: string) => Promise<string | null>;
+}
+
+const EXPORTERS: Exporter[] = [
+  { id: "html", name: "HTML", extension: "html", run: (path) => exportHtml(path) },
+  { id: "docx", name: "Word Document", extension: "docx", run: exportDocx },
+];
+
+export function listExportFormats(): ExportFormat[] {
+  return EXPORTERS.map(({ id, name, extension }) => ({ id, name, extension }));
+}
+
+/**
+ * Export a note with the exporter registered for a format
+ * @returns Name of the written file, or null if the save dialog was cancelled
+ */
+export async function exportNote(path: string, format: ExportFormatId): Promise<string | null> {
+  const exporter = EXPORTERS.find((candidate) => candidate.id === format);
+  if (!exporter) {
+    throw new AppError("unsupported", `Export to "${format}" is not supported`, { path });
+  }
+  return exporter.run(path);
+}
@@ -3,12 +3,102 @@
  * Handles markdown file naming conventions and folder names
  */
 
+import type { FileKind, FilenameProblem } from '../types';
+
 export interface FilenameValidation {
   isValid: boolean;
   errorMessage?: string;
   normalizedName: string; // With extension applied (for files)
 }
 
+// Device names Windows reserves in every folder, with or without an extension
+const WINDOWS_RESERVED_NAME = /^(con|prn|aux|nul|com[1-9]|lpt[1-9])(\..*)?$/i;
+
+/**
+ * Check a file or folder name against Windows naming rules
+ * Such names can be created elsewhere but break the workspace on Windows,
+ * so they are rejected everywhere
+ *
+ * @returns Explanation of the problem, or null if the name is fine
+ *
+ * @example
+ * getReservedNameError('aux.md') // → 'AUX is a reserved device name on Windows...'
+ * getReservedNameError('notes.') // → 'Names cannot end with a dot or space...'
+ */
+export function getReservedNameError(name: string): string | null {
+  const match = WINDOWS_RESERVED_NAME.exec(name);
+  if (match) {
+    return `${match[1].toUpperCase()} is a reserved device name on Windows and cannot be used as a file or folder name`;
+  }
+
+  if (/[. ]$/.test(name)) {
+    return 'Names cannot end with a dot or space (Windows drops them silently)';
+  }
+
+  return null;
+}
+
+// Most filesystems cap a single name at 255 bytes (UTF-8 on Linux/macOS)
+const MAX_NAME_BYTES = 255;
+
+// Characters Windows rejects in names ("/" is a separator everywhere)
+const PORTABLE_INVALID_CHARS = /[<>:"/\\|?*]/g;
+
+/**
+ * Check a proposed file or folder name against the rules of every platform,
+ * so a workspace created on Linux stays usable on Windows and macOS
+ *
+ * @returns Every problem found (empty if the name is portable)
+ *
+ * @example
+ * validateFilename('notes.md') // → []
+ * validateFilename('a:b.') // → [{ code: 'invalid-character', ... }, { code: 'trailing-dot-or-space', ... }]
+ */
+export function validateFilename(name: string): FilenameProblem[] {
+  if (name === '') {
+    return [{ code: 'empty', message: 'Name cannot be empty' }];
+  }
+
+  const problems: FilenameProblem[] = [];
+
+  const invalidChars = [...new Set(name.match(PORTABLE_INVALID_CHARS) ?? [])];
+  if (invalidChars.length > 0) {
+    problems.push({
+      code: 'invalid-character',
+      message: `Name cannot contain ${invalidChars.map((char) => `"${char}"`).join(', ')}`,
+    });
+  }
+
+  if (/[\x00-\x1f\x7f]/.test(name)) {
+    problems.push({ code: 'control-character', message: 'Name cannot contain control characters' });
+  }
+
+  const reserved = WINDOWS_RESERVED_NAME.exec(name);
+  if (reserved) {
+    problems.push({
+      code: 'reserved-name',
+      message: `${reserved[1].toUpperCase()} is a reserved device name on Windows`,
+    });
+  }
+
+  if (/[. ]$/.test(name)) {
+    problems.push({
+      code: 'trailing-dot-or-space',
+      message: 'Name cannot end with a dot or space (Windows drops them silently)',
+    });
+  }
+
+  const byteLength = new TextEncoder().encode(name).length;
+  if (byteLength > MAX_NAME_BYTES) {
+    problems.push({
+      code: 'too-long',
+      message: `Name is ${byteLength} bytes long; the limit is ${MAX_NAME_BYTES}`,
+    });
+  }
+
+  return problems;
+}
+
 /**
  * Validates and normalizes filename for markdown files
  * 
@@ -20,6 +110,7 @@ export interface FilenameValidation {
  * 5. Contains / or \ → Invalid (no paths)
  * 6. Only whitespace → Invalid
  * 7. Starts with . → Invalid (hidden files)
+ * 8. Windows reserved names (con, aux, nul, ...) → Invalid
  * 
  * @param input - User-provided filename
  * @returns Validation result with normalized name
@@ -70,6 +161,15 @@ export function validateAndNormalizeFilename(input: string): FilenameValidation
     };
   }
 
+  const reservedNameError = getReservedNameError(trimmed);
+  if (reservedNameError) {
+    return {
+      isValid: false,
+      errorMessage: reservedNameError,
+      normalizedName: trimmed,
+    };
+  }
+
   // Parse extension
   const lastDotIndex = trimmed.lastIndexOf('.');
   const hasExtension = lastDotIndex > 0 && lastDotIndex < trimmed.length - 1;
@@ -108,6 +208,7 @@ export function validateAndNormalizeFilename(input: string): FilenameValidation
  * 2. Contains / or \ → Invalid (no paths)
  * 3. Only whitespace → Invalid
  * 4. Starts with . → Invalid (hidden folders)
+ * 5. Windows reserved names (con, aux, nul, ...) → Invalid
  * 
  * @param input - User-provided folder name
  * @returns Validation result
@@ -157,8 +258,94 @@ export function validateFolderName(input: string): FilenameValidation {
     };
   }
 
+  const reservedNameError = getReservedNameError(trimmed);
+  if (reservedNameError) {
+    return {
+      isValid: false,
+      errorMessage: reservedNameError,
+      normalizedName: trimmed,
+    };
+  }
+
   return {
     isValid: true,
     normalizedName: trimmed,
   };
 }
+
+const FILE_KINDS = new Map<string, FileKind>([
+  ['md', 'markdown'],
+  ['mdx', 'markdown'],
+  ['markdown', 'markdown'],
+  ['png', 'image'],
+  ['jpg', 'image'],
+  ['jpeg', 'image'],
+  ['gif', 'image'],
+  ['webp', 'image'],
+  ['svg', 'image'],
+  ['avif', 'image'],
+  ['bmp', 'image'],
+  ['ico', 'image'],
+  ['heic', 'image'],
+  ['heif', 'image'],
+  ['pdf', 'pdf'],
+  ['js', 'code'],
+  ['jsx', 'code'],
+  ['mjs', 'code'],
+  ['ts', 'code'],
+  ['tsx', 'code'],
+  ['json', 'code'],
+  ['css', 'code'],
+  ['scss', 'code'],
+  ['html', 'code'],
+  ['xml', 'code'],
+  ['yaml', 'code'],
+  ['yml', 'code'],
+  ['toml', 'code'],
+  ['py', 'code'],
+  ['rs', 'code'],
+  ['go', 'code'],
+  ['java', 'code'],
+  ['kt', 'code'],
+  ['c', 'code'],
+  ['h', 'code'],
+  ['cpp', 'code'],
+  ['cs', 'code'],
+  ['rb', 'code'],
+  ['php', 'code'],
+  ['swift', 'code'],
+  ['sh', 'code'],
+  ['sql', 'code'],
+  ['graphql', 'code'],
+  ['lua', 'code'],
+]);
+
+/**
+ * Lowercase extension of a filename, without the dot
+ * Returns "" when there is none; a leading dot (".gitignore") doesn't start one.
+ */
+export function getExtension(name: string): string {
+  const dotIndex = name.lastIndexOf('.');
+  return dotIndex > 0 ? name.slice(dotIndex + 1).toLowerCase() : '';
+}
+
+/**
+ * Classify a file by its extension, for icons and open behavior
+ */
+export function getFileKind(name: string): FileKind {
+  return FILE_KINDS.get(getExtension(name)) ?? 'other';
+}
+
+/**
+ * Check whether a path is a note, i.e. a markdown file
+ */
+export function isNotePath(path: string): boolean {
+  return getFileKind(path) === 'markdown';
+}
+
+/**
+ * A note's path without its extension, as links and titles name it; other paths are returned as is
+ */
+export function stripNoteExtension(path: string): string {
+  return isNotePath(path) ? path.slice(0, path.lastIndexOf('.')) : path;
+}
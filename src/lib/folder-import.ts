@@ -0,0 +1,96 @@
+/**
+ * Folder import - copies a folder from outside the workspace into it as is
+ * Unlike the Obsidian and Notion importers nothing is converted; the folder
+ * keeps its structure under a folder of the same name, optionally limited
+ * to notes and images.
+ */
+
+import type { FolderImportOptions, ImportReport, OperationProgress } from "../types";
+import { AppError } from "./errors";
+import { getFileKind } from "./filenameUtils";
+import * as fsService from "./fs-service";
+import { joinPath } from "./pathUtils";
+
+/**
+ * Import a folder picked by the user into a workspace folder
+ * The folder lands in a folder named after it, which may already exist;
+ * files that do are handled by the conflict policy.
+ * @param destinationPath - Workspace folder to import into
+ * @returns Import report, or null if the folder picker was cancelled
+ */
+export async function importFolder(
+  destinationPath: string,
+  options: FolderImportOptions = {},
+  onProgress?: (progress: OperationProgress) => void,
+  signal?: AbortSignal
+): Promise<ImportReport | null> {
+  const { conflict = "rename", filter = "all", includeHidden = false } = options;
+
+  const source = await fsService.showImportFolderDialog();
+  if (!source) {
+    return null;
+  }
+
+  const files: { relativePath: string; handle: FileSystemFileHandle }[] = [];
+  for await (const entry of fsService.walkExternalDirectory(source, { includeHidden, signal })) {
+    if (entry.handle.kind !== "file") {
+      continue;
+    }
+    const kind = getFileKind(entry.handle.name);
+    if (filter === "all" || kind === "markdown" || (filter === "notes-and-images" && kind === "image")) {
+      files.push({ relativePath: entry.relativePath, handle: entry.handle });
+    }
+  }
+
+  const parentFolder = await fsService.getRelativeWorkspacePath(destinationPath);
+  const destination = joinPath(parentFolder, source.name) ?? source.name;
+  const existing = await fsService.getEntryKind(destination);
+  if (existing === "file") {
+    throw new AppError("already-exists", `A file named "${source.name}" is in the way of the import`, {
+      path: destination,
+    });
+  }
+  if (existing === null) {
+    await fsService.createFolder(destination);
+  }
+
+  const report: ImportReport = {
+    destination,
+    imported_notes: 0,
+    imported_attachments: 0,
+    issues: [],
+  };
+
+  let bytes = 0;
+  for (const [index, file] of files.entries()) {
+    signal?.throwIfAborted();
+
+    let targetPath = `${destination}/${file.relativePath}`;
+    const targetKind = await fsService.getEntryKind(targetPath);
+    if (targetKind === "directory" || (targetKind === "file" && conflict === "skip")) {
+      report.issues.push({
+        path: targetPath,
+        line: null,
+        construct: file.relativePath,
+        message: `Skipped: a ${targetKind === "directory" ? "folder" : "file"} with this name already exists`,
+      });
+    } else {
+      if (targetKind === "file" && conflict === "rename") {
+        targetPath = await fsService.getAvailablePath(targetPath);
+      }
+
+      const sourceFile = await file.handle.getFile();
+      await fsService.writeFile(targetPath, sourceFile);
+      bytes += sourceFile.size;
+      if (getFileKind(file.handle.name) === "markdown") {
+        report.imported_notes += 1;
+      } else {
+        report.imported_attachments += 1;
+      }
+    }
+
+    onProgress?.({ processed: index + 1, total: files.length, current_path: targetPath, bytes });
+  }
+
+  return report;
+}
@@ -0,0 +1,185 @@
+/**
+ * Markdown formatter - normalizes a note's style: bullet markers, ordered
+ * list numbering, aligned tables and (optionally) wrapped paragraphs
+ * Frontmatter and fenced code are never touched.
+ */
+
+import type { FormatOptions } from "../types";
+import { getProseLines } from "./markdownUtils";
+import { findTables, formatTable } from "./tables";
+
+const BULLET_PATTERN = /^(\s*)[-*+](\s+)/;
+const ORDERED_PATTERN = /^(\s*)(\d{1,9})([.)])(\s+)/;
+const THEMATIC_BREAK_PATTERN = /^\s{0,3}([-*_])(?:\s*\1){2,}\s*$/;
+const INDENTED_CODE_PATTERN = /^(?: {4}|\t)/;
+
+/** Blocks that interrupt a list without a blank line before them */
+const LIST_INTERRUPT_PATTERN = /^(?:#{1,6}(?:\s|$)|>|\|)/;
+
+/** Lines that aren't plain paragraph text, so are never joined or wrapped */
+const NON_PARAGRAPH_PATTERN = /^(?:\s{4}|\t|\s{0,3}(?:[-*+>#|<]|\d{1,9}[.)]|\[[^\]]+\]:|=+\s*$|-+\s*$))/;
+
+/** Words that would start a block if a wrapped line began with them */
+const BLOCK_START_WORD_PATTERN = /^(?:[-*+>]|#{1,6}|\d{1,9}[.)]|=+|-+|\|.*)$/;
+
+/**
+ * Rewrap paragraph text to a line width, never breaking a word and never
+ * starting a line with something markdown would read as a new block
+ */
+function wrapParagraph(lines: string[], width: number): string[] {
+  const words = lines.join(" ").split(/\s+/).filter(Boolean);
+  const wrapped: string[] = [];
+  let current = "";
+
+  for (const word of words) {
+    if (current && current.length + 1 + word.length > width && !BLOCK_START_WORD_PATTERN.test(word)) {
+      wrapped.push(current);
+      current = word;
+    } else {
+      current = current ? `${current} ${word}` : word;
+    }
+  }
+  if (current) {
+    wrapped.push(current);
+  }
+
+  return wrapped;
+}
+
+/**
+ * Normalize a note's markdown
+ * Paragraphs ending in a hard line break keep their line breaks when wrapping.
+ */
+export function formatDocument(content: string, options: FormatOptions = {}): string {
+  const listMarker = options.listMarker ?? "-";
+  const lineWidth = options.lineWidth ?? null;
+  const eol = content.includes("\r\n") ? "\r\n" : "\n";
+  const lines = content.split(/\r?\n/);
+
+  const replacements = new Map<number, { end: number; lines: string[] }>();
+  if (options.alignTables ?? true) {
+    for (const table of findTables(content)) {
+      replacements.set(table.startLine, { end: table.endLine, lines: formatTable(table) });
+    }
+  }
+
+  const output: string[] = [];
+  let nextLine = 1;
+  const copyUntil = (line: number) => {
+    output.push(...lines.slice(nextLine - 1, line - 1));
+    nextLine = line;
+  };
+
+  // Ordered list counters by indentation, cleared when a list ends
+  const counters = new Map<number, number>();
+  const clearCounters = (fromIndent: number) => {
+    for (const indent of [...counters.keys()]) {
+      if (indent >= fromIndent) {
+        counters.delete(indent);
+      }
+    }
+  };
+
+  let paragraph: string[] = [];
+  const flushParagraph = () => {
+    if (paragraph.length > 0) {
+      output.push(...(lineWidth === null ? paragraph : wrapParagraph(paragraph, lineWidth)));
+    }
+    paragraph = [];
+  };
+
+  let previousBlank = true;
+  let inList = false;
+  let inIndentedCode = false;
+  const endLists = () => {
+    counters.clear();
+    inList = false;
+  };
+
+  for (const { line } of getProseLines(content)) {
+    if (line < nextLine) {
+      continue;
+    }
+    if (line !== nextLine) {
+      // Fenced code in between: copied as is, and it ends any paragraph, and any list unless indented into it
+      flushParagraph();
+      if (!/^\s/.test(lines[nextLine - 1])) {
+        endLists();
+      }
+      copyUntil(line);
+      previousBlank = false;
+      inIndentedCode = false;
+    }
+
+    const table = replacements.get(line);
+    if (table) {
+      flushParagraph();
+      if (!/^\s/.test(lines[line - 1])) {
+        endLists();
+      }
+      output.push(...table.lines);
+      nextLine = table.end + 1;
+      previousBlank = false;
+      inIndentedCode = false;
+      continue;
+    }
+
+    let text = lines[line - 1];
+    nextLine = line + 1;
+
+    // Indented code outside lists starts after a blank line and runs while lines stay indented or blank
+    if (!inList && INDENTED_CODE_PATTERN.test(text)) {
+      inIndentedCode ||= previousBlank;
+    } else if (text.trim()) {
+      inIndentedCode = false;
+    }
+    if (inIndentedCode) {
+      flushParagraph();
+      output.push(text);
+      previousBlank = !text.trim();
+      continue;
+    }
+
+    const bullet = THEMATIC_BREAK_PATTERN.test(text) ? null : BULLET_PATTERN.exec(text);
+    const ordered = bullet ? null : ORDERED_PATTERN.exec(text);
+    if (bullet) {
+      clearCounters(bullet[1].length);
+      text = `${bullet[1]}${listMarker}${bullet[2]}${text.slice(bullet[0].length)}`;
+    } else if (ordered && (options.renumberLists ?? true)) {
+      const indent = ordered[1].length;
+      const number = counters.get(indent) ?? Number(ordered[2]);
+      clearCounters(indent + 1);
+      counters.set(indent, number + 1);
+      text = `${ordered[1]}${number}${ordered[3]}${ordered[4]}${text.slice(ordered[0].length)}`;
+    } else if (
+      text.trim() &&
+      !/^\s/.test(text) &&
+      (previousBlank || LIST_INTERRUPT_PATTERN.test(text) || THEMATIC_BREAK_PATTERN.test(text))
+    ) {
+      // An unindented block after a blank line, or one that interrupts the list, ends every list
+      endLists();
+    }
+    inList = inList || bullet !== null || ordered !== null;
+    previousBlank = !text.trim();
+
+    // List items and their continuation lines are left as written
+    if (lineWidth === null || inList || !text.trim() || NON_PARAGRAPH_PATTERN.test(text)) {
+      flushParagraph();
+      output.push(text);
+      continue;
+    }
+
+    paragraph.push(text.trim());
+    // A hard break (trailing spaces or a backslash) has to stay at the end of its line
+    if (/(?: {2}|\\)$/.test(text)) {
+      flushParagraph();
+      if (text.endsWith(" ")) {
+        output[output.length - 1] += "  ";
+      }
+    }
+  }
+
+  flushParagraph();
+  copyUntil(lines.length + 1);
+  return output.join(eol);
+}
@@ -1,18 +1,91 @@
-import type { DirectoryPage, FileNode } from "../types";
+import type {
+  DialogFilter,
+  DirectoryCursorPage,
+  DirectoryPage,
+  FileEntryKind,
+  FileKind,
+  FileNode,
+  OpenFilesDialogOptions,
+  OperationProgress,
+  SaveDialogOptions,
+  SaveTarget,
+  TextEdit,
+  UploadImageOptions,
+  UploadedImage,
+  WorkspaceSettings,
+} from "../types";
+import { emit, listen } from "./app-events";
+import { assertUnlocked } from "./app-lock";
 import {
   clearWorkspaceHandle,
-  loadWorkspaceHandle,
+  loadWorkspace,
   saveWorkspaceHandle,
 } from "./handle-store";
+import { AppError } from "./errors";
+import { getFileKind, getReservedNameError, stripNoteExtension } from "./filenameUtils";
+import { convertHeicImage, isHeicImage, processImage, stripImageMetadata } from "./image-processing";
+import { withIoPolicy } from "./io-policy";
+import {
+  DEFAULT_WORKSPACE_SETTINGS,
+  SETTINGS_BACKUP_PATH,
+  SETTINGS_PATH,
+  expandAssetPattern,
+  getAssetPattern,
+  parseWorkspaceSettings,
+} from "./workspace-settings";
 
 let workspaceHandle: FileSystemDirectoryHandle | null = null;
 let workspacePath: string | null = null;
+let workspaceId: string | null = null;
 
 const ALLOWED_IMAGE_EXTENSIONS = new Set(["png", "jpg", "jpeg", "gif", "webp", "svg"]);
-const MAX_IMAGE_SIZE = 10 * 1024 * 1024;
+export const MAX_IMAGE_SIZE = 10 * 1024 * 1024;
+const ASSET_INDEX_PATH = ".mdx/asset-index.json";
+
+const MIME_TYPES: Record<string, string> = {
+  md: "text/markdown",
+  mdx: "text/markdown",
+  txt: "text/plain",
+  html: "text/html",
+  css: "text/css",
+  json: "application/json",
+  pdf: "application/pdf",
+  zip: "application/zip",
+  png: "image/png",
+  jpg: "image/jpeg",
+  jpeg: "image/jpeg",
+  gif: "image/gif",
+  webp: "image/webp",
+  svg: "image/svg+xml",
+};
+
+/**
+ * Types the browser may show in a tab of its own
+ * Object URLs share the app's origin, so a workspace HTML or SVG file opened
+ * in a tab would run its scripts with access to the app's storage; anything
+ * not listed here is downloaded instead.
+ */
+const VIEWABLE_EXTENSIONS = new Set(["pdf", "png", "jpg", "jpeg", "gif", "webp"]);
+
+/** How long object URLs handed to other tabs stay valid */
+const EXTERNAL_URL_REVOKE_DELAY_MS = 60_000;
 
 const IMAGE_SOURCE_PASSTHROUGH_PATTERN = /^(https?:\/\/|data:|blob:|file:|\/\/)/i;
 
+/** How long a directory listing stays available to page cursors after its last use */
+const LISTING_SNAPSHOT_TTL_MS = 5 * 60_000;
+const MAX_LISTING_SNAPSHOTS = 16;
+
+/** Directory listings that cursor pagination pages through, by snapshot id */
+const listingSnapshots = new Map<
+  string,
+  {
+    workspacePath: string;
+    nodes: FileNode[];
+    lastUsed: number;
+  }
+>();
+
 const imagePreviewCache = new Map<
   string,
   {
@@ -24,7 +97,7 @@ const imagePreviewCache = new Map<
 
 function ensureFsAccessSupport(): void {
   if (typeof window.showDirectoryPicker !== "function") {
-    throw new Error("This browser does not support the File System Access API");
+    throw new AppError("unsupported", "This browser does not support the File System Access API");
   }
 }
 
@@ -77,6 +150,14 @@ function getUtcMonthDirectory(date: Date): string {
   return `${year}-${month}`;
 }
 
+function getMimeType(extension: string): string {
+  return MIME_TYPES[extension.toLowerCase()] ?? "application/octet-stream";
+}
+
+function isAbortError(error: unknown): boolean {
+  return error instanceof DOMException && error.name === "AbortError";
+}
+
 function isTypeMismatch(error: unknown): boolean {
   return error instanceof DOMException && error.name === "TypeMismatchError";
 }
@@ -88,14 +169,58 @@ function clearImagePreviewCache(): void {
   imagePreviewCache.clear();
 }
 
+function emitConfigChanged(): void {
+  emit("config-changed", { workspace_path: workspacePath });
+}
+
+/**
+ * Announce that this window left a workspace, entered one, or both (a switch)
+ * @param previousPath - Workspace open before the change
+ */
+function emitWorkspaceLifecycle(previousPath: string | null): void {
+  if (previousPath === workspacePath) {
+    return;
+  }
+  if (previousPath !== null) {
+    emit("workspace-closed", { workspace_path: previousPath });
+  }
+  if (workspacePath !== null) {
+    emit("workspace-opened", { workspace_path: workspacePath, previous_path: previousPath });
+  }
+}
+
+// Another window switched workspaces: drop our cached handle so the next
+// restore picks up the newly stored one
+listen("config-changed", (config, { remote }) => {
+  if (remote && config.workspace_path !== workspacePath) {
+    workspaceHandle = null;
+    workspacePath = null;
+    clearImagePreviewCache();
+  }
+});
+
 function validateSegments(segments: string[]): void {
   for (const segment of segments) {
     if (!segment || segment === "." || segment === "..") {
-      throw new Error(`Invalid path segment: ${segment}`);
+      throw new AppError("invalid-path", `Invalid path segment: ${segment}`);
     }
   }
 }
 
+/**
+ * Reject names Windows cannot store before creating them, instead of failing
+ * with an opaque error from the browser
+ */
+function validateNewName(segments: string[]): void {
+  const name = segments[segments.length - 1];
+  const error = name === undefined ? null : getReservedNameError(name);
+  if (error) {
+    throw new AppError("invalid-name", `Invalid file name "${name}": ${error}`, {
+      path: segments.join("/"),
+    });
+  }
+}
+
 function toRelativeSegments(path: string, currentWorkspacePath: string): string[] {
   const normalized = normalizePath(path);
 
@@ -133,6 +258,28 @@ function getDisplayName(currentWorkspacePath: string, segments: string[]): strin
 
 type FsHandle = FileSystemFileHandle | FileSystemDirectoryHandle;
 
+export interface WorkspaceEntry {
+  /** Workspace path (prefixed with the workspace name, like FileNode.path) */
+  path: string;
+
+  /** Path relative to the workspace root, forward slashes */
+  relativePath: string;
+
+  /** Handle for reading the entry */
+  handle: FsHandle;
+}
+
+export interface WalkOptions {
+  /** Include dot-files and dot-folders */
+  includeHidden?: boolean;
+
+  /** Return true to skip an entry; skipped folders are not descended into */
+  exclude?: (relativePath: string, kind: FsHandle["kind"]) => boolean;
+
+  /** Abort the walk between entries */
+  signal?: AbortSignal;
+}
+
 function sortByTypeThenName(a: FsHandle, b: FsHandle): number {
   if (a.kind !== b.kind) {
     return a.kind === "directory" ? -1 : 1;
@@ -141,13 +288,16 @@ function sortByTypeThenName(a: FsHandle, b: FsHandle): number {
 }
 
 async function ensureWorkspace(): Promise<{ handle: FileSystemDirectoryHandle; path: string }> {
+  // Every workspace operation comes through here, so this is where the app lock holds
+  assertUnlocked();
+
   if (workspaceHandle && workspacePath) {
     return { handle: workspaceHandle, path: workspacePath };
   }
 
   const restored = await restoreWorkspace();
   if (!restored || !workspaceHandle) {
-    throw new Error("No workspace selected");
+    throw new AppError("no-workspace", "No workspace selected");
   }
 
   return { handle: workspaceHandle, path: restored };
@@ -171,7 +321,7 @@ async function getParentDirectoryAndName(
   createParent: boolean
 ): Promise<{ parent: FileSystemDirectoryHandle; name: string }> {
   if (pathSegments.length === 0) {
-    throw new Error("Path cannot reference workspace root");
+    throw new AppError("invalid-path", "Path cannot reference workspace root");
   }
 
   const parentSegments = pathSegments.slice(0, -1);
@@ -214,7 +364,7 @@ function resolveImageSourcePath(
   const normalizedSource = normalizePath(basePath);
 
   if (!normalizedSource) {
-    throw new Error("Image path cannot be empty");
+    throw new AppError("invalid-path", "Image path cannot be empty");
   }
 
   const sourceSegments = normalizedSource.split("/").filter(Boolean);
@@ -234,7 +384,7 @@ function resolveImageSourcePath(
 
     if (segment === "..") {
       if (segments.length === 0) {
-        throw new Error("Image path cannot traverse outside workspace");
+        throw new AppError("invalid-path", "Image path cannot traverse outside workspace");
       }
       segments.pop();
       continue;
@@ -245,7 +395,7 @@ function resolveImageSourcePath(
   }
 
   if (segments.length === 0) {
-    throw new Error("Image path must reference a file");
+    throw new AppError("invalid-path", "Image path must reference a file");
   }
 
   return buildWorkspacePath(currentWorkspacePath, segments);
@@ -278,11 +428,15 @@ async function getExistingHandle(
   }
 }
 
+/**
+ * Copy a file into a directory
+ * @returns Number of bytes copied
+ */
 async function copyFileTo(
   source: FileSystemFileHandle,
   destinationDirectory: FileSystemDirectoryHandle,
   destinationName: string
-): Promise<void> {
+): Promise<number> {
   const sourceFile = await source.getFile();
   const targetHandle = await destinationDirectory.getFileHandle(destinationName, { create: true });
   const writable = await targetHandle.createWritable();
@@ -291,28 +445,154 @@ async function copyFileTo(
   } finally {
     await writable.close();
   }
+  return sourceFile.size;
+}
+
+async function findAvailableFilename(
+  directory: FileSystemDirectoryHandle,
+  filename: string
+): Promise<string> {
+  const extension = getFileExtension(filename);
+  const { name: baseName } = splitExtension(filename, extension);
+
+  let candidate = filename;
+  let counter = 1;
+
+  while (await getExistingHandle(directory, candidate)) {
+    candidate = extension ? `${baseName}-${counter}.${extension}` : `${baseName}-${counter}`;
+    counter += 1;
+  }
+
+  return candidate;
+}
+
+/**
+ * Run a task under a Web Lock shared by every window of the app
+ * Falls back to running unlocked where the Web Locks API is missing
+ */
+async function withLock<T>(name: string, task: () => Promise<T>): Promise<T> {
+  if (typeof navigator === "undefined" || !navigator.locks) {
+    return task();
+  }
+  return navigator.locks.request(`mdx:${name}`, task);
+}
+
+/** Called after each item of a recursive copy or delete */
+type ItemCallback = (relativePath: string, bytes: number) => void;
+
+function createProgressReporter(total: number, onProgress?: (progress: OperationProgress) => void): ItemCallback {
+  let processed = 0;
+  let bytes = 0;
+
+  return (relativePath, itemBytes) => {
+    processed += 1;
+    bytes += itemBytes;
+    onProgress?.({ processed, total, current_path: relativePath, bytes });
+  };
+}
+
+async function countEntries(directory: FileSystemDirectoryHandle, signal?: AbortSignal): Promise<number> {
+  let count = 0;
+  for await (const [, handle] of directory.entries()) {
+    signal?.throwIfAborted();
+    count += handle.kind === "directory" ? 1 + (await countEntries(handle, signal)) : 1;
+  }
+  return count;
 }
 
 async function copyDirectoryTo(
   source: FileSystemDirectoryHandle,
   destinationDirectory: FileSystemDirectoryHandle,
-  destinationName: string
+  destinationSegments: string[],
+  signal?: AbortSignal,
+  onItem?: ItemCallback
 ): Promise<void> {
+  const destinationName = destinationSegments[destinationSegments.length - 1];
   const targetDirectory = await destinationDirectory.getDirectoryHandle(destinationName, { create: true });
 
   for await (const [entryName, entryHandle] of source.entries()) {
+    signal?.throwIfAborted();
+    const entrySegments = [...destinationSegments, entryName];
+
     if (entryHandle.kind === "directory") {
-      await copyDirectoryTo(entryHandle, targetDirectory, entryName);
+      await copyDirectoryTo(entryHandle, targetDirectory, entrySegments, signal, onItem);
+      onItem?.(entrySegments.join("/"), 0);
+    } else {
+      const bytes = await copyFileTo(entryHandle, targetDirectory, entryName);
+      onItem?.(entrySegments.join("/"), bytes);
+    }
+  }
+}
+
+/**
+ * Delete everything inside a directory entry by entry, deepest first
+ */
+async function deleteDirectoryContents(
+  directory: FileSystemDirectoryHandle,
+  segments: string[],
+  signal?: AbortSignal,
+  onItem?: ItemCallback
+): Promise<void> {
+  const children: FsHandle[] = [];
+  for await (const [, handle] of directory.entries()) {
+    children.push(handle);
+  }
+
+  for (const handle of children) {
+    signal?.throwIfAborted();
+    const childSegments = [...segments, handle.name];
+
+    if (handle.kind === "directory") {
+      await deleteDirectoryContents(handle, childSegments, signal, onItem);
+      await directory.removeEntry(handle.name);
+      onItem?.(childSegments.join("/"), 0);
     } else {
-      await copyFileTo(entryHandle, targetDirectory, entryName);
+      const { size } = await handle.getFile();
+      await directory.removeEntry(handle.name);
+      onItem?.(childSegments.join("/"), size);
+    }
+  }
+}
+
+/**
+ * Whether a directory has an entry the tree would show, found by reading
+ * only as far as the first one
+ */
+async function hasVisibleEntries(directory: FileSystemDirectoryHandle, includeHidden: boolean): Promise<boolean> {
+  for await (const [name] of directory.entries()) {
+    if (includeHidden || !name.startsWith(".")) {
+      return true;
     }
   }
+  return false;
+}
+
+/**
+ * Classify a file without an extension by its leading bytes
+ */
+async function sniffFileKind(file: File): Promise<FileKind> {
+  const header = new Uint8Array(await file.slice(0, 8).arrayBuffer());
+  const startsWith = (...bytes: number[]) => bytes.every((byte, index) => header[index] === byte);
+
+  if (startsWith(0x25, 0x50, 0x44, 0x46)) {
+    return "pdf";
+  }
+  if (
+    startsWith(0x89, 0x50, 0x4e, 0x47) ||
+    startsWith(0xff, 0xd8, 0xff) ||
+    startsWith(0x47, 0x49, 0x46, 0x38) ||
+    (startsWith(0x52, 0x49, 0x46, 0x46) && (await file.slice(8, 12).text()) === "WEBP")
+  ) {
+    return "image";
+  }
+  return "other";
 }
 
 async function toFileNode(
   handle: FsHandle,
   parentSegments: string[],
-  currentWorkspacePath: string
+  currentWorkspacePath: string,
+  includeHidden: boolean
 ): Promise<FileNode> {
   const segments = [...parentSegments, handle.name];
   const path = buildWorkspacePath(currentWorkspacePath, segments);
@@ -320,37 +600,77 @@ async function toFileNode(
   if (handle.kind === "directory") {
     return {
       path,
+      relative_path: segments.join("/"),
       name: handle.name,
       is_file: false,
       size: null,
       modified: null,
+      created: null,
+      extension: "",
       children: null,
+      has_children: await hasVisibleEntries(handle, includeHidden),
+      kind: "directory",
     };
   }
 
   const file = await handle.getFile();
+  const extension = getFileExtension(handle.name);
   return {
     path,
+    relative_path: segments.join("/"),
     name: handle.name,
     is_file: true,
     size: file.size,
-    modified: new Date(file.lastModified).toISOString(),
+    modified: file.lastModified,
+    created: null,
+    extension,
     children: null,
+    has_children: false,
+    kind: extension ? getFileKind(handle.name) : await sniffFileKind(file),
   };
 }
 
+/**
+ * Match a name against a sidebar filter: the filter as a case-insensitive
+ * substring or, failing that, its characters in order ("mtg" in "meeting")
+ * @param filter - Lowercased filter text
+ */
+function matchesNameFilter(name: string, filter: string): boolean {
+  const text = name.toLowerCase();
+  if (text.includes(filter)) {
+    return true;
+  }
+
+  let position = 0;
+  for (const char of filter) {
+    position = text.indexOf(char, position) + 1;
+    if (position === 0) {
+      return false;
+    }
+  }
+  return true;
+}
+
 async function listDirectoryNodes(
   directory: FileSystemDirectoryHandle,
   parentSegments: string[],
   currentWorkspacePath: string,
-  includeHidden: boolean
+  includeHidden: boolean,
+  signal?: AbortSignal,
+  filter: string = ""
 ): Promise<FileNode[]> {
   const entries: FsHandle[] = [];
+  const normalizedFilter = filter.trim().toLowerCase();
 
   for await (const [, handle] of directory.entries()) {
+    signal?.throwIfAborted();
     if (!includeHidden && handle.name.startsWith(".")) {
       continue;
     }
+    // Filtered out before any metadata is read for the entry
+    if (normalizedFilter && !matchesNameFilter(handle.name, normalizedFilter)) {
+      continue;
+    }
     entries.push(handle);
   }
 
@@ -358,7 +678,8 @@ async function listDirectoryNodes(
 
   const nodes: FileNode[] = [];
   for (const handle of entries) {
-    nodes.push(await toFileNode(handle, parentSegments, currentWorkspacePath));
+    signal?.throwIfAborted();
+    nodes.push(await toFileNode(handle, parentSegments, currentWorkspacePath, includeHidden));
   }
 
   return nodes;
@@ -368,10 +689,13 @@ export async function openWorkspace(): Promise<string> {
   ensureFsAccessSupport();
 
   const handle = await window.showDirectoryPicker({ mode: "readwrite" });
+  const previousPath = workspacePath;
   workspaceHandle = handle;
   workspacePath = handle.name;
   clearImagePreviewCache();
-  await saveWorkspaceHandle(handle);
+  workspaceId = await saveWorkspaceHandle(handle);
+  emitConfigChanged();
+  emitWorkspaceLifecycle(previousPath);
 
   return handle.name;
 }
@@ -381,11 +705,12 @@ export async function restoreWorkspace(): Promise<string | null> {
     return workspacePath;
   }
 
-  const handle = await loadWorkspaceHandle();
-  if (!handle) {
+  const stored = await loadWorkspace();
+  if (!stored) {
     return null;
   }
 
+  const { handle } = stored;
   if (!handle.queryPermission) {
     return null;
   }
@@ -395,8 +720,12 @@ export async function restoreWorkspace(): Promise<string | null> {
     return null;
   }
 
+  // A concurrent restore may have finished first; announce the workspace once
+  const previousPath = workspaceHandle ? workspacePath : null;
   workspaceHandle = handle;
   workspacePath = handle.name;
+  workspaceId = stored.id;
+  emitWorkspaceLifecycle(previousPath);
   return workspacePath;
 }
 
@@ -404,7 +733,7 @@ export async function requestWorkspacePermission(): Promise<string | null> {
   let handle = workspaceHandle;
 
   if (!handle) {
-    handle = await loadWorkspaceHandle();
+    handle = (await loadWorkspace())?.handle ?? null;
   }
 
   if (!handle || !handle.requestPermission) {
@@ -416,9 +745,12 @@ export async function requestWorkspacePermission(): Promise<string | null> {
     return null;
   }
 
+  const previousPath = workspacePath;
   workspaceHandle = handle;
   workspacePath = handle.name;
-  await saveWorkspaceHandle(handle);
+  workspaceId = await saveWorkspaceHandle(handle);
+  emitConfigChanged();
+  emitWorkspaceLifecycle(previousPath);
 
   return workspacePath;
 }
@@ -428,15 +760,234 @@ export async function hasStoredWorkspace(): Promise<boolean> {
     return true;
   }
 
-  const handle = await loadWorkspaceHandle();
-  return handle !== null;
+  return (await loadWorkspace()) !== null;
 }
 
 export async function clearWorkspace(): Promise<void> {
+  const previousPath = workspacePath;
   workspaceHandle = null;
   workspacePath = null;
+  workspaceId = null;
   clearImagePreviewCache();
   await clearWorkspaceHandle();
+  emitConfigChanged();
+  emitWorkspaceLifecycle(previousPath);
+}
+
+/**
+ * Stable id of the open workspace, for data kept per workspace outside it
+ * Unlike the workspace name it tells apart folders that share a name.
+ */
+export async function getWorkspaceId(): Promise<string> {
+  await ensureWorkspace();
+  if (!workspaceId) {
+    throw new AppError("no-workspace", "No workspace selected");
+  }
+  return workspaceId;
+}
+
+function toPickerTypes(filters: DialogFilter[]): FilePickerAcceptType[] {
+  return filters.map((filter) => {
+    const accept: Record<string, string[]> = {};
+    for (const extension of filter.extensions) {
+      const mimeType = getMimeType(extension);
+      accept[mimeType] = [...(accept[mimeType] ?? []), `.${extension}`];
+    }
+    return { description: filter.name, accept };
+  });
+}
+
+/**
+ * Workspace path of a handle obtained outside the workspace tree
+ * (pickers, drops, launches), or null if it lies outside the workspace
+ */
+export async function resolveWorkspacePath(handle: FileSystemHandle): Promise<string | null> {
+  if (!workspaceHandle || !workspacePath) {
+    return null;
+  }
+
+  const segments = await workspaceHandle.resolve(handle);
+  return segments ? buildWorkspacePath(workspacePath, segments) : null;
+}
+
+export async function showSaveDialog(options: SaveDialogOptions = {}): Promise<SaveTarget | null> {
+  if (typeof window.showSaveFilePicker !== "function") {
+    throw new AppError("unsupported", "This browser does not support the save file dialog");
+  }
+
+  const filters = options.filters ?? [];
+
+  let handle: FileSystemFileHandle;
+  try {
+    // With filters the picker itself only offers their extensions
+    handle = await window.showSaveFilePicker({
+      suggestedName: options.suggestedName,
+      types: toPickerTypes(filters),
+      excludeAcceptAllOption: filters.length > 0,
+    });
+  } catch (error) {
+    if (isAbortError(error)) {
+      return null;
+    }
+    throw error;
+  }
+
+  const allowedExtensions = filters.flatMap((filter) =>
+    filter.extensions.map((extension) => extension.toLowerCase())
+  );
+  if (allowedExtensions.length > 0 && !allowedExtensions.includes(getFileExtension(handle.name))) {
+    // The picker has already created the file; don't leave it behind empty
+    await handle.remove?.().catch(() => undefined);
+    throw new AppError("invalid-name", `File must use one of these extensions: ${allowedExtensions.join(", ")}`);
+  }
+
+  return {
+    handle,
+    name: handle.name,
+    workspacePath: await resolveWorkspacePath(handle),
+  };
+}
+
+/**
+ * Let the user pick files and bring them into the workspace
+ * Images go through the upload pipeline into assets/, other files are copied
+ * into the destination folder. Files already inside the workspace are returned as-is.
+ * @returns Workspace paths of the imported files (empty if cancelled)
+ */
+export async function showOpenFilesDialog(
+  destinationPath: string,
+  options: OpenFilesDialogOptions = {}
+): Promise<string[]> {
+  if (typeof window.showOpenFilePicker !== "function") {
+    throw new AppError("unsupported", "This browser does not support the open file dialog");
+  }
+
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+
+  let handles: FileSystemFileHandle[];
+  try {
+    handles = await window.showOpenFilePicker({
+      multiple: options.multiple ?? true,
+      types: toPickerTypes(options.filters ?? []),
+    });
+  } catch (error) {
+    if (isAbortError(error)) {
+      return [];
+    }
+    throw error;
+  }
+
+  const destinationSegments = toRelativeSegments(destinationPath, currentWorkspacePath);
+  const destination = await resolveDirectoryHandle(root, destinationSegments, true);
+  const importedPaths: string[] = [];
+
+  for (const handle of handles) {
+    const existingPath = await resolveWorkspacePath(handle);
+    if (existingPath) {
+      importedPaths.push(existingPath);
+      continue;
+    }
+
+    const file = await handle.getFile();
+    if (ALLOWED_IMAGE_EXTENSIONS.has(getFileExtension(file.name)) || isHeicImage(file)) {
+      const { path: assetPath } = await uploadImage(file);
+      importedPaths.push(buildWorkspacePath(currentWorkspacePath, assetPath.split("/")));
+      continue;
+    }
+
+    const name = await findAvailableFilename(destination, file.name);
+    await copyFileTo(handle, destination, name);
+    importedPaths.push(buildWorkspacePath(currentWorkspacePath, [...destinationSegments, name]));
+  }
+
+  return importedPaths;
+}
+
+/**
+ * Let the user pick a folder outside the workspace to import from
+ * The folder is opened read-only; folders inside the workspace, or
+ * containing it, are rejected so an import can never copy into itself
+ * @returns The chosen folder, or null if the picker was cancelled
+ */
+export async function showImportFolderDialog(): Promise<FileSystemDirectoryHandle | null> {
+  ensureFsAccessSupport();
+
+  const { handle: root } = await ensureWorkspace();
+
+  let handle: FileSystemDirectoryHandle;
+  try {
+    handle = await window.showDirectoryPicker({ mode: "read" });
+  } catch (error) {
+    if (isAbortError(error)) {
+      return null;
+    }
+    throw error;
+  }
+
+  if ((await root.resolve(handle)) !== null || (await handle.resolve(root)) !== null) {
+    throw new AppError("invalid-path", "Import folder cannot overlap the workspace");
+  }
+
+  return handle;
+}
+
+/**
+ * Let the user pick a folder outside the workspace to export into
+ * Folders inside the workspace, or containing it, are rejected so an
+ * export never writes over the notes it reads
+ * @returns The chosen folder, or null if the picker was cancelled
+ */
+export async function showExportFolderDialog(): Promise<FileSystemDirectoryHandle | null> {
+  ensureFsAccessSupport();
+
+  const { handle: root } = await ensureWorkspace();
+
+  let handle: FileSystemDirectoryHandle;
+  try {
+    handle = await window.showDirectoryPicker({ mode: "readwrite" });
+  } catch (error) {
+    if (isAbortError(error)) {
+      return null;
+    }
+    throw error;
+  }
+
+  if ((await root.resolve(handle)) !== null || (await handle.resolve(root)) !== null) {
+    throw new AppError("invalid-path", "Export folder cannot overlap the workspace");
+  }
+
+  return handle;
+}
+
+/**
+ * Let the user pick a single file to import from (e.g. an export archive)
+ * The file is only read; nothing is copied into the workspace
+ * @returns The chosen file, or null if the picker was cancelled
+ */
+export async function showImportFileDialog(filters: DialogFilter[] = []): Promise<File | null> {
+  if (typeof window.showOpenFilePicker !== "function") {
+    throw new AppError("unsupported", "This browser does not support the open file dialog");
+  }
+
+  try {
+    const [handle] = await window.showOpenFilePicker({ multiple: false, types: toPickerTypes(filters) });
+    return await handle.getFile();
+  } catch (error) {
+    if (isAbortError(error)) {
+      return null;
+    }
+    throw error;
+  }
+}
+
+export async function writeFileHandle(handle: FileSystemFileHandle, data: string | Blob): Promise<void> {
+  const writable = await handle.createWritable();
+
+  try {
+    await writable.write(data);
+  } finally {
+    await writable.close();
+  }
 }
 
 async function fileExists(directory: FileSystemDirectoryHandle, name: string): Promise<boolean> {
@@ -451,11 +1002,139 @@ async function fileExists(directory: FileSystemDirectoryHandle, name: string): P
   }
 }
 
-export async function uploadImage(file: File): Promise<string> {
-  const { handle: root } = await ensureWorkspace();
+async function readSettingsFile(path: string): Promise<string | null> {
+  try {
+    return await readFile(path);
+  } catch (error) {
+    if (isNotFound(error)) {
+      return null;
+    }
+    throw error;
+  }
+}
+
+/**
+ * Read the workspace settings, or the defaults if none are saved
+ * A corrupt settings.json is recovered from the backup of the last good file
+ * instead of silently resetting every setting
+ */
+export async function getWorkspaceSettings(): Promise<WorkspaceSettings> {
+  const text = await readSettingsFile(SETTINGS_PATH);
+  if (text === null) {
+    return DEFAULT_WORKSPACE_SETTINGS;
+  }
+
+  const settings = parseWorkspaceSettings(text);
+  if (settings) {
+    return settings;
+  }
+
+  const backupText = await readSettingsFile(SETTINGS_BACKUP_PATH);
+  const backup = backupText === null ? null : parseWorkspaceSettings(backupText);
+  if (backupText === null || !backup) {
+    console.warn(`[Settings] ${SETTINGS_PATH} is corrupt and has no usable backup; using defaults`);
+    return DEFAULT_WORKSPACE_SETTINGS;
+  }
+
+  console.warn(`[Settings] ${SETTINGS_PATH} is corrupt; restored from ${SETTINGS_BACKUP_PATH}`);
+  await writeFile(SETTINGS_PATH, backupText);
+  return backup;
+}
+
+/**
+ * Save the workspace settings, keeping the previous file as a backup
+ * The write itself is atomic: the browser writes a swap file and only
+ * replaces settings.json when the stream is closed
+ */
+export async function saveWorkspaceSettings(settings: WorkspaceSettings): Promise<void> {
+  const previous = await readSettingsFile(SETTINGS_PATH);
+  if (previous !== null && parseWorkspaceSettings(previous)) {
+    await writeFile(SETTINGS_BACKUP_PATH, previous);
+  }
+
+  await writeFile(SETTINGS_PATH, `${JSON.stringify(settings, null, 2)}\n`);
+}
+
+async function getContentHash(file: Blob): Promise<string> {
+  const digest = await crypto.subtle.digest("SHA-256", await file.arrayBuffer());
+  return Array.from(new Uint8Array(digest), (byte) => byte.toString(16).padStart(2, "0")).join("");
+}
+
+/**
+ * Content hash -> asset path of every stored upload, kept in the workspace so
+ * identical images are found again across sessions and windows
+ */
+async function readAssetIndex(): Promise<Record<string, string>> {
+  try {
+    const parsed: unknown = JSON.parse(await readFile(ASSET_INDEX_PATH));
+    return parsed && typeof parsed === "object" ? (parsed as Record<string, string>) : {};
+  } catch (error) {
+    if (isNotFound(error) || error instanceof SyntaxError) {
+      return {};
+    }
+    throw error;
+  }
+}
+
+async function isStoredAsset(path: string, size: number): Promise<boolean> {
+  try {
+    return (await getFile(path)).size === size;
+  } catch (error) {
+    if (isNotFound(error) || isTypeMismatch(error)) {
+      return false;
+    }
+    throw error;
+  }
+}
+
+function pad2(value: number): string {
+  return String(value).padStart(2, "0");
+}
+
+/**
+ * Name a stored image after the workspace's filename template
+ * {counter} takes the first number that gives a name not yet in the folder;
+ * the image's extension is added unless the name already ends with it
+ */
+async function getTemplateFilename(
+  template: string,
+  directory: FileSystemDirectoryHandle,
+  extension: string,
+  variables: Record<string, string>
+): Promise<string> {
+  const format = (counter: number) => {
+    const expanded = expandAssetPattern(template, { ...variables, counter: String(counter), ext: extension });
+    const name = sanitizeFilename(expanded);
+    return name.toLowerCase().endsWith(`.${extension}`) ? name : `${name}.${extension}`;
+  };
+
+  if (!template.includes("{counter}")) {
+    return format(1);
+  }
+
+  let counter = 1;
+  while (await fileExists(directory, format(counter))) {
+    counter += 1;
+  }
+  return format(counter);
+}
+
+/**
+ * Store an image under the assets root, laid out per the workspace settings
+ * (assets/YYYY-MM/ by default). An image identical to one uploaded before is
+ * not written again; the existing asset path is returned instead.
+ */
+export async function uploadImage(originalFile: File, options: UploadImageOptions = {}): Promise<UploadedImage> {
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+  const source = isHeicImage(originalFile)
+    ? await convertHeicImage(originalFile, options.heicFormat, options.processing?.quality)
+    : originalFile;
+  const processed = options.processing ? await processImage(source, options.processing) : source;
+  const file = (options.stripMetadata ?? true) ? await stripImageMetadata(processed) : processed;
 
   if (file.size > MAX_IMAGE_SIZE) {
-    throw new Error(
+    throw new AppError(
+      "too-large",
       `Image size ${file.size} bytes exceeds maximum of ${MAX_IMAGE_SIZE} bytes (10MB)`
     );
   }
@@ -463,44 +1142,113 @@ export async function uploadImage(file: File): Promise<string> {
   const sanitizedFilename = sanitizeFilename(file.name);
 
   if (sanitizedFilename.includes("/") || sanitizedFilename.includes("\\")) {
-    throw new Error("Filename cannot contain path separators");
+    throw new AppError("invalid-name", "Filename cannot contain path separators");
   }
 
   const extension = getFileExtension(sanitizedFilename);
   if (!ALLOWED_IMAGE_EXTENSIONS.has(extension)) {
-    throw new Error(
+    throw new AppError(
+      "unsupported",
       `Unsupported image format. Allowed: ${Array.from(ALLOWED_IMAGE_EXTENSIONS).join(", ")}`
     );
   }
 
-  const now = new Date();
-  const monthDirectory = getUtcMonthDirectory(now);
+  const hash = await getContentHash(file);
+
+  return withLock(`assets:${currentWorkspacePath}`, async () => {
+    const assetIndex = await readAssetIndex();
+    const existingPath = assetIndex[hash];
+    if (existingPath && (await isStoredAsset(existingPath, file.size))) {
+      return {
+        path: existingPath,
+        original_size: originalFile.size,
+        stored_size: file.size,
+        reused: true,
+      };
+    }
 
-  const assetsDirectory = await root.getDirectoryHandle("assets", { create: true });
-  const monthHandle = await assetsDirectory.getDirectoryHandle(monthDirectory, { create: true });
+    const settings = await getWorkspaceSettings();
+    const pattern = getAssetPattern(settings);
+    const noteSegments = options.notePath ? toRelativeSegments(options.notePath, currentWorkspacePath) : [];
+
+    const expanded = expandAssetPattern(pattern, {
+      name: splitExtension(sanitizedFilename, extension).name,
+      date: getUtcMonthDirectory(new Date()),
+      note: noteSegments.length > 0 ? noteSegments.join("/").replace(/\.[^./]*$/, "") : "unsorted",
+      hash: hash.slice(0, 12),
+    });
+
+    // Every segment is sanitized like a file name; the last one is the file stem
+    const directorySegments = [...settings.assets.root.split("/"), ...expanded.split("/")]
+      .filter((segment) => segment.trim() && segment !== "." && segment !== "..")
+      .map(sanitizeFilename);
+    const baseName = directorySegments.pop() ?? "image";
+    const directoryHandle = await resolveDirectoryHandle(root, directorySegments, true);
+
+    // A filename template takes the place of the pattern's last segment
+    const now = new Date();
+    let finalFilename = settings.assets.filename
+      ? await getTemplateFilename(settings.assets.filename, directoryHandle, extension, {
+          name: splitExtension(sanitizedFilename, extension).name,
+          note: noteSegments.length > 0 ? stripNoteExtension(noteSegments[noteSegments.length - 1]) : "unsorted",
+          date: `${now.getFullYear()}-${pad2(now.getMonth() + 1)}-${pad2(now.getDate())}`,
+          time: `${pad2(now.getHours())}${pad2(now.getMinutes())}${pad2(now.getSeconds())}`,
+          hash: hash.slice(0, 12),
+        })
+      : `${baseName}.${extension}`;
+    const collisionBase = splitExtension(finalFilename, extension).name;
+    let collisionAttempt = 0;
+
+    while (await fileExists(directoryHandle, finalFilename)) {
+      const timestamp = Math.floor(Date.now() / 1000);
+      const collisionSuffix = collisionAttempt === 0 ? `${timestamp}` : `${timestamp}-${collisionAttempt}`;
+      finalFilename = `${collisionBase}-${collisionSuffix}.${extension}`;
+      collisionAttempt += 1;
+    }
 
-  const { name: baseName } = splitExtension(sanitizedFilename, extension);
+    const fileHandle = await directoryHandle.getFileHandle(finalFilename, { create: true });
+    const writable = await fileHandle.createWritable();
 
-  let finalFilename = sanitizedFilename;
-  let collisionAttempt = 0;
+    try {
+      await writable.write(await file.arrayBuffer());
+    } finally {
+      await writable.close();
+    }
 
-  while (await fileExists(monthHandle, finalFilename)) {
-    const timestamp = Math.floor(Date.now() / 1000);
-    const collisionSuffix = collisionAttempt === 0 ? `${timestamp}` : `${timestamp}-${collisionAttempt}`;
-    finalFilename = `${baseName}-${collisionSuffix}.${extension}`;
-    collisionAttempt += 1;
-  }
+    const path = [...directorySegments, finalFilename].join("/");
+    assetIndex[hash] = path;
+    await writeFile(ASSET_INDEX_PATH, `${JSON.stringify(assetIndex, null, 2)}\n`);
 
-  const fileHandle = await monthHandle.getFileHandle(finalFilename, { create: true });
-  const writable = await fileHandle.createWritable();
+    return {
+      path,
+      original_size: originalFile.size,
+      stored_size: file.size,
+      reused: false,
+    };
+  });
+}
 
-  try {
-    await writable.write(await file.arrayBuffer());
-  } finally {
-    await writable.close();
-  }
+async function getLinkedFile(
+  source: string,
+  currentDocumentPath: string | null
+): Promise<{ path: string; file: File }> {
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+  const resolvedPath = resolveImageSourcePath(source.trim(), currentWorkspacePath, currentDocumentPath);
 
-  return `assets/${monthDirectory}/${finalFilename}`;
+  const segments = toRelativeSegments(resolvedPath, currentWorkspacePath);
+  const { parent, name } = await getParentDirectoryAndName(root, segments, false);
+  const fileHandle = await parent.getFileHandle(name);
+
+  return { path: resolvedPath, file: await fileHandle.getFile() };
+}
+
+/**
+ * Read a file referenced from a document, resolving the link relative to the
+ * document (or to the workspace root when it starts with "/")
+ */
+export async function readLinkedFile(source: string, currentDocumentPath: string | null): Promise<File> {
+  const { file } = await getLinkedFile(source, currentDocumentPath);
+  return file;
 }
 
 export async function resolveImagePreviewSource(
@@ -512,13 +1260,7 @@ export async function resolveImagePreviewSource(
     return imageSource;
   }
 
-  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
-  const resolvedPath = resolveImageSourcePath(trimmedSource, currentWorkspacePath, currentDocumentPath);
-
-  const segments = toRelativeSegments(resolvedPath, currentWorkspacePath);
-  const { parent, name } = await getParentDirectoryAndName(root, segments, false);
-  const fileHandle = await parent.getFileHandle(name);
-  const file = await fileHandle.getFile();
+  const { path: resolvedPath, file } = await getLinkedFile(trimmedSource, currentDocumentPath);
 
   const cached = imagePreviewCache.get(resolvedPath);
   if (cached && cached.size === file.size && cached.lastModified === file.lastModified) {
@@ -539,44 +1281,158 @@ export async function resolveImagePreviewSource(
   return objectUrl;
 }
 
-export async function readFile(path: string): Promise<string> {
+/**
+ * Open a workspace file in a new browser tab so the browser's built-in
+ * viewer handles it
+ * Only PDFs and raster images are shown; other files are downloaded.
+ */
+export async function openWithDefaultApp(path: string): Promise<void> {
   const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
   const segments = toRelativeSegments(path, currentWorkspacePath);
 
   if (segments.length === 0) {
-    throw new Error("Expected file path, received workspace root");
+    throw new AppError("invalid-path", "Expected file path, received workspace root", { path: path });
   }
 
   const { parent, name } = await getParentDirectoryAndName(root, segments, false);
   const fileHandle = await parent.getFileHandle(name);
   const file = await fileHandle.getFile();
 
-  return file.text();
+  const extension = getFileExtension(name);
+  if (!VIEWABLE_EXTENSIONS.has(extension)) {
+    const downloadUrl = URL.createObjectURL(new Blob([file], { type: "application/octet-stream" }));
+    const link = document.createElement("a");
+    link.href = downloadUrl;
+    link.download = name;
+    link.click();
+    setTimeout(() => URL.revokeObjectURL(downloadUrl), EXTERNAL_URL_REVOKE_DELAY_MS);
+    return;
+  }
+
+  const objectUrl = URL.createObjectURL(new Blob([file], { type: getMimeType(extension) }));
+
+  const opened = window.open(objectUrl, "_blank");
+  if (!opened) {
+    URL.revokeObjectURL(objectUrl);
+    throw new AppError("permission-denied", "The browser blocked opening a new tab");
+  }
+
+  setTimeout(() => URL.revokeObjectURL(objectUrl), EXTERNAL_URL_REVOKE_DELAY_MS);
 }
 
-export async function writeFile(path: string, content: string): Promise<void> {
+/**
+ * Get a workspace file as a File (contents plus size and lastModified)
+ */
+export async function getFile(path: string): Promise<File> {
   const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
   const segments = toRelativeSegments(path, currentWorkspacePath);
-  const { parent, name } = await getParentDirectoryAndName(root, segments, true);
 
-  const fileHandle = await parent.getFileHandle(name, { create: true });
-  const writable = await fileHandle.createWritable();
-
-  try {
-    await writable.write(content);
-  } finally {
-    await writable.close();
+  if (segments.length === 0) {
+    throw new AppError("invalid-path", "Expected file path, received workspace root", { path: path });
   }
+
+  const { parent, name } = await getParentDirectoryAndName(root, segments, false);
+  const fileHandle = await parent.getFileHandle(name);
+
+  return fileHandle.getFile();
+}
+
+export async function readFile(path: string): Promise<string> {
+  return withIoPolicy("Reading the file", path, async () => (await getFile(path)).text());
+}
+
+export async function writeFile(path: string, content: string | Blob): Promise<void> {
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+  const segments = toRelativeSegments(path, currentWorkspacePath);
+
+  await withIoPolicy("Writing the file", path, async () => {
+    const { parent, name } = await getParentDirectoryAndName(root, segments, true);
+    const fileHandle = await parent.getFileHandle(name, { create: true });
+    const writable = await fileHandle.createWritable();
+
+    try {
+      await writable.write(content);
+    } finally {
+      await writable.close();
+    }
+  });
+}
+
+/**
+ * Get the SHA-256 of a file's content, for use as an applyEdits precondition
+ */
+export async function getFileHash(path: string): Promise<string> {
+  return getContentHash(await getFile(path));
+}
+
+/**
+ * Read a file together with its content hash, both from the same read, for
+ * an edit that has to fail if the file changes before it's written
+ */
+export async function readFileWithHash(path: string): Promise<{ content: string; hash: string }> {
+  const file = await withIoPolicy("Reading the file", path, () => getFile(path));
+  return { content: await file.text(), hash: await getContentHash(file) };
+}
+
+/**
+ * Apply ranged edits to a file and write the result in one step
+ * Edits address the content as it is before any of them apply, must not
+ * overlap, and are serialized with other edits to the file across windows.
+ * @param expectedHash - If given, the edit is refused with a "conflict" error
+ *   unless the file still has this content hash
+ * @returns Content hash after the edit
+ */
+export async function applyEdits(path: string, edits: TextEdit[], expectedHash?: string): Promise<string> {
+  const { path: currentWorkspacePath } = await ensureWorkspace();
+  const relativePath = toRelativeSegments(path, currentWorkspacePath).join("/");
+
+  return withLock(`edit:${buildWorkspacePath(currentWorkspacePath, [relativePath])}`, async () => {
+    const file = await getFile(relativePath);
+    if (expectedHash !== undefined && (await getContentHash(file)) !== expectedHash) {
+      throw new AppError("conflict", "File changed since it was read", { path: relativePath });
+    }
+
+    const content = await file.text();
+    const sorted = [...edits].sort((left, right) => left.start - right.start || left.end - right.end);
+    let previousEnd = 0;
+    for (const edit of sorted) {
+      if (
+        !Number.isInteger(edit.start) ||
+        !Number.isInteger(edit.end) ||
+        edit.start < previousEnd ||
+        edit.end < edit.start ||
+        edit.end > content.length
+      ) {
+        throw new AppError("invalid-argument", `Invalid or overlapping edit range ${edit.start}-${edit.end}`, {
+          path: relativePath,
+        });
+      }
+      previousEnd = edit.end;
+    }
+
+    let result = "";
+    let offset = 0;
+    for (const edit of sorted) {
+      result += content.slice(offset, edit.start) + edit.replacement;
+      offset = edit.end;
+    }
+    result += content.slice(offset);
+
+    // createWritable writes to a swap file that replaces the original on close
+    await writeFile(relativePath, result);
+    return getContentHash(new Blob([result]));
+  });
 }
 
 export async function createFile(path: string): Promise<void> {
   const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
   const segments = toRelativeSegments(path, currentWorkspacePath);
+  validateNewName(segments);
   const { parent, name } = await getParentDirectoryAndName(root, segments, true);
 
   const existing = await getExistingHandle(parent, name);
   if (existing) {
-    throw new Error(`Path already exists: ${path}`);
+    throw new AppError("already-exists", `Path already exists: ${path}`, { path: path });
   }
 
   const fileHandle = await parent.getFileHandle(name, { create: true });
@@ -584,13 +1440,50 @@ export async function createFile(path: string): Promise<void> {
   await writable.close();
 }
 
+/**
+ * Create the first free untitled file in a folder: "Untitled.md",
+ * "Untitled 2.md", ... The name is picked and the file created under a lock,
+ * so repeated clicks (in this or another window) never collide
+ * @returns Workspace path of the created file
+ */
+export async function generateUntitledPath(
+  directoryPath: string,
+  baseName: string = "Untitled",
+  extension: string = "md"
+): Promise<string> {
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+  const segments = toRelativeSegments(directoryPath, currentWorkspacePath);
+  const normalizedExtension = extension.replace(/^\./, "");
+
+  if (!baseName.trim() || /[\\/]/.test(baseName) || /[\\/]/.test(normalizedExtension)) {
+    throw new AppError("invalid-name", "Base name and extension cannot be empty or contain path separators");
+  }
+
+  const directory = await resolveDirectoryHandle(root, segments, true);
+  const suffix = normalizedExtension ? `.${normalizedExtension}` : "";
+
+  return withLock(`untitled:${buildWorkspacePath(currentWorkspacePath, segments)}`, async () => {
+    let name = `${baseName}${suffix}`;
+    for (let counter = 2; await getExistingHandle(directory, name); counter += 1) {
+      name = `${baseName} ${counter}${suffix}`;
+    }
+
+    const fileHandle = await directory.getFileHandle(name, { create: true });
+    const writable = await fileHandle.createWritable();
+    await writable.close();
+
+    return buildWorkspacePath(currentWorkspacePath, [...segments, name]);
+  });
+}
+
 export async function createFolder(path: string): Promise<void> {
   const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
   const segments = toRelativeSegments(path, currentWorkspacePath);
 
   if (segments.length === 0) {
-    throw new Error("Cannot create workspace root");
+    throw new AppError("invalid-path", "Cannot create workspace root");
   }
+  validateNewName(segments);
 
   let current = root;
 
@@ -601,7 +1494,7 @@ export async function createFolder(path: string): Promise<void> {
     if (isLast) {
       const existing = await getExistingHandle(current, segment);
       if (existing) {
-        throw new Error(`Path already exists: ${path}`);
+        throw new AppError("already-exists", `Path already exists: ${path}`, { path: path });
       }
     }
 
@@ -609,31 +1502,76 @@ export async function createFolder(path: string): Promise<void> {
   }
 }
 
-export async function deletePath(path: string): Promise<void> {
+/**
+ * Delete a file or folder
+ * With a signal or progress callback, folders are emptied entry by entry so
+ * progress can be reported; a cancelled delete stops part-way through
+ */
+export async function deletePath(
+  path: string,
+  signal?: AbortSignal,
+  onProgress?: (progress: OperationProgress) => void
+): Promise<void> {
   const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
   const segments = toRelativeSegments(path, currentWorkspacePath);
   const { parent, name } = await getParentDirectoryAndName(root, segments, false);
 
+  const handle = signal || onProgress ? await getExistingHandle(parent, name) : null;
+  if (handle?.kind === "directory") {
+    const reportItem = createProgressReporter((await countEntries(handle, signal)) + 1, onProgress);
+    await deleteDirectoryContents(handle, segments, signal, reportItem);
+    signal?.throwIfAborted();
+    await parent.removeEntry(name, { recursive: true });
+    reportItem(segments.join("/"), 0);
+    return;
+  }
+
   await parent.removeEntry(name, { recursive: true });
 }
 
-export async function renamePath(oldPath: string, newPath: string): Promise<void> {
+/**
+ * Whether a path names a file or a folder
+ * @returns Null if nothing exists at the path
+ */
+export async function getEntryKind(path: string): Promise<FileEntryKind | null> {
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+  const segments = toRelativeSegments(path, currentWorkspacePath);
+  if (segments.length === 0) {
+    return "directory";
+  }
+
+  const { parent, name } = await getParentDirectoryAndName(root, segments, false);
+  return (await getExistingHandle(parent, name))?.kind ?? null;
+}
+
+/**
+ * Rename or move a file or folder
+ * Folders are copied then removed; a cancelled copy removes the partial
+ * destination and leaves the source untouched. Progress covers the copy.
+ */
+export async function renamePath(
+  oldPath: string,
+  newPath: string,
+  signal?: AbortSignal,
+  onProgress?: (progress: OperationProgress) => void
+): Promise<void> {
   const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
   const oldSegments = toRelativeSegments(oldPath, currentWorkspacePath);
   const newSegments = toRelativeSegments(newPath, currentWorkspacePath);
 
   if (oldSegments.length === 0 || newSegments.length === 0) {
-    throw new Error("Cannot rename workspace root");
+    throw new AppError("invalid-path", "Cannot rename workspace root", { path: oldPath });
   }
 
   if (oldSegments.join("/") === newSegments.join("/")) {
     return;
   }
+  validateNewName(newSegments);
 
   const oldInfo = await getParentDirectoryAndName(root, oldSegments, false);
   const oldHandle = await getExistingHandle(oldInfo.parent, oldInfo.name);
   if (!oldHandle) {
-    throw new Error(`Path not found: ${oldPath}`);
+    throw new AppError("not-found", `Path not found: ${oldPath}`, { path: oldPath });
   }
 
   if (
@@ -641,57 +1579,185 @@ export async function renamePath(oldPath: string, newPath: string): Promise<void
     newSegments.length > oldSegments.length &&
     oldSegments.every((segment, index) => segment === newSegments[index])
   ) {
-    throw new Error("Cannot move a folder into itself");
+    throw new AppError("invalid-path", "Cannot move a folder into itself", { path: newPath });
   }
 
   const newInfo = await getParentDirectoryAndName(root, newSegments, true);
   const destinationExisting = await getExistingHandle(newInfo.parent, newInfo.name);
   if (destinationExisting) {
-    throw new Error(`Path already exists: ${newPath}`);
+    throw new AppError("already-exists", `Path already exists: ${newPath}`, { path: newPath });
   }
 
   if (oldHandle.kind === "directory") {
-    await copyDirectoryTo(oldHandle, newInfo.parent, newInfo.name);
+    const total = onProgress ? (await countEntries(oldHandle, signal)) + 1 : 0;
+    const reportItem = createProgressReporter(total, onProgress);
+
+    try {
+      await copyDirectoryTo(oldHandle, newInfo.parent, newSegments, signal, reportItem);
+      reportItem(newSegments.join("/"), 0);
+    } catch (error) {
+      if (signal?.aborted) {
+        await newInfo.parent.removeEntry(newInfo.name, { recursive: true }).catch(() => undefined);
+      }
+      throw error;
+    }
   } else {
-    await copyFileTo(oldHandle, newInfo.parent, newInfo.name);
+    const bytes = await copyFileTo(oldHandle, newInfo.parent, newInfo.name);
+    onProgress?.({ processed: 1, total: 1, current_path: newSegments.join("/"), bytes });
   }
 
+  signal?.throwIfAborted();
   await oldInfo.parent.removeEntry(oldInfo.name, { recursive: true });
 }
 
-export async function readDirectory(path: string, includeHidden: boolean = false): Promise<FileNode> {
+async function* walkEntries(
+  directory: FileSystemDirectoryHandle,
+  segments: string[],
+  currentWorkspacePath: string,
+  options: WalkOptions
+): AsyncGenerator<WorkspaceEntry> {
+  const children: FsHandle[] = [];
+
+  for await (const [, handle] of directory.entries()) {
+    options.signal?.throwIfAborted();
+    if (!options.includeHidden && handle.name.startsWith(".")) {
+      continue;
+    }
+    children.push(handle);
+  }
+
+  children.sort(sortByTypeThenName);
+
+  for (const handle of children) {
+    options.signal?.throwIfAborted();
+
+    const childSegments = [...segments, handle.name];
+    const relativePath = childSegments.join("/");
+    if (options.exclude?.(relativePath, handle.kind)) {
+      continue;
+    }
+
+    yield {
+      path: buildWorkspacePath(currentWorkspacePath, childSegments),
+      relativePath,
+      handle,
+    };
+
+    if (handle.kind === "directory") {
+      yield* walkEntries(handle, childSegments, currentWorkspacePath, options);
+    }
+  }
+}
+
+/**
+ * Walk everything below a directory depth-first
+ * Entries within a folder come folders-first, then by name
+ */
+export async function* walkDirectory(path: string, options: WalkOptions = {}): AsyncGenerator<WorkspaceEntry> {
   const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
   const segments = toRelativeSegments(path, currentWorkspacePath);
   const directory = await resolveDirectoryHandle(root, segments, false);
-  const children = await listDirectoryNodes(directory, segments, currentWorkspacePath, includeHidden);
+
+  yield* walkEntries(directory, segments, currentWorkspacePath, options);
+}
+
+/**
+ * Walk a folder outside the workspace (e.g. an import source)
+ * Entry paths are prefixed with the folder's own name
+ */
+export async function* walkExternalDirectory(
+  directory: FileSystemDirectoryHandle,
+  options: WalkOptions = {}
+): AsyncGenerator<WorkspaceEntry> {
+  yield* walkEntries(directory, [], directory.name, options);
+}
+
+/**
+ * Convert a workspace path to its workspace-relative form ("." for the root)
+ */
+export async function getRelativeWorkspacePath(path: string): Promise<string> {
+  const { path: currentWorkspacePath } = await ensureWorkspace();
+  const segments = toRelativeSegments(path, currentWorkspacePath);
+  return segments.length === 0 ? "." : segments.join("/");
+}
+
+/**
+ * Find a free path by suffixing the name ("name-1.ext") when it is taken
+ * @returns Workspace-relative path that does not exist yet
+ */
+export async function getAvailablePath(path: string): Promise<string> {
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+  const segments = toRelativeSegments(path, currentWorkspacePath);
+  if (segments.length === 0) {
+    throw new AppError("invalid-path", "Path cannot reference workspace root");
+  }
+
+  const parentSegments = segments.slice(0, -1);
+  const name = segments[segments.length - 1];
+
+  let parent: FileSystemDirectoryHandle;
+  try {
+    parent = await resolveDirectoryHandle(root, parentSegments, false);
+  } catch (error) {
+    if (isNotFound(error)) {
+      return segments.join("/");
+    }
+    throw error;
+  }
+
+  return [...parentSegments, await findAvailableFilename(parent, name)].join("/");
+}
+
+export async function readDirectory(
+  path: string,
+  includeHidden: boolean = false,
+  signal?: AbortSignal
+): Promise<FileNode> {
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+  const segments = toRelativeSegments(path, currentWorkspacePath);
+  const children = await withIoPolicy("Listing the folder", path, async () => {
+    const directory = await resolveDirectoryHandle(root, segments, false);
+    return listDirectoryNodes(directory, segments, currentWorkspacePath, includeHidden, signal);
+  });
 
   return {
     path: buildWorkspacePath(currentWorkspacePath, segments),
+    relative_path: segments.join("/"),
     name: getDisplayName(currentWorkspacePath, segments),
     is_file: false,
     size: null,
     modified: null,
+    created: null,
+    extension: "",
     children,
+    has_children: children.length > 0,
+    kind: "directory",
   };
 }
 
+/**
+ * @param filter - Only list entries whose name matches (substring or
+ * in-order characters, ignoring case); total_count counts the matches
+ */
 export async function getDirectoryPage(
   path: string,
   offset: number,
   limit: number,
-  includeHidden: boolean = false
+  includeHidden: boolean = false,
+  filter: string = "",
+  signal?: AbortSignal
 ): Promise<DirectoryPage> {
   if (offset < 0) {
-    throw new Error("Offset must be >= 0");
+    throw new AppError("invalid-argument", "Offset must be >= 0");
   }
   if (limit <= 0) {
-    throw new Error("Limit must be > 0");
+    throw new AppError("invalid-argument", "Limit must be > 0");
   }
 
   const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
   const segments = toRelativeSegments(path, currentWorkspacePath);
   const directory = await resolveDirectoryHandle(root, segments, false);
-  const allNodes = await listDirectoryNodes(directory, segments, currentWorkspacePath, includeHidden);
+  const allNodes = await listDirectoryNodes(directory, segments, currentWorkspacePath, includeHidden, signal, filter);
 
   return {
     nodes: allNodes.slice(offset, offset + limit),
@@ -699,3 +1765,140 @@ export async function getDirectoryPage(
     has_more: offset + limit < allNodes.length,
   };
 }
+
+/**
+ * Count a directory's entries without reading any file's metadata
+ * Hidden entries are skipped like in listings unless includeHidden is set.
+ */
+export async function countDirectoryItems(
+  path: string,
+  includeHidden: boolean = false,
+  signal?: AbortSignal
+): Promise<number> {
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+  const directory = await resolveDirectoryHandle(root, toRelativeSegments(path, currentWorkspacePath), false);
+
+  let count = 0;
+  for await (const [name] of directory.entries()) {
+    signal?.throwIfAborted();
+    if (includeHidden || !name.startsWith(".")) {
+      count += 1;
+    }
+  }
+  return count;
+}
+
+function takeListingSnapshot(currentWorkspacePath: string, nodes: FileNode[]): string {
+  const now = Date.now();
+  for (const [id, snapshot] of listingSnapshots) {
+    if (now - snapshot.lastUsed > LISTING_SNAPSHOT_TTL_MS) {
+      listingSnapshots.delete(id);
+    }
+  }
+  // Maps iterate in insertion order, so the first entries are the oldest
+  while (listingSnapshots.size >= MAX_LISTING_SNAPSHOTS) {
+    const [oldest] = listingSnapshots.keys();
+    listingSnapshots.delete(oldest);
+  }
+
+  const id = crypto.randomUUID();
+  listingSnapshots.set(id, { workspacePath: currentWorkspacePath, nodes, lastUsed: now });
+  return id;
+}
+
+/**
+ * Page through a directory with cursors instead of offsets
+ * The first call (no cursor) lists the directory once and keeps that
+ * listing; later pages come from it, so entries created or deleted
+ * meanwhile can't shift items between pages. Re-list to see changes.
+ * @param cursor - next_cursor of the previous page, or null for the first page
+ */
+export async function getDirectoryPageByCursor(
+  path: string,
+  cursor: string | null,
+  limit: number,
+  includeHidden: boolean = false,
+  signal?: AbortSignal
+): Promise<DirectoryCursorPage> {
+  if (limit <= 0) {
+    throw new AppError("invalid-argument", "Limit must be > 0");
+  }
+
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+  let snapshotId: string;
+  let offset = 0;
+
+  if (cursor === null) {
+    const segments = toRelativeSegments(path, currentWorkspacePath);
+    const directory = await resolveDirectoryHandle(root, segments, false);
+    const nodes = await listDirectoryNodes(directory, segments, currentWorkspacePath, includeHidden, signal);
+    snapshotId = takeListingSnapshot(currentWorkspacePath, nodes);
+  } else {
+    const separator = cursor.lastIndexOf(":");
+    snapshotId = cursor.slice(0, separator);
+    offset = Number(cursor.slice(separator + 1));
+    if (separator === -1 || !Number.isInteger(offset) || offset < 0) {
+      throw new AppError("invalid-argument", "Invalid page cursor");
+    }
+  }
+
+  const snapshot = listingSnapshots.get(snapshotId);
+  if (!snapshot || snapshot.workspacePath !== currentWorkspacePath) {
+    throw new AppError("invalid-argument", "The page cursor has expired; list the directory again");
+  }
+  snapshot.lastUsed = Date.now();
+
+  const end = offset + limit;
+  return {
+    nodes: snapshot.nodes.slice(offset, end),
+    total_count: snapshot.nodes.length,
+    has_more: end < snapshot.nodes.length,
+    next_cursor: end < snapshot.nodes.length ? `${snapshotId}:${end}` : null,
+  };
+}
+
+/**
+ * List a directory in batches, each yielded as soon as it fills, so a very
+ * large folder can be shown before it has been read to the end
+ * Batches come in the order the browser lists entries, each one sorted
+ * folders-first then by name; the full listing is not sorted across batches.
+ */
+export async function* scanDirectory(
+  path: string,
+  batchSize: number,
+  includeHidden: boolean = false,
+  signal?: AbortSignal
+): AsyncGenerator<FileNode[]> {
+  if (batchSize <= 0) {
+    throw new AppError("invalid-argument", "Batch size must be > 0");
+  }
+
+  const { handle: root, path: currentWorkspacePath } = await ensureWorkspace();
+  const segments = toRelativeSegments(path, currentWorkspacePath);
+  const directory = await resolveDirectoryHandle(root, segments, false);
+
+  let batch: FsHandle[] = [];
+  const toNodes = (handles: FsHandle[]) =>
+    Promise.all(
+      handles
+        .sort(sortByTypeThenName)
+        .map((handle) => toFileNode(handle, segments, currentWorkspacePath, includeHidden))
+    );
+
+  for await (const [, handle] of directory.entries()) {
+    signal?.throwIfAborted();
+    if (!includeHidden && handle.name.startsWith(".")) {
+      continue;
+    }
+
+    batch.push(handle);
+    if (batch.length >= batchSize) {
+      yield await toNodes(batch);
+      batch = [];
+    }
+  }
+
+  if (batch.length > 0) {
+    yield await toNodes(batch);
+  }
+}
@@ -0,0 +1,49 @@
+/**
+ * Glob matching for workspace-relative paths
+ * Supports *, ** and ? with forward-slash separators
+ */
+
+/**
+ * Convert a glob pattern into an anchored regular expression
+ *
+ * @example
+ * globToRegExp('notes/**\/*.md').test('notes/2025/plan.md') // → true
+ */
+export function globToRegExp(pattern: string): RegExp {
+  let source = "";
+
+  for (let index = 0; index < pattern.length; index += 1) {
+    const char = pattern[index];
+
+    if (char === "*") {
+      if (pattern[index + 1] === "*") {
+        if (pattern[index + 2] === "/") {
+          // "**/" matches zero or more whole directories
+          source += "(?:.*/)?";
+          index += 2;
+        } else {
+          source += ".*";
+          index += 1;
+        }
+      } else {
+        source += "[^/]*";
+      }
+    } else if (char === "?") {
+      source += "[^/]";
+    } else {
+      source += char.replace(/[.+^${}()|[\]\\]/g, "\\$&");
+    }
+  }
+
+  return new RegExp(`^${source}$`);
+}
+
+/**
+ * Test a workspace-relative path against a glob
+ * Patterns without a slash match the last path segment at any depth
+ * (like .gitignore), so "*.tmp" matches "a/b/c.tmp"
+ */
+export function matchesGlob(path: string, pattern: string): boolean {
+  const target = pattern.includes("/") ? path : path.split("/").pop() ?? path;
+  return globToRegExp(pattern).test(target);
+}
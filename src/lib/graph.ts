@@ -0,0 +1,90 @@
+/**
+ * Graph view data - notes as nodes and their links as edges, computed from
+ * the note index so the view never parses files itself
+ */
+
+import type { GraphEdge, GraphNode, GraphOptions, NoteGraph } from "../types";
+import * as fsService from "./fs-service";
+import { indexPaths, resolveLink } from "./link-checker";
+import { getNoteIndex, getTitleLookup } from "./note-index";
+import type { IndexedNote } from "./note-index";
+
+const TAG_ID_PREFIX = "tag:";
+
+function hasTag(note: IndexedNote, tag: string): boolean {
+  return note.tags.some((noteTag) => {
+    const key = noteTag.toLowerCase();
+    return key === tag || key.startsWith(`${tag}/`);
+  });
+}
+
+/**
+ * Build the link graph of the workspace, or of a folder or tag within it
+ * Links to notes outside the filter are left out
+ */
+export async function getGraph(options: GraphOptions = {}): Promise<NoteGraph> {
+  const { notes } = await getNoteIndex();
+  const folder = options.folder ? await fsService.getRelativeWorkspacePath(options.folder) : ".";
+  const tag = options.tag?.replace(/^#/, "").toLowerCase() ?? null;
+
+  const included = [...notes.values()].filter(
+    (note) =>
+      (folder === "." || note.path.startsWith(`${folder}/`)) && (tag === null || hasTag(note, tag))
+  );
+  const includedPaths = new Set(included.map((note) => note.path));
+
+  // Resolve against every note so names match the same notes they do unfiltered
+  const resolver = indexPaths(notes.keys(), [], getTitleLookup(notes));
+  const edgesByKey = new Map<string, GraphEdge>();
+  const addEdge = (source: string, target: string, kind: GraphEdge["kind"]) => {
+    const key = `${source}\n${target}`;
+    const edge = edgesByKey.get(key) ?? { source, target, kind, weight: 0 };
+    edge.weight += 1;
+    edgesByKey.set(key, edge);
+  };
+
+  const tagLabels = new Map<string, string>();
+  for (const note of included) {
+    for (const link of note.links) {
+      const target = resolveLink(note.path, link, resolver).path;
+      if (target !== null && target !== note.path && includedPaths.has(target)) {
+        addEdge(note.path, target, "link");
+      }
+    }
+
+    if (options.includeTags) {
+      for (const noteTag of note.tags) {
+        const key = noteTag.toLowerCase();
+        tagLabels.set(key, tagLabels.get(key) ?? noteTag);
+        addEdge(note.path, `${TAG_ID_PREFIX}${key}`, "tag");
+      }
+    }
+  }
+
+  const edges = [...edgesByKey.values()];
+  const degrees = new Map<string, number>();
+  for (const edge of edges) {
+    degrees.set(edge.source, (degrees.get(edge.source) ?? 0) + 1);
+    degrees.set(edge.target, (degrees.get(edge.target) ?? 0) + 1);
+  }
+
+  const nodes: GraphNode[] = [
+    ...included.map((note) => ({
+      id: note.path,
+      kind: "note" as const,
+      label: note.title,
+      degree: degrees.get(note.path) ?? 0,
+    })),
+    ...[...tagLabels].map(([key, label]) => ({
+      id: `${TAG_ID_PREFIX}${key}`,
+      kind: "tag" as const,
+      label,
+      degree: degrees.get(`${TAG_ID_PREFIX}${key}`) ?? 0,
+    })),
+  ];
+
+  return {
+    nodes: options.includeOrphans === false ? nodes.filter((node) => node.degree > 0) : nodes,
+    edges,
+  };
+}
@@ -3,6 +3,12 @@ const DB_VERSION = 1;
 const STORE_NAME = "workspace";
 const WORKSPACE_KEY = "root-handle";
 
+/** The workspace folder, with an id that stays the same while the same folder is reopened */
+export interface StoredWorkspace {
+  id: string;
+  handle: FileSystemDirectoryHandle;
+}
+
 function openDatabase(): Promise<IDBDatabase> {
   return new Promise((resolve, reject) => {
     const request = indexedDB.open(DB_NAME, DB_VERSION);
@@ -19,37 +25,45 @@ function openDatabase(): Promise<IDBDatabase> {
   });
 }
 
-export async function saveWorkspaceHandle(handle: FileSystemDirectoryHandle): Promise<void> {
+/**
+ * Remember the workspace folder
+ * @returns Id of the workspace: the stored one if this is the same folder, a new one otherwise
+ */
+export async function saveWorkspaceHandle(handle: FileSystemDirectoryHandle): Promise<string> {
+  const previous = await loadWorkspace();
+  const id = previous && (await previous.handle.isSameEntry(handle)) ? previous.id : crypto.randomUUID();
   const database = await openDatabase();
 
   await new Promise<void>((resolve, reject) => {
     const transaction = database.transaction(STORE_NAME, "readwrite");
     const store = transaction.objectStore(STORE_NAME);
-    const request = store.put(handle, WORKSPACE_KEY);
+    const request = store.put({ id, handle } satisfies StoredWorkspace, WORKSPACE_KEY);
 
     request.onsuccess = () => resolve();
     request.onerror = () => reject(request.error ?? new Error("Failed to store workspace handle"));
   });
 
   database.close();
+  return id;
 }
 
-export async function loadWorkspaceHandle(): Promise<FileSystemDirectoryHandle | null> {
+export async function loadWorkspace(): Promise<StoredWorkspace | null> {
   const database = await openDatabase();
 
-  const result = await new Promise<FileSystemDirectoryHandle | null>((resolve, reject) => {
+  const result = await new Promise<StoredWorkspace | FileSystemDirectoryHandle | null>((resolve, reject) => {
     const transaction = database.transaction(STORE_NAME, "readonly");
     const store = transaction.objectStore(STORE_NAME);
     const request = store.get(WORKSPACE_KEY);
 
     request.onsuccess = () => {
-      resolve((request.result as FileSystemDirectoryHandle | undefined) ?? null);
+      resolve((request.result as StoredWorkspace | FileSystemDirectoryHandle | undefined) ?? null);
     };
     request.onerror = () => reject(request.error ?? new Error("Failed to load workspace handle"));
   });
 
   database.close();
-  return result;
+  // Handles stored before workspaces had ids go by their name, which sessions used to be keyed on
+  return result && "kind" in result ? { id: result.name, handle: result } : result;
 }
 
 export async function clearWorkspaceHandle(): Promise<void> {
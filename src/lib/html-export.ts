@@ -0,0 +1,164 @@
+/**
+ * HTML export - renders a note into a standalone HTML document
+ * Referenced workspace images can be inlined as data URLs so the file
+ * stays readable after it leaves the workspace
+ */
+
+import type { HtmlExportOptions, HtmlExportTheme } from "../types";
+import { resolveCitations } from "./bibliography";
+import { AppError } from "./errors";
+import * as fsService from "./fs-service";
+import { renderMarkdown } from "./markdown-render";
+import { getNoteTitle, splitFrontmatter } from "./markdownUtils";
+
+const EXTERNAL_SOURCE_PATTERN = /^(https?:\/\/|data:|blob:|file:|\/\/|#)/i;
+
+const BASE_STYLES = `
+body{margin:0;background:var(--bg);color:var(--fg);font:16px/1.6 -apple-system,BlinkMacSystemFont,"Segoe UI",Helvetica,Arial,sans-serif}
+.markdown-body{max-width:820px;margin:0 auto;padding:32px 24px}
+a{color:var(--link)}
+pre,code{font-family:ui-monospace,SFMono-Regular,Menlo,Consolas,monospace;font-size:.9em}
+pre{background:var(--code-bg);padding:16px;border-radius:6px;overflow:auto}
+:not(pre)>code{background:var(--code-bg);padding:.2em .4em;border-radius:4px}
+blockquote{margin:0;padding:0 1em;color:var(--muted);border-left:4px solid var(--border)}
+table{border-collapse:collapse}
+th,td{border:1px solid var(--border);padding:6px 13px}
+hr{border:0;border-top:1px solid var(--border)}
+img{max-width:100%}
+.site-nav{margin-bottom:24px;color:var(--muted);font-size:.9em}
+`;
+
+const THEME_STYLES: Record<HtmlExportTheme, string> = {
+  light: `:root{--bg:#ffffff;--fg:#1f2328;--muted:#59636e;--link:#0969da;--code-bg:#f6f8fa;--border:#d1d9e0}${BASE_STYLES}`,
+  dark: `:root{--bg:#0d1117;--fg:#e6edf3;--muted:#9198a1;--link:#4493f8;--code-bg:#151b23;--border:#3d444d}${BASE_STYLES}`,
+  none: "",
+};
+
+export function escapeHtml(text: string): string {
+  return text
+    .replace(/&/g, "&amp;")
+    .replace(/</g, "&lt;")
+    .replace(/>/g, "&gt;")
+    .replace(/"/g, "&quot;");
+}
+
+function readAsDataUrl(blob: Blob): Promise<string> {
+  return new Promise((resolve, reject) => {
+    const reader = new FileReader();
+    reader.onload = () => resolve(reader.result as string);
+    reader.onerror = () => reject(reader.error ?? new Error("Failed to read file"));
+    reader.readAsDataURL(blob);
+  });
+}
+
+async function inlineImages(html: string, documentPath: string): Promise<string> {
+  const parsed = new DOMParser().parseFromString(html, "text/html");
+
+  for (const image of Array.from(parsed.querySelectorAll("img[src]"))) {
+    const source = image.getAttribute("src") ?? "";
+    if (!source || EXTERNAL_SOURCE_PATTERN.test(source)) {
+      continue;
+    }
+
+    try {
+      const file = await fsService.readLinkedFile(decodeURI(source), documentPath);
+      image.setAttribute("src", await readAsDataUrl(file));
+    } catch (error) {
+      // Leave the original link in place so the export still succeeds
+      console.warn(`[HtmlExport] Could not inline image ${source}:`, error);
+    }
+  }
+
+  return parsed.body.innerHTML;
+}
+
+/**
+ * Wrap an HTML fragment in a complete document with the export stylesheet
+ */
+export function buildHtmlPage(title: string, body: string, theme: HtmlExportTheme = "light"): string {
+  const styles = THEME_STYLES[theme];
+
+  return [
+    "<!doctype html>",
+    '<html lang="en">',
+    "<head>",
+    '<meta charset="utf-8" />',
+    '<meta name="viewport" content="width=device-width, initial-scale=1" />',
+    `<title>${escapeHtml(title)}</title>`,
+    styles ? `<style>${styles}</style>` : "",
+    "</head>",
+    "<body>",
+    '<main class="markdown-body">',
+    body,
+    "</main>",
+    "</body>",
+    "</html>",
+  ]
+    .filter(Boolean)
+    .join("\n");
+}
+
+/**
+ * Render markdown into a complete HTML document
+ * @param content - Markdown source (frontmatter is dropped)
+ * @param documentPath - Path of the note, used to resolve relative images
+ */
+export async function renderHtmlDocument(
+  content: string,
+  documentPath: string,
+  options: HtmlExportOptions = {}
+): Promise<string> {
+  let html = await renderMarkdown(content);
+
+  if (options.inlineAssets ?? true) {
+    html = await inlineImages(html, documentPath);
+  }
+
+  return buildHtmlPage(getNoteTitle(documentPath), html, options.theme);
+}
+
+/**
+ * Export a note to an HTML file chosen through the save dialog
+ * @returns Name of the written file, or null if the dialog was cancelled
+ */
+export async function exportHtml(path: string, options: HtmlExportOptions = {}): Promise<string | null> {
+  const content = await resolveCitations(await fsService.readFile(path));
+  const html = await renderHtmlDocument(content, path, options);
+
+  const target = await fsService.showSaveDialog({
+    suggestedName: `${getNoteTitle(path)}.html`,
+    filters: [{ name: "HTML", extensions: ["html"] }],
+  });
+  if (!target) {
+    return null;
+  }
+
+  await fsService.writeFileHandle(target.handle, html);
+  return target.name;
+}
+
+/**
+ * Copy a note to the clipboard as rich text, with its markdown as the plain
+ * text alternative, so pasting into email or a word processor keeps formatting
+ * Workspace images are inlined as data URLs.
+ * @param path - Note to copy, or the note unsaved content belongs to (used to resolve images)
+ * @param content - Markdown to copy instead of the saved note, e.g. unsaved edits or a selection
+ */
+export async function copyAsHtml(path: string, content?: string): Promise<void> {
+  if (!navigator.clipboard?.write || typeof ClipboardItem === "undefined") {
+    throw new AppError("unsupported", "This browser does not support copying rich text");
+  }
+
+  const markdown = (content === undefined ? fsService.readFile(path) : Promise.resolve(content)).then(
+    resolveCitations
+  );
+  const html = markdown.then(async (source) => inlineImages(await renderMarkdown(source), path));
+
+  // The item is created before anything is awaited: Safari only allows the
+  // write while the click that asked for it is still being handled
+  const item = new ClipboardItem({
+    "text/html": html.then((fragment) => new Blob([fragment], { type: "text/html" })),
+    "text/plain": markdown.then((source) => new Blob([splitFrontmatter(source).body], { type: "text/plain" })),
+  });
+  await navigator.clipboard.write([item]);
+}
@@ -0,0 +1,370 @@
+/**
+ * Image processing - downscales and re-encodes images before they are stored
+ * Runs in the browser via createImageBitmap and OffscreenCanvas; animated
+ * GIFs and SVGs are never touched.
+ */
+
+import type { ImageOutputFormat, ImageProcessingOptions } from "../types";
+import { AppError } from "./errors";
+
+const RASTER_TYPES = new Set(["image/png", "image/jpeg", "image/webp"]);
+
+const OUTPUT_TYPES: Record<Exclude<ImageOutputFormat, "original">, { type: string; extension: string }> = {
+  webp: { type: "image/webp", extension: "webp" },
+  jpeg: { type: "image/jpeg", extension: "jpg" },
+};
+
+function getImageType(file: File): string {
+  if (file.type) {
+    return file.type;
+  }
+
+  const extension = file.name.split(".").pop()?.toLowerCase();
+  return extension === "png" ? "image/png" : extension === "jpg" || extension === "jpeg" ? "image/jpeg" : "";
+}
+
+function withExtension(name: string, extension: string): string {
+  const dotIndex = name.lastIndexOf(".");
+  return `${dotIndex > 0 ? name.slice(0, dotIndex) : name}.${extension}`;
+}
+
+/**
+ * Downscale and re-encode an image according to the processing options
+ * Returns the original file when processing would not make it smaller
+ */
+export async function processImage(file: File, options: ImageProcessingOptions): Promise<File> {
+  const sourceType = getImageType(file);
+  if (!RASTER_TYPES.has(sourceType) || typeof OffscreenCanvas === "undefined") {
+    return file;
+  }
+
+  const bitmap = await createImageBitmap(file);
+
+  try {
+    const longestSide = Math.max(bitmap.width, bitmap.height);
+    const scale =
+      options.maxDimension !== null && longestSide > options.maxDimension
+        ? options.maxDimension / longestSide
+        : 1;
+
+    const output =
+      sourceType === "image/png" && options.pngFormat !== "original" ? OUTPUT_TYPES[options.pngFormat] : null;
+
+    if (scale === 1 && !output) {
+      return file;
+    }
+
+    const width = Math.max(1, Math.round(bitmap.width * scale));
+    const height = Math.max(1, Math.round(bitmap.height * scale));
+    const canvas = new OffscreenCanvas(width, height);
+    const context = canvas.getContext("2d");
+    if (!context) {
+      return file;
+    }
+
+    const type = output?.type ?? sourceType;
+    if (type === "image/jpeg") {
+      // JPEG has no alpha channel; flatten onto white instead of black
+      context.fillStyle = "#fff";
+      context.fillRect(0, 0, width, height);
+    }
+    context.drawImage(bitmap, 0, 0, width, height);
+
+    const blob = await canvas.convertToBlob({ type, quality: options.quality });
+
+    // Browsers without a WebP encoder fall back to PNG
+    if (blob.type !== type || blob.size >= file.size) {
+      return file;
+    }
+
+    const name = output ? withExtension(file.name, output.extension) : file.name;
+    return new File([blob], name, { type, lastModified: file.lastModified });
+  } finally {
+    bitmap.close();
+  }
+}
+
+const HEIC_TYPES = new Set(["image/heic", "image/heif", "image/heic-sequence", "image/heif-sequence"]);
+const HEIC_EXTENSION_PATTERN = /\.(heic|heif)$/i;
+const DEFAULT_HEIC_QUALITY = 0.9;
+
+/**
+ * Check for a HEIC/HEIF photo, the default format of iPhone cameras
+ */
+export function isHeicImage(file: File): boolean {
+  return HEIC_TYPES.has(file.type.toLowerCase()) || HEIC_EXTENSION_PATTERN.test(file.name);
+}
+
+/**
+ * Decode a HEIC photo with the browser's own decoder where there is one
+ * (Safari), otherwise with a WebAssembly decoder loaded on first use
+ */
+async function decodeHeic(file: File): Promise<ImageBitmap> {
+  try {
+    return await createImageBitmap(file);
+  } catch {
+    // No native HEIC support
+  }
+
+  const { default: heic2any } = await import("heic2any");
+  const decoded = await heic2any({ blob: file, toType: "image/png" });
+  return createImageBitmap(Array.isArray(decoded) ? decoded[0] : decoded);
+}
+
+/**
+ * Transcode a HEIC/HEIF photo to JPEG or WebP so every browser can show it
+ * Browsers without a WebP encoder get a JPEG.
+ */
+export async function convertHeicImage(
+  file: File,
+  format: Exclude<ImageOutputFormat, "original"> = "jpeg",
+  quality: number = DEFAULT_HEIC_QUALITY
+): Promise<File> {
+  if (typeof OffscreenCanvas === "undefined") {
+    throw new AppError("unsupported", "This browser cannot convert HEIC photos");
+  }
+
+  const bitmap = await decodeHeic(file);
+  try {
+    const canvas = new OffscreenCanvas(bitmap.width, bitmap.height);
+    const context = canvas.getContext("2d");
+    if (!context) {
+      throw new AppError("unsupported", "This browser cannot convert HEIC photos");
+    }
+    context.drawImage(bitmap, 0, 0);
+
+    let output = OUTPUT_TYPES[format];
+    let blob = await canvas.convertToBlob({ type: output.type, quality });
+    if (blob.type !== output.type) {
+      output = OUTPUT_TYPES.jpeg;
+      blob = await canvas.convertToBlob({ type: output.type, quality });
+    }
+
+    return new File([blob], withExtension(file.name, output.extension), {
+      type: output.type,
+      lastModified: file.lastModified,
+    });
+  } finally {
+    bitmap.close();
+  }
+}
+
+const JPEG_SOI = 0xffd8;
+const JPEG_SOS = 0xda;
+const JPEG_APP1 = 0xe1;
+const EXIF_ORIENTATION_TAG = 0x0112;
+
+/**
+ * JPEG segments that only carry metadata: EXIF/XMP (APP1), APP3-APP13
+ * (maker notes, IPTC) and comments. JFIF (APP0), the ICC profile (APP2) and
+ * Adobe's color transform (APP14) change how the image decodes, so they stay.
+ */
+function isJpegMetadataSegment(marker: number): boolean {
+  return marker === JPEG_APP1 || (marker >= 0xe3 && marker <= 0xed) || marker === 0xef || marker === 0xfe;
+}
+
+/**
+ * Read the orientation tag from an APP1 EXIF segment's payload
+ * @returns 1 (upright) when the segment has none
+ */
+function readExifOrientation(payload: Uint8Array): number {
+  const header = String.fromCharCode(...payload.subarray(0, 6));
+  if (header !== "Exif\0\0" || payload.length < 14) {
+    return 1;
+  }
+
+  const tiff = new DataView(payload.buffer, payload.byteOffset + 6, payload.length - 6);
+  const littleEndian = tiff.getUint16(0) === 0x4949;
+  const ifdOffset = tiff.getUint32(4, littleEndian);
+  if (ifdOffset + 2 > tiff.byteLength) {
+    return 1;
+  }
+
+  const entries = tiff.getUint16(ifdOffset, littleEndian);
+  for (let index = 0; index < entries; index += 1) {
+    const entry = ifdOffset + 2 + index * 12;
+    if (entry + 12 > tiff.byteLength) {
+      break;
+    }
+    if (tiff.getUint16(entry, littleEndian) === EXIF_ORIENTATION_TAG) {
+      return tiff.getUint16(entry + 8, littleEndian);
+    }
+  }
+  return 1;
+}
+
+/**
+ * An APP1 segment holding nothing but the orientation tag
+ */
+function createOrientationSegment(orientation: number): Uint8Array {
+  const segment = new Uint8Array(36);
+  const view = new DataView(segment.buffer);
+  view.setUint16(0, 0xff00 | JPEG_APP1);
+  view.setUint16(2, 34);
+  segment.set([0x45, 0x78, 0x69, 0x66, 0, 0], 4); // "Exif\0\0"
+  // Big-endian TIFF header, then one IFD with a single SHORT entry
+  segment.set([0x4d, 0x4d, 0x00, 0x2a, 0, 0, 0, 8], 10);
+  view.setUint16(18, 1);
+  view.setUint16(20, EXIF_ORIENTATION_TAG);
+  view.setUint16(22, 3);
+  view.setUint32(24, 1);
+  view.setUint16(28, orientation);
+  return segment;
+}
+
+function stripJpegMetadata(bytes: Uint8Array): Uint8Array | null {
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  if (bytes.length < 4 || view.getUint16(0) !== JPEG_SOI) {
+    return null;
+  }
+
+  const kept: Uint8Array[] = [bytes.subarray(0, 2)];
+  let orientation = 1;
+  let stripped = false;
+  let offset = 2;
+
+  while (offset + 4 <= bytes.length && bytes[offset] === 0xff) {
+    const marker = bytes[offset + 1];
+    if (marker === JPEG_SOS) {
+      break;
+    }
+
+    const end = offset + 2 + view.getUint16(offset + 2);
+    if (end > bytes.length) {
+      return null;
+    }
+
+    if (isJpegMetadataSegment(marker)) {
+      if (marker === JPEG_APP1 && orientation === 1) {
+        orientation = readExifOrientation(bytes.subarray(offset + 4, end));
+      }
+      stripped = true;
+    } else {
+      kept.push(bytes.subarray(offset, end));
+    }
+    offset = end;
+  }
+
+  if (!stripped) {
+    return null;
+  }
+
+  // Browsers rotate photos by their EXIF orientation; without it they'd show sideways
+  // (after the JFIF segment, which has to come first)
+  if (orientation !== 1) {
+    kept.splice(kept[1]?.[1] === 0xe0 ? 2 : 1, 0, createOrientationSegment(orientation));
+  }
+  kept.push(bytes.subarray(offset));
+  return concatBytes(kept);
+}
+
+const PNG_SIGNATURE = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+
+/** PNG chunks that only carry metadata: EXIF, text (XMP lives in iTXt) and the edit time */
+const PNG_METADATA_CHUNKS = new Set(["eXIf", "tEXt", "zTXt", "iTXt", "tIME"]);
+
+function stripPngMetadata(bytes: Uint8Array): Uint8Array | null {
+  if (bytes.length < 8 || PNG_SIGNATURE.some((byte, index) => bytes[index] !== byte)) {
+    return null;
+  }
+
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  const kept: Uint8Array[] = [bytes.subarray(0, 8)];
+  let stripped = false;
+  let offset = 8;
+
+  while (offset + 12 <= bytes.length) {
+    const end = offset + 12 + view.getUint32(offset);
+    if (end > bytes.length) {
+      return null;
+    }
+
+    const type = String.fromCharCode(...bytes.subarray(offset + 4, offset + 8));
+    if (PNG_METADATA_CHUNKS.has(type)) {
+      stripped = true;
+    } else {
+      kept.push(bytes.subarray(offset, end));
+    }
+    offset = end;
+  }
+
+  return stripped ? concatBytes([...kept, bytes.subarray(offset)]) : null;
+}
+
+/** VP8X flags announcing EXIF and XMP chunks */
+const WEBP_EXIF_FLAG = 0x08;
+const WEBP_XMP_FLAG = 0x04;
+
+function stripWebpMetadata(bytes: Uint8Array): Uint8Array | null {
+  const header = String.fromCharCode(...bytes.subarray(0, 4)) + String.fromCharCode(...bytes.subarray(8, 12));
+  if (bytes.length < 12 || header !== "RIFFWEBP") {
+    return null;
+  }
+
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  const kept: Uint8Array[] = [];
+  let stripped = false;
+  let offset = 12;
+
+  while (offset + 8 <= bytes.length) {
+    const size = view.getUint32(offset + 4, true);
+    // Chunks are padded to an even size
+    const end = Math.min(offset + 8 + size + (size % 2), bytes.length);
+    const type = String.fromCharCode(...bytes.subarray(offset, offset + 4));
+
+    if (type === "EXIF" || type === "XMP ") {
+      stripped = true;
+    } else if (type === "VP8X" && end - offset >= 9) {
+      const chunk = bytes.slice(offset, end);
+      chunk[8] &= ~(WEBP_EXIF_FLAG | WEBP_XMP_FLAG);
+      kept.push(chunk);
+    } else {
+      kept.push(bytes.subarray(offset, end));
+    }
+    offset = end;
+  }
+
+  if (!stripped) {
+    return null;
+  }
+
+  const body = concatBytes(kept);
+  const riff = new Uint8Array(12 + body.length);
+  riff.set(bytes.subarray(0, 12));
+  new DataView(riff.buffer).setUint32(4, 4 + body.length, true);
+  riff.set(body, 12);
+  return riff;
+}
+
+function concatBytes(parts: Uint8Array[]): Uint8Array {
+  const result = new Uint8Array(parts.reduce((total, part) => total + part.length, 0));
+  let offset = 0;
+  for (const part of parts) {
+    result.set(part, offset);
+    offset += part.length;
+  }
+  return result;
+}
+
+/**
+ * Remove EXIF, XMP and similar metadata (GPS position, camera, software)
+ * from a JPEG, PNG or WebP image without re-encoding it
+ * A JPEG's orientation is kept so it still displays upright. Other files,
+ * and images that carry no metadata, are returned unchanged.
+ */
+export async function stripImageMetadata(file: File): Promise<File> {
+  const sourceType = getImageType(file);
+  if (!RASTER_TYPES.has(sourceType)) {
+    return file;
+  }
+
+  const bytes = new Uint8Array(await file.arrayBuffer());
+  const stripped =
+    sourceType === "image/jpeg"
+      ? stripJpegMetadata(bytes)
+      : sourceType === "image/png"
+        ? stripPngMetadata(bytes)
+        : stripWebpMetadata(bytes);
+
+  return stripped ? new File([stripped], file.name, { type: sourceType, lastModified: file.lastModified }) : file;
+}
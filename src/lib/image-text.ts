@@ -0,0 +1,137 @@
+/**
+ * Image text - recognizes the text in uploaded screenshots and keeps it in a
+ * sidecar index, so images can be searched by what they show
+ * The recognizer (Tesseract, compiled to WebAssembly) is only loaded when a
+ * workspace turns OCR on. Its worker, core and language data are served by
+ * the app itself (copied into tesseract/ at build time), never from a CDN.
+ */
+
+import type { Worker } from "tesseract.js";
+import type { ImageTextMatch } from "../types";
+import * as fsService from "./fs-service";
+
+const IMAGE_TEXT_PATH = ".mdx/image-text.json";
+const OCR_LANGUAGE = "eng";
+const SNIPPET_RADIUS = 60;
+
+const TESSERACT_PATH = `${import.meta.env.BASE_URL}tesseract`;
+
+/** The recognizer is kept for a while after its last image, so a batch of uploads starts it once */
+const WORKER_IDLE_MS = 60_000;
+
+let worker: Promise<Worker> | null = null;
+let activeRecognitions = 0;
+let idleTimer: ReturnType<typeof setTimeout> | undefined;
+
+/** Formats the recognizer can read; SVGs already hold their text */
+const RECOGNIZABLE_PATTERN = /\.(?:png|jpe?g|webp|gif)$/i;
+
+async function readImageText(): Promise<Record<string, string>> {
+  try {
+    const parsed: unknown = JSON.parse(await fsService.readFile(IMAGE_TEXT_PATH));
+    return parsed && typeof parsed === "object" ? (parsed as Record<string, string>) : {};
+  } catch {
+    return {};
+  }
+}
+
+/**
+ * Run a read-modify-write of the index under a lock shared by every window,
+ * so images recognized at the same time don't drop each other's entries
+ */
+async function withIndexLock<T>(task: () => Promise<T>): Promise<T> {
+  if (!navigator.locks) {
+    return task();
+  }
+  return navigator.locks.request(`mdx:image-text:${await fsService.getWorkspaceId()}`, task);
+}
+
+/**
+ * Read the text in an image
+ * @returns The recognized text with runs of whitespace collapsed, or "" if there is none
+ */
+async function recognizeText(image: Blob): Promise<string> {
+  clearTimeout(idleTimer);
+  activeRecognitions += 1;
+  try {
+    worker ??= import("tesseract.js").then(({ createWorker }) =>
+      createWorker(OCR_LANGUAGE, undefined, {
+        workerPath: `${TESSERACT_PATH}/worker.min.js`,
+        corePath: `${TESSERACT_PATH}/core`,
+        langPath: `${TESSERACT_PATH}/lang`,
+      })
+    );
+    const pending = worker;
+    // A recognizer that failed to start is retried with the next image
+    void pending.catch(() => {
+      if (worker === pending) {
+        worker = null;
+      }
+    });
+
+    const { data } = await (await pending).recognize(image);
+    return data.text.replace(/\s+/g, " ").trim();
+  } finally {
+    activeRecognitions -= 1;
+    if (activeRecognitions === 0) {
+      idleTimer = setTimeout(() => {
+        const idle = worker;
+        worker = null;
+        void idle?.then((instance) => instance.terminate()).catch(() => undefined);
+      }, WORKER_IDLE_MS);
+    }
+  }
+}
+
+/**
+ * Recognize the text in a stored image and add it to the index
+ * @param path - Workspace-relative asset path
+ * @returns The recognized text; "" when the image holds none or can't be read
+ */
+export async function indexImageText(path: string): Promise<string> {
+  if (!RECOGNIZABLE_PATTERN.test(path)) {
+    return "";
+  }
+
+  const text = await recognizeText(await fsService.getFile(path));
+  if (text) {
+    await withIndexLock(async () => {
+      const index = await readImageText();
+      index[path] = text;
+      await fsService.writeFile(IMAGE_TEXT_PATH, `${JSON.stringify(index, null, 2)}\n`);
+    });
+  }
+  return text;
+}
+
+function getSnippet(text: string, index: number): string {
+  const start = Math.max(0, index - SNIPPET_RADIUS);
+  const end = Math.min(text.length, index + SNIPPET_RADIUS);
+  return `${start > 0 ? "…" : ""}${text.slice(start, end).trim()}${end < text.length ? "…" : ""}`;
+}
+
+/**
+ * Find images whose recognized text holds every word of a query, ignoring case
+ * Images deleted since they were recognized are left out.
+ * @returns Matches sorted by path
+ */
+export async function searchImageText(query: string): Promise<ImageTextMatch[]> {
+  const words = query.toLowerCase().split(/\s+/).filter(Boolean);
+  if (words.length === 0) {
+    return [];
+  }
+
+  const matches: ImageTextMatch[] = [];
+  for (const [path, text] of Object.entries(await readImageText())) {
+    const lowered = text.toLowerCase();
+    if (!words.every((word) => lowered.includes(word))) {
+      continue;
+    }
+    if ((await fsService.getFile(path).catch(() => null)) === null) {
+      continue;
+    }
+    matches.push({ path, snippet: getSnippet(text, lowered.indexOf(words[0])) });
+  }
+
+  return matches.sort((left, right) => left.path.localeCompare(right.path));
+}
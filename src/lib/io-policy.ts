@@ -0,0 +1,96 @@
+/**
+ * I/O policy - retries and timeouts for workspace reads and writes
+ * Workspaces on network mounts (SMB, NFS) can fail a read for a moment or
+ * stall without ever answering. Transient failures are retried with
+ * backoff; an operation that outlasts the timeout fails with a "timeout"
+ * error instead of hanging the app. Kept per device in localStorage, since
+ * how a mount behaves depends on the machine.
+ */
+
+import type { IoPolicy } from "../types";
+import { AppError } from "./errors";
+
+const POLICY_KEY = "mdx-io-policy";
+
+/** DOMException names that a retry can clear: a flaky read, or a file that changed mid-read */
+const TRANSIENT_ERRORS = new Set(["NotReadableError", "InvalidStateError", "UnknownError"]);
+
+export const DEFAULT_IO_POLICY: IoPolicy = {
+  retries: 3,
+  retry_delay_ms: 200,
+  timeout_ms: 30_000,
+};
+
+function toCount(value: unknown, fallback: number): number {
+  return typeof value === "number" && Number.isFinite(value) && value >= 0 ? Math.floor(value) : fallback;
+}
+
+function parseIoPolicy(value: unknown): IoPolicy {
+  const stored = (value && typeof value === "object" ? value : {}) as Partial<Record<keyof IoPolicy, unknown>>;
+  const defaults = DEFAULT_IO_POLICY;
+
+  return {
+    retries: toCount(stored.retries, defaults.retries),
+    retry_delay_ms: toCount(stored.retry_delay_ms, defaults.retry_delay_ms),
+    timeout_ms: toCount(stored.timeout_ms, defaults.timeout_ms),
+  };
+}
+
+export function getIoPolicy(): IoPolicy {
+  try {
+    return parseIoPolicy(JSON.parse(localStorage.getItem(POLICY_KEY) ?? "null"));
+  } catch {
+    return DEFAULT_IO_POLICY;
+  }
+}
+
+/**
+ * Change some of the policy, keeping the rest
+ * @returns The policy now in effect
+ */
+export function setIoPolicy(changes: Partial<IoPolicy>): IoPolicy {
+  const policy = parseIoPolicy({ ...getIoPolicy(), ...changes });
+  localStorage.setItem(POLICY_KEY, JSON.stringify(policy));
+  return policy;
+}
+
+function isTransient(error: unknown): boolean {
+  return error instanceof DOMException && TRANSIENT_ERRORS.has(error.name);
+}
+
+function withTimeout<T>(task: Promise<T>, timeoutMs: number, operation: string, path: string): Promise<T> {
+  if (timeoutMs === 0) {
+    return task;
+  }
+
+  let timer: ReturnType<typeof setTimeout> | undefined;
+  const timeout = new Promise<never>((_, reject) => {
+    timer = setTimeout(() => {
+      const seconds = Math.round(timeoutMs / 100) / 10;
+      reject(new AppError("timeout", `${operation} took longer than ${seconds}s`, { path }));
+    }, timeoutMs);
+  });
+  return Promise.race([task, timeout]).finally(() => clearTimeout(timer));
+}
+
+/**
+ * Run a workspace I/O task under the current policy
+ * Each attempt gets the full timeout. Timeouts aren't retried: a stalled
+ * attempt may still be running, and a mount that stalls rarely recovers
+ * within a retry.
+ * @param operation - What the task does, for the timeout message, e.g. "Reading the file"
+ */
+export async function withIoPolicy<T>(operation: string, path: string, task: () => Promise<T>): Promise<T> {
+  const policy = getIoPolicy();
+
+  for (let attempt = 0; ; attempt += 1) {
+    try {
+      return await withTimeout(task(), policy.timeout_ms, operation, path);
+    } catch (error) {
+      if (attempt >= policy.retries || !isTransient(error)) {
+        throw error;
+      }
+      await new Promise((resolve) => setTimeout(resolve, policy.retry_delay_ms * 2 ** attempt));
+    }
+  }
+}
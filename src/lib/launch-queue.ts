@@ -0,0 +1,68 @@
+/**
+ * Launch queue - files and URLs handed to the installed app by the OS
+ * With the manifest's "focus-existing" launch handler a second launch
+ * focuses the running window and arrives here instead of opening a new one
+ */
+
+import { joinPath } from "./pathUtils";
+
+/** Query parameter the manifest's web+mdx protocol handler fills in */
+export const DEEP_LINK_PARAM = "open";
+const DEEP_LINK_PATTERN = /^web\+mdx:(?:\/\/)?open\/?\?/i;
+
+export interface AppLaunch {
+  files: FileSystemFileHandle[];
+  folders: FileSystemDirectoryHandle[];
+  targetURL: string | null;
+}
+
+/**
+ * Receive every launch of the app, including the one that opened this window
+ * Does nothing outside an installed PWA or in browsers without launch handling
+ */
+export function listenForLaunches(handler: (launch: AppLaunch) => void): void {
+  if (!window.launchQueue) {
+    // Protocol links still open a browser tab on this page's URL
+    handler({ files: [], folders: [], targetURL: window.location.href });
+    return;
+  }
+
+  window.launchQueue.setConsumer((params) => {
+    handler({
+      files: params.files.filter((handle): handle is FileSystemFileHandle => handle.kind === "file"),
+      folders: params.files.filter((handle): handle is FileSystemDirectoryHandle => handle.kind === "directory"),
+      targetURL: params.targetURL ?? null,
+    });
+  });
+}
+
+/**
+ * Extract the note path from a web+mdx://open?path=... link the app was launched with
+ * @param targetURL - URL of the launch, with the link in its "open" parameter
+ * @returns Workspace-relative path, or null if there is no valid link
+ *
+ * @example
+ * parseDeepLink('https://app/?open=web%2Bmdx%3A%2F%2Fopen%3Fpath%3Dprojects%2Fideas.md') // → 'projects/ideas.md'
+ */
+export function parseDeepLink(targetURL: string): string | null {
+  let link: string | null;
+  try {
+    link = new URL(targetURL).searchParams.get(DEEP_LINK_PARAM);
+  } catch {
+    return null;
+  }
+
+  if (!link || !DEEP_LINK_PATTERN.test(link)) {
+    return null;
+  }
+
+  const path = new URLSearchParams(link.replace(DEEP_LINK_PATTERN, "")).get("path");
+
+  // Links come from other apps: reject anything that could leave the workspace
+  if (!path || path.includes("\\") || /[\u0000-\u001f]/.test(path)) {
+    return null;
+  }
+
+  const resolved = joinPath(".", path);
+  return resolved ? resolved : null;
+}
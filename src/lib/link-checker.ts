@@ -0,0 +1,336 @@
+/**
+ * Link checker - finds links to missing notes, files and headings
+ * Notes are checked concurrently; each target note is parsed for headings
+ * at most once per run
+ */
+
+import type { BrokenLink, LinkCheckReport, OutlineHeading } from "../types";
+import { isNotePath, stripNoteExtension } from "./filenameUtils";
+import * as fsService from "./fs-service";
+import { findLocalLinks, parseHeadings, slugifyHeading } from "./markdownUtils";
+import type { MarkdownLink } from "./markdownUtils";
+import { getNoteIndex, getTitleLookup } from "./note-index";
+import { runWithConcurrency } from "./operations";
+import { getParentPath, getRelativePath, joinPath } from "./pathUtils";
+
+const CHECK_CONCURRENCY = 8;
+const MAX_SUGGESTION_DISTANCE = 3;
+
+/**
+ * Lookups wikilink resolution needs, over any kind of indexed file
+ */
+export interface WikilinkIndex<T> {
+  /** Lowercased path -> file */
+  byLowerPath: Map<string, T>;
+
+  /** Lowercased file name (and note name without extension) -> files */
+  byName: Map<string, T[]>;
+
+  /** Lowercased frontmatter title or alias -> notes */
+  byTitle?: Map<string, T[]>;
+}
+
+export interface WorkspaceIndex {
+  files: Set<string>;
+  folders: Set<string>;
+  byLowerPath: Map<string, string>;
+
+  /** Lowercased file name (and note name without extension) -> paths */
+  byName: Map<string, string[]>;
+
+  /** Lowercased frontmatter title or alias -> note paths */
+  byTitle: Map<string, string[]>;
+}
+
+export interface ResolvedTarget {
+  path: string | null;
+  anchor: string;
+}
+
+function getName(path: string): string {
+  return path.split("/").pop() ?? path;
+}
+
+function levenshtein(a: string, b: string): number {
+  let previous = Array.from({ length: b.length + 1 }, (_, index) => index);
+
+  for (let i = 1; i <= a.length; i += 1) {
+    const current = [i];
+    for (let j = 1; j <= b.length; j += 1) {
+      current[j] = Math.min(
+        previous[j] + 1,
+        current[j - 1] + 1,
+        previous[j - 1] + (a[i - 1] === b[j - 1] ? 0 : 1)
+      );
+    }
+    previous = current;
+  }
+
+  return previous[b.length];
+}
+
+/**
+ * Pick the candidate closest to a value, ignoring case
+ * Only near misses count: a few edits, and never a complete rewrite
+ */
+function findClosest(
+  value: string,
+  candidates: string[],
+  toKey: (candidate: string) => string = (candidate) => candidate
+): string | null {
+  const target = value.toLowerCase();
+  const limit = Math.min(Math.max(1, Math.floor(target.length / 3)), MAX_SUGGESTION_DISTANCE, target.length - 1);
+  let best: string | null = null;
+  let bestDistance = limit + 1;
+
+  for (const candidate of candidates) {
+    const distance = levenshtein(target, toKey(candidate).toLowerCase());
+    if (distance < bestDistance) {
+      best = candidate;
+      bestDistance = distance;
+    }
+  }
+
+  return best;
+}
+
+/**
+ * Index workspace paths for link resolution
+ * @param titles - Lowercased note titles and aliases -> paths, for wikilinks by title
+ */
+export function indexPaths(
+  files: Iterable<string>,
+  folders: Iterable<string> = [],
+  titles: Map<string, string[]> = new Map()
+): WorkspaceIndex {
+  const index: WorkspaceIndex = {
+    files: new Set(),
+    folders: new Set(folders),
+    byLowerPath: new Map(),
+    byName: new Map(),
+    byTitle: titles,
+  };
+
+  for (const file of files) {
+    index.files.add(file);
+    index.byLowerPath.set(file.toLowerCase(), file);
+
+    const name = getName(file).toLowerCase();
+    const names = isNotePath(name) ? [name, stripNoteExtension(name)] : [name];
+    for (const key of names) {
+      index.byName.set(key, [...(index.byName.get(key) ?? []), file]);
+    }
+  }
+
+  return index;
+}
+
+/**
+ * Index every file and folder of the open workspace, with note titles
+ */
+export async function buildWorkspaceIndex(signal?: AbortSignal): Promise<WorkspaceIndex> {
+  const files: string[] = [];
+  const folders: string[] = [];
+
+  for await (const entry of fsService.walkDirectory(".", { signal })) {
+    (entry.handle.kind === "directory" ? folders : files).push(entry.relativePath);
+  }
+
+  const { notes } = await getNoteIndex();
+  return indexPaths(files, folders, getTitleLookup(notes));
+}
+
+function splitAnchor(target: string): { path: string; anchor: string } {
+  const hashIndex = target.indexOf("#");
+  return hashIndex === -1
+    ? { path: target, anchor: "" }
+    : { path: target.slice(0, hashIndex), anchor: target.slice(hashIndex + 1) };
+}
+
+function resolveMarkdownLink(notePath: string, target: string, index: WorkspaceIndex): ResolvedTarget {
+  const { path, anchor } = splitAnchor(target);
+  if (!path) {
+    return { path: notePath, anchor };
+  }
+
+  let decoded = path;
+  try {
+    decoded = decodeURI(path);
+  } catch {
+    // Keep malformed escapes as written
+  }
+
+  const resolved = decoded.startsWith("/")
+    ? joinPath(".", decoded)
+    : joinPath(getParentPath(notePath), decoded);
+  const exists = resolved !== null && (index.files.has(resolved) || index.folders.has(resolved));
+
+  return { path: exists ? resolved : null, anchor };
+}
+
+/**
+ * Resolve a wikilink target the way Obsidian does: a path from the root, then
+ * one relative to the note, then a file name (a match in the note's folder
+ * first, otherwise the shortest path), then a note title or alias
+ * Shared by the link checker and the Obsidian importer so both read a vault alike.
+ * @param target - Link target without its #anchor
+ * @param pathOf - Path of an indexed file
+ */
+export function resolveWikilinkTarget<T>(
+  notePath: string,
+  target: string,
+  index: WikilinkIndex<T>,
+  pathOf: (file: T) => string
+): T | null {
+  const name = target.trim();
+  for (const candidate of [joinPath(".", name), joinPath(getParentPath(notePath), name)]) {
+    if (candidate === null) {
+      continue;
+    }
+    for (const withExtension of [candidate, `${candidate}.md`]) {
+      const match = index.byLowerPath.get(withExtension.toLowerCase());
+      if (match !== undefined) {
+        return match;
+      }
+    }
+  }
+
+  // File names first, then titles and aliases, which survive renames
+  const matches = index.byName.get(getName(name).toLowerCase()) ?? index.byTitle?.get(name.toLowerCase()) ?? [];
+  const noteFolder = getParentPath(notePath);
+  return (
+    matches.find((file) => getParentPath(pathOf(file)) === noteFolder) ??
+    [...matches].sort((a, b) => pathOf(a).length - pathOf(b).length)[0] ??
+    null
+  );
+}
+
+function resolveWikilink(notePath: string, target: string, index: WorkspaceIndex): ResolvedTarget {
+  const { path, anchor } = splitAnchor(target);
+  if (!path.trim()) {
+    return { path: notePath, anchor };
+  }
+  return { path: resolveWikilinkTarget(notePath, path, index, (file) => file), anchor };
+}
+
+/**
+ * Resolve a link in a note to the workspace path it points at
+ * The path is null when the target doesn't exist
+ */
+export function resolveLink(notePath: string, link: MarkdownLink, index: WorkspaceIndex): ResolvedTarget {
+  return link.isWikilink
+    ? resolveWikilink(notePath, link.target, index)
+    : resolveMarkdownLink(notePath, link.target, index);
+}
+
+function getStem(path: string): string {
+  return getName(path).replace(/\.[^.]*$/, "");
+}
+
+function suggestFile(notePath: string, link: MarkdownLink, index: WorkspaceIndex): string | null {
+  const { path, anchor } = splitAnchor(link.target);
+  const suffix = anchor ? `#${anchor}` : "";
+
+  if (link.isWikilink) {
+    const notes = [...index.files].filter((file) => isNotePath(file));
+    const closest = findClosest(getName(path), notes, getStem);
+    return closest ? `${getStem(closest)}${suffix}` : null;
+  }
+
+  // Wrong case, then the same file name in another folder, then a near-miss name
+  const resolved = joinPath(getParentPath(notePath), path);
+  const match =
+    (resolved === null ? undefined : index.byLowerPath.get(resolved.toLowerCase())) ??
+    [...(index.byName.get(getName(path).toLowerCase()) ?? [])].sort((a, b) => a.length - b.length)[0] ??
+    findClosest(getStem(path), [...index.files], getStem);
+
+  return match ? `${getRelativePath(notePath, match)}${suffix}` : null;
+}
+
+function suggestAnchor(link: MarkdownLink, headings: OutlineHeading[]): string | null {
+  const { path, anchor } = splitAnchor(link.target);
+
+  if (link.isWikilink) {
+    const closest = findClosest(anchor, headings.map((heading) => heading.text));
+    return closest ? `${path}#${closest}` : null;
+  }
+
+  const closest = findClosest(anchor, headings.map((heading) => heading.slug));
+  return closest ? `${path}#${closest}` : null;
+}
+
+/**
+ * Check the links of every note in a folder, or of a single note
+ * @param scope - Workspace folder or note path ("." for the whole workspace)
+ * @param signal - Stops the check between notes
+ */
+export async function checkLinks(scope: string, signal?: AbortSignal): Promise<LinkCheckReport> {
+  const index = await buildWorkspaceIndex(signal);
+  const scopePath = await fsService.getRelativeWorkspacePath(scope);
+
+  const notes = index.files.has(scopePath)
+    ? [scopePath]
+    : [...index.files].filter(
+        (file) => isNotePath(file) && (scopePath === "." || file.startsWith(`${scopePath}/`))
+      );
+
+  const headingCache = new Map<string, Promise<OutlineHeading[]>>();
+  const getHeadings = (path: string) => {
+    let headings = headingCache.get(path);
+    if (!headings) {
+      headings = fsService.readFile(path).then(parseHeadings);
+      headingCache.set(path, headings);
+    }
+    return headings;
+  };
+
+  const brokenByNote = new Map<string, BrokenLink[]>();
+  let checkedLinks = 0;
+
+  await runWithConcurrency(notes, CHECK_CONCURRENCY, async (notePath) => {
+    signal?.throwIfAborted();
+    const links = findLocalLinks(await fsService.readFile(notePath));
+    const broken: BrokenLink[] = [];
+    checkedLinks += links.length;
+
+    for (const link of links) {
+      const resolved = resolveLink(notePath, link, index);
+
+      if (!resolved.path) {
+        broken.push({
+          path: notePath,
+          line: link.line,
+          target: link.target,
+          reason: "missing-file",
+          suggestion: suggestFile(notePath, link, index),
+        });
+        continue;
+      }
+
+      // Block references (#^id) have no heading to check against
+      if (!resolved.anchor || resolved.anchor.startsWith("^") || !isNotePath(resolved.path)) {
+        continue;
+      }
+
+      const headings = await getHeadings(resolved.path);
+      const slug = link.isWikilink ? slugifyHeading(resolved.anchor) : resolved.anchor.toLowerCase();
+      if (!headings.some((heading) => heading.slug === slug)) {
+        broken.push({
+          path: notePath,
+          line: link.line,
+          target: link.target,
+          reason: "missing-anchor",
+          suggestion: suggestAnchor(link, headings),
+        });
+      }
+    }
+
+    brokenByNote.set(notePath, broken);
+  });
+
+  return {
+    checked_notes: notes.length,
+    checked_links: checkedLinks,
+    broken: notes.flatMap((note) => brokenByNote.get(note) ?? []),
+  };
+}
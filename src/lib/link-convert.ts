@@ -0,0 +1,208 @@
+/**
+ * Link conversion - rewrites links between notes from [[wikilinks]] to
+ * relative markdown links or back, so a vault can move between tools
+ * Links that can't be converted without losing meaning are left as written:
+ * note embeds, block references and anchors to headings that don't exist.
+ */
+
+import type { LinkConversion, LinkConversionChange, OutlineHeading } from "../types";
+import { diffText } from "./diff";
+import { isNotePath, stripNoteExtension } from "./filenameUtils";
+import * as fsService from "./fs-service";
+import { buildWorkspaceIndex, resolveLink } from "./link-checker";
+import type { WorkspaceIndex } from "./link-checker";
+import { isExternalLink, parseHeadings, rewriteInlineLinks, rewriteWikilinks, slugifyHeading } from "./markdownUtils";
+import { runWithConcurrency } from "./operations";
+import { formatLinkTarget, getRelativePath } from "./pathUtils";
+
+const CONVERT_CONCURRENCY = 8;
+
+interface ConvertResult {
+  content: string;
+
+  /** Number of links rewritten */
+  count: number;
+}
+
+/** Characters a wikilink target or label can't hold */
+const WIKILINK_RESERVED_PATTERN = /[[\]|\r\n]/;
+
+interface ConvertContext {
+  index: WorkspaceIndex;
+
+  /** Headings of the notes linked to with an anchor, by path */
+  headings: Map<string, OutlineHeading[]>;
+}
+
+/**
+ * Replace [[wikilinks]] with relative markdown links
+ * Embedded images become markdown images; embedded notes have no markdown form
+ */
+function toMarkdownLinks(content: string, notePath: string, context: ConvertContext): ConvertResult {
+  let count = 0;
+  const converted = rewriteWikilinks(content, (target, label, isEmbed) => {
+    const { path, anchor } = resolveLink(notePath, { target, isWikilink: true, line: 0 }, context.index);
+    if (path === null || anchor.startsWith("^") || (isEmbed && isNotePath(path))) {
+      return null;
+    }
+
+    const fragment = anchor ? `#${slugifyHeading(anchor)}` : "";
+    const sameNote = !target.split("#")[0].trim();
+    const destination = sameNote ? fragment : formatLinkTarget(`${getRelativePath(notePath, path)}${fragment}`);
+    const text = label ?? target.replace("#", " > ");
+    count += 1;
+    return `${isEmbed ? "!" : ""}[${text}](${destination})`;
+  });
+
+  return { content: converted, count };
+}
+
+/**
+ * Name a note the shortest way a wikilink still resolves: its file name when
+ * no other file shares it, otherwise its workspace path
+ */
+function getWikilinkName(path: string, index: WorkspaceIndex): string {
+  const name = stripNoteExtension(path.split("/").pop() ?? path);
+  return (index.byName.get(name.toLowerCase()) ?? []).length === 1 ? name : stripNoteExtension(path);
+}
+
+/**
+ * Resolve a markdown link to the note and heading a wikilink would name
+ * @returns Null when the link isn't to a note, or its anchor matches no heading
+ */
+function resolveNoteLink(
+  notePath: string,
+  destination: string,
+  context: ConvertContext
+): { path: string; heading: string | null } | null {
+  if (isExternalLink(destination) && !destination.startsWith("#")) {
+    return null;
+  }
+
+  const { path, anchor } = resolveLink(notePath, { target: destination, isWikilink: false, line: 0 }, context.index);
+  if (path === null || !isNotePath(path)) {
+    return null;
+  }
+  if (!anchor) {
+    return { path, heading: null };
+  }
+
+  const heading = context.headings.get(path)?.find((candidate) => candidate.slug === anchor.toLowerCase());
+  return heading ? { path, heading: heading.text } : null;
+}
+
+/**
+ * Replace markdown links to notes with [[wikilinks]]
+ * Images stay markdown images, which every tool renders
+ */
+function toWikilinks(content: string, notePath: string, context: ConvertContext): ConvertResult {
+  let count = 0;
+  const converted = rewriteInlineLinks(content, (text, destination, isImage) => {
+    const resolved = isImage ? null : resolveNoteLink(notePath, destination, context);
+    if (!resolved || WIKILINK_RESERVED_PATTERN.test(`${text}${resolved.heading ?? ""}`)) {
+      return null;
+    }
+
+    const sameNote = resolved.path === notePath && destination.startsWith("#");
+    const name = sameNote ? "" : getWikilinkName(resolved.path, context.index);
+    if (!name && resolved.heading === null) {
+      return null;
+    }
+
+    const target = resolved.heading === null ? name : `${name}#${resolved.heading}`;
+    count += 1;
+    return !text.trim() || text === name || text === target ? `[[${target}]]` : `[[${target}|${text}]]`;
+  });
+
+  return { content: converted, count };
+}
+
+/**
+ * Read the headings of every note a markdown link with an anchor points at
+ */
+async function loadLinkedHeadings(
+  content: string,
+  notePath: string,
+  context: ConvertContext,
+  cache: Map<string, Promise<OutlineHeading[]>>
+): Promise<void> {
+  const paths = new Set<string>();
+  rewriteInlineLinks(content, (_text, destination, isImage) => {
+    if (!isImage && destination.includes("#") && (!isExternalLink(destination) || destination.startsWith("#"))) {
+      const link = { target: destination, isWikilink: false, line: 0 };
+      const { path } = resolveLink(notePath, link, context.index);
+      if (path !== null && isNotePath(path)) {
+        paths.add(path);
+      }
+    }
+    return null;
+  });
+
+  for (const path of paths) {
+    let headings = cache.get(path);
+    if (!headings) {
+      headings = fsService.readFile(path).then(parseHeadings);
+      cache.set(path, headings);
+    }
+    context.headings.set(path, await headings);
+  }
+}
+
+/**
+ * Convert the links between notes in a folder, or in a single note
+ * A note is only written if it didn't change while it was converted.
+ * @param scope - Workspace folder or note path ("." for the whole workspace)
+ * @param dryRun - Only report what would change
+ * @returns Notes with converted links, sorted by path
+ */
+export async function convertLinks(
+  scope: string,
+  direction: LinkConversion,
+  dryRun: boolean = false,
+  signal?: AbortSignal
+): Promise<LinkConversionChange[]> {
+  const index = await buildWorkspaceIndex(signal);
+  const scopePath = await fsService.getRelativeWorkspacePath(scope);
+  const notes = index.files.has(scopePath)
+    ? [scopePath]
+    : [...index.files].filter(
+        (file) => isNotePath(file) && (scopePath === "." || file.startsWith(`${scopePath}/`))
+      );
+
+  const headingCache = new Map<string, Promise<OutlineHeading[]>>();
+  const changes: LinkConversionChange[] = [];
+  const writes: Array<{ path: string; hash: string; content: string; converted: string }> = [];
+
+  await runWithConcurrency(notes, CONVERT_CONCURRENCY, async (notePath) => {
+    signal?.throwIfAborted();
+    const hash = await fsService.getFileHash(notePath);
+    const content = await fsService.readFile(notePath);
+
+    let result: ConvertResult;
+    if (direction === "to-markdown") {
+      result = toMarkdownLinks(content, notePath, { index, headings: new Map() });
+    } else {
+      const context: ConvertContext = { index, headings: new Map() };
+      await loadLinkedHeadings(content, notePath, context, headingCache);
+      result = toWikilinks(content, notePath, context);
+    }
+
+    // Rewriting joins lines with \n, so keep the note's own line endings
+    const converted = content.includes("\r\n") ? result.content.replace(/\n/g, "\r\n") : result.content;
+    if (result.count === 0 || converted === content) {
+      return;
+    }
+
+    writes.push({ path: notePath, hash, content, converted });
+    changes.push({ path: notePath, converted: result.count, hunks: diffText(content, converted) });
+  });
+
+  // Notes are only written once every conversion succeeded, and not cancellable from here
+  if (!dryRun) {
+    for (const { path, hash, content, converted } of writes) {
+      await fsService.applyEdits(path, [{ start: 0, end: content.length, replacement: converted }], hash);
+    }
+  }
+
+  return changes.sort((left, right) => left.path.localeCompare(right.path));
+}
@@ -0,0 +1,110 @@
+/**
+ * Link rebasing - keeps the relative links of moved notes pointing at the
+ * same files once the notes sit in another folder
+ */
+
+import { isNotePath } from "./filenameUtils";
+import * as fsService from "./fs-service";
+import { isExternalLink, rewriteNoteLinkDestinations } from "./markdownUtils";
+import { getParentPath, getRelativePath, joinPath } from "./pathUtils";
+
+/** The file or folder a rename moved, as workspace-relative paths */
+interface MovedPath {
+  oldPath: string;
+  newPath: string;
+}
+
+function isSameOrInside(path: string, folder: string): boolean {
+  return path === folder || path.startsWith(`${folder}/`);
+}
+
+/**
+ * Rewrite one note's relative links for its new location
+ * Targets that moved along with it (inside a moved folder) keep their link.
+ * @returns The rewritten content, or null if no link changed
+ */
+function rebaseNoteLinks(content: string, oldNotePath: string, newNotePath: string, moved: MovedPath): string | null {
+  let changed = false;
+  const rebased = rewriteNoteLinkDestinations(content, (destination) => {
+    // URLs, same-note anchors and workspace-absolute links don't depend on where the note is
+    if (isExternalLink(destination) || destination.startsWith("/")) {
+      return null;
+    }
+
+    const hashIndex = destination.indexOf("#");
+    const path = hashIndex === -1 ? destination : destination.slice(0, hashIndex);
+    const anchor = hashIndex === -1 ? "" : destination.slice(hashIndex);
+    const encoded = /%[0-9a-f]{2}/i.test(path);
+    let decoded = path;
+    try {
+      decoded = encoded ? decodeURI(path) : path;
+    } catch {
+      return null;
+    }
+
+    const target = joinPath(getParentPath(oldNotePath), decoded);
+    if (target === null) {
+      return null;
+    }
+
+    const movedTarget = isSameOrInside(target, moved.oldPath)
+      ? `${moved.newPath}${target.slice(moved.oldPath.length)}`
+      : target;
+    if (joinPath(getParentPath(newNotePath), decoded) === movedTarget) {
+      return null;
+    }
+
+    const relative = getRelativePath(newNotePath, movedTarget);
+    changed = true;
+    return `${encoded ? encodeURI(relative) : relative}${anchor}`;
+  });
+
+  if (!changed) {
+    return null;
+  }
+  return content.includes("\r\n") ? rebased.replace(/\n/g, "\r\n") : rebased;
+}
+
+/**
+ * Rebase the relative links of the notes a rename moved: the note itself, or
+ * every note inside a moved folder
+ * Call after the move; renames within a folder change no relative link.
+ * @returns Workspace-relative paths of the notes that were rewritten
+ */
+export async function rebaseMovedLinks(oldPath: string, newPath: string): Promise<string[]> {
+  const moved: MovedPath = {
+    oldPath: await fsService.getRelativeWorkspacePath(oldPath),
+    newPath: await fsService.getRelativeWorkspacePath(newPath),
+  };
+  if (getParentPath(moved.oldPath) === getParentPath(moved.newPath)) {
+    return [];
+  }
+
+  const notes: string[] = [];
+  const isFile = (await fsService.getFile(moved.newPath).catch(() => null)) !== null;
+  if (isFile) {
+    if (isNotePath(moved.newPath)) {
+      notes.push(moved.newPath);
+    }
+  } else {
+    for await (const entry of fsService.walkDirectory(moved.newPath)) {
+      if (entry.handle.kind === "file" && isNotePath(entry.relativePath)) {
+        notes.push(entry.relativePath);
+      }
+    }
+  }
+
+  const rewritten: string[] = [];
+  for (const notePath of notes) {
+    const oldNotePath = `${moved.oldPath}${notePath.slice(moved.newPath.length)}`;
+    const hash = await fsService.getFileHash(notePath);
+    const content = await fsService.readFile(notePath);
+    const rebased = rebaseNoteLinks(content, oldNotePath, notePath, moved);
+    if (rebased !== null) {
+      await fsService.applyEdits(notePath, [{ start: 0, end: content.length, replacement: rebased }], hash);
+      rewritten.push(notePath);
+    }
+  }
+
+  return rewritten;
+}
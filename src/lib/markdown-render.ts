@@ -0,0 +1,113 @@
+/**
+ * Markdown rendering - the one markdown-to-HTML pipeline used by exports
+ * GFM (tables, task lists, strikethrough, autolinks) plus footnotes; fenced
+ * code keeps its language-* class for highlighting by the page that shows it,
+ * and headings get GitHub-style ids so #heading links work.
+ * Large documents are rendered in a worker so typing never waits on them.
+ */
+
+import { Marked } from "marked";
+import type { MarkedExtension } from "marked";
+import markedFootnote from "marked-footnote";
+import type { RenderMarkdownOptions } from "../types";
+import { createSlugger, splitFrontmatter, stripInlineMarkdown } from "./markdownUtils";
+
+/** Documents at least this long (in characters) are rendered off the main thread */
+const WORKER_THRESHOLD = 200_000;
+
+/** Reset per document so ids are unique within, not across, renders */
+let slugify = createSlugger();
+
+/**
+ * Give headings the same GitHub-style ids the outline and link checker use
+ */
+const headingIds: MarkedExtension = {
+  hooks: {
+    preprocess(markdown) {
+      slugify = createSlugger();
+      return markdown;
+    },
+  },
+  renderer: {
+    heading({ tokens, depth, text }) {
+      const slug = slugify(stripInlineMarkdown(text));
+      const id = slug ? ` id="${slug}"` : "";
+      return `<h${depth}${id}>${this.parser.parseInline(tokens)}</h${depth}>\n`;
+    },
+  },
+};
+
+const renderer = new Marked({ gfm: true }).use(headingIds);
+const footnoteRenderer = new Marked({ gfm: true }).use(markedFootnote(), headingIds);
+
+export interface RenderRequest {
+  id: number;
+  content: string;
+  options: RenderMarkdownOptions;
+}
+
+export type RenderResponse = { id: number; html: string } | { id: number; error: string };
+
+let worker: Worker | null = null;
+let nextRequestId = 0;
+const pendingRenders = new Map<number, { resolve: (html: string) => void; reject: (error: Error) => void }>();
+
+/**
+ * Render markdown synchronously on the calling thread
+ */
+export function renderMarkdownSync(content: string, options: RenderMarkdownOptions = {}): string {
+  const body = (options.stripFrontmatter ?? true) ? splitFrontmatter(content).body : content;
+  const marked = (options.footnotes ?? true) ? footnoteRenderer : renderer;
+  return marked.parse(body, { async: false });
+}
+
+function getWorker(): Worker {
+  if (worker) {
+    return worker;
+  }
+
+  worker = new Worker(new URL("./markdown-render.worker.ts", import.meta.url), { type: "module" });
+  worker.addEventListener("message", (event: MessageEvent<RenderResponse>) => {
+    const pending = pendingRenders.get(event.data.id);
+    pendingRenders.delete(event.data.id);
+    if (!pending) {
+      return;
+    }
+
+    if ("error" in event.data) {
+      pending.reject(new Error(event.data.error));
+    } else {
+      pending.resolve(event.data.html);
+    }
+  });
+  worker.addEventListener("error", (event) => {
+    // A broken worker fails everything in flight; the next render starts a new one
+    for (const pending of pendingRenders.values()) {
+      pending.reject(new Error(event.message || "Markdown worker failed"));
+    }
+    pendingRenders.clear();
+    worker?.terminate();
+    worker = null;
+  });
+
+  return worker;
+}
+
+/**
+ * Render markdown to an HTML fragment
+ * Small documents render inline; large ones in a shared worker
+ */
+export async function renderMarkdown(content: string, options: RenderMarkdownOptions = {}): Promise<string> {
+  if (content.length < WORKER_THRESHOLD || typeof Worker === "undefined") {
+    return renderMarkdownSync(content, options);
+  }
+
+  const id = nextRequestId;
+  nextRequestId += 1;
+
+  return new Promise((resolve, reject) => {
+    pendingRenders.set(id, { resolve, reject });
+    const request: RenderRequest = { id, content, options };
+    getWorker().postMessage(request);
+  });
+}
@@ -0,0 +1,19 @@
+/**
+ * Markdown render worker - runs renderMarkdownSync off the main thread
+ */
+
+import { renderMarkdownSync } from "./markdown-render";
+import type { RenderRequest, RenderResponse } from "./markdown-render";
+
+self.addEventListener("message", (event: MessageEvent<RenderRequest>) => {
+  const { id, content, options } = event.data;
+  let response: RenderResponse;
+
+  try {
+    response = { id, html: renderMarkdownSync(content, options) };
+  } catch (error) {
+    response = { id, error: error instanceof Error ? error.message : String(error) };
+  }
+
+  self.postMessage(response);
+});
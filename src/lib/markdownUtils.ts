@@ -0,0 +1,624 @@
+/**
+ * Markdown text utilities shared by the export and analysis features
+ */
+
+import type {
+  CodeBlock,
+  FrontmatterScalar,
+  FrontmatterValue,
+  NoteReference,
+  OutlineHeading,
+  ReferenceKind,
+} from "../types";
+import { stripNoteExtension } from "./filenameUtils";
+import { formatLinkTarget } from "./pathUtils";
+
+const FRONTMATTER_PATTERN = /^---\r?\n(?:([\s\S]*?)\r?\n)?---[ \t]*(?:\r?\n|$)/;
+const INLINE_LINK_PATTERN = /(!?\[[^\]]*\]\()(<[^>]+>|[^)\s]+)((?:\s+"[^"]*")?\))/g;
+const EXTERNAL_LINK_PATTERN = /^([a-z][a-z0-9+.-]*:|\/\/|#)/i;
+const CODE_FENCE_PATTERN = /^( {0,3})(`{3,}|~{3,})(.*)$/;
+const ATX_HEADING_PATTERN = /^\s{0,3}(#{1,6})(?:[ \t]+(.*?))?(?:[ \t]+#+)?[ \t]*$/;
+const SETEXT_UNDERLINE_PATTERN = /^\s{0,3}(=+|-+)[ \t]*$/;
+const BLOCK_START_PATTERN = /^\s{0,3}([-*+>]|\d+[.)]|#)/;
+const WIKILINK_PATTERN = /!?\[\[([^\]|#]*)(#[^\]|]*)?(?:\|([^\]]*))?\]\]/g;
+const INLINE_CODE_PATTERN = /`[^`]*`/g;
+const FOOTNOTE_DEFINITION_PATTERN = /^ {0,3}\[\^([^\]\s]+)\]:[ \t]*(.*)$/;
+const LINK_DEFINITION_PATTERN = /^ {0,3}\[([^\]^][^\]]*)\]:[ \t]*(<[^>]*>|\S+)(?:[ \t]+(?:"[^"]*"|'[^']*'|\([^)]*\)))?[ \t]*$/;
+const FOOTNOTE_USAGE_PATTERN = /\[\^([^\]\s]+)\]/g;
+const FULL_REFERENCE_PATTERN = /!?\[([^\]]*)\]\[([^\]]*)\]/g;
+const SHORTCUT_REFERENCE_PATTERN = /!?\[([^\]^][^\]]*)\](?![(:[])/g;
+const FRONTMATTER_FIELD_PATTERN = /^([\w-]+):[ \t]*(.*)$/;
+const FRONTMATTER_ITEM_PATTERN = /^[ \t]*-(?:[ \t]+(.*))?$/;
+const NUMBER_PATTERN = /^-?\d+(?:\.\d+)?$/;
+const INLINE_TAG_PATTERN = /(?:^|\s)#([\p{L}\p{N}_/-]+)/gu;
+const TASK_PATTERN = /^\s*(?:[-*+]|\d+[.)])\s+\[([ xX])\](?:\s+(.*))?$/;
+const DUE_DATE_PATTERN = /(?:📅|🗓️?|\bdue::?|@due\()\s*(\d{4}-\d{2}-\d{2})/u;
+
+export interface ProseLine {
+  text: string;
+
+  /** 1-based line number in the note */
+  line: number;
+}
+
+export interface MarkdownLink {
+  /** Destination as written (path, optionally with #anchor) */
+  target: string;
+
+  /** True for [[wikilinks]] */
+  isWikilink: boolean;
+
+  /** 1-based line number in the note */
+  line: number;
+}
+
+export interface MarkdownTask {
+  /** Task text after the checkbox, as written */
+  text: string;
+
+  completed: boolean;
+
+  /** Due date (YYYY-MM-DD) from a "📅 date", "due: date", "[due:: date]" or "@due(date)" annotation */
+  due: string | null;
+
+  /** 1-based line number in the note */
+  line: number;
+}
+
+export interface FrontmatterSplit {
+  /** Raw YAML between the --- fences (null if the note has no frontmatter) */
+  frontmatter: string | null;
+
+  /** Note content after the frontmatter block */
+  body: string;
+}
+
+/**
+ * Separate a leading YAML frontmatter block from the note body
+ *
+ * @example
+ * splitFrontmatter('---\ntitle: A\n---\n# A') // → { frontmatter: 'title: A', body: '# A' }
+ */
+export function splitFrontmatter(content: string): FrontmatterSplit {
+  const match = FRONTMATTER_PATTERN.exec(content);
+  if (!match) {
+    return { frontmatter: null, body: content };
+  }
+
+  return {
+    frontmatter: match[1] ?? "",
+    body: content.slice(match[0].length),
+  };
+}
+
+function parseFrontmatterScalar(raw: string): FrontmatterScalar {
+  const value = raw.trim();
+  const quote = value[0];
+  if ((quote === '"' || quote === "'") && value.endsWith(quote) && value.length > 1) {
+    return value.slice(1, -1);
+  }
+
+  const unquoted = value.replace(/\s+#.*$/, "");
+  if (unquoted === "" || unquoted === "~" || unquoted === "null") {
+    return null;
+  }
+  if (unquoted === "true" || unquoted === "false") {
+    return unquoted === "true";
+  }
+  return NUMBER_PATTERN.test(unquoted) ? Number(unquoted) : unquoted;
+}
+
+/**
+ * Parse the flat subset of YAML notes use in frontmatter: `key: value`
+ * scalars, inline `[a, b]` lists and `- item` block lists
+ * Nested mappings and multi-line strings are skipped
+ *
+ * @example
+ * parseFrontmatter('title: A\ntags: [x, y]') // → { title: 'A', tags: ['x', 'y'] }
+ */
+export function parseFrontmatter(frontmatter: string): Record<string, FrontmatterValue> {
+  const fields: Record<string, FrontmatterValue> = {};
+  let listKey: string | null = null;
+
+  for (const line of frontmatter.split(/\r?\n/)) {
+    const item = FRONTMATTER_ITEM_PATTERN.exec(line);
+    if (item && listKey !== null) {
+      const list = fields[listKey];
+      const value = parseFrontmatterScalar(item[1] ?? "");
+      fields[listKey] = Array.isArray(list) ? [...list, value] : [value];
+      continue;
+    }
+
+    const field = FRONTMATTER_FIELD_PATTERN.exec(line);
+    if (!field) {
+      // Indented continuation of a value we don't parse
+      if (!/^\s/.test(line)) {
+        listKey = null;
+      }
+      continue;
+    }
+
+    const [, key, rawValue] = field;
+    const value = rawValue.trim();
+    listKey = value === "" ? key : null;
+
+    if (value.startsWith("[") && value.endsWith("]")) {
+      const inner = value.slice(1, -1).trim();
+      fields[key] = inner ? inner.split(",").map(parseFrontmatterScalar) : [];
+    } else {
+      fields[key] = parseFrontmatterScalar(value);
+    }
+  }
+
+  return fields;
+}
+
+/**
+ * Get a frontmatter field as a list of non-empty strings
+ * A single string value is split on commas
+ */
+export function getFrontmatterList(fields: Record<string, FrontmatterValue>, key: string): string[] {
+  const value = fields[key];
+  const items = Array.isArray(value) ? value : typeof value === "string" ? value.split(",") : [];
+  return items
+    .filter((item): item is string | number => typeof item === "string" || typeof item === "number")
+    .map((item) => String(item).trim())
+    .filter(Boolean);
+}
+
+/**
+ * Find a note's tags: frontmatter `tags:` plus inline #tags in the text
+ * Tags are returned without the # and deduplicated ignoring case
+ *
+ * @example
+ * findTags('---\ntags: [a]\n---\nSee #b/c and #a') // → ['a', 'b/c']
+ */
+export function findTags(content: string): string[] {
+  const { frontmatter } = splitFrontmatter(content);
+  const fields = frontmatter === null ? {} : parseFrontmatter(frontmatter);
+  const tags = [...getFrontmatterList(fields, "tags"), ...getFrontmatterList(fields, "tag")].flatMap((value) =>
+    value.split(/\s+/).map((tag) => tag.replace(/^#/, ""))
+  );
+
+  for (const { text } of getProseLines(content)) {
+    const prose = text.replace(INLINE_CODE_PATTERN, (code) => " ".repeat(code.length));
+    for (const match of prose.matchAll(INLINE_TAG_PATTERN)) {
+      // Purely numeric "tags" are issue numbers and the like
+      if (/[^\p{N}]/u.test(match[1])) {
+        tags.push(match[1].replace(/[/-]+$/, ""));
+      }
+    }
+  }
+
+  const seen = new Set<string>();
+  return tags.filter((tag) => {
+    const key = tag.toLowerCase();
+    if (!tag || seen.has(key)) {
+      return false;
+    }
+    seen.add(key);
+    return true;
+  });
+}
+
+/**
+ * Get a note's display title from its path (file name without extension)
+ */
+export function getNoteTitle(path: string): string {
+  return stripNoteExtension(path.split("/").pop() ?? path);
+}
+
+/**
+ * Build the anchor slug for a heading (GitHub style): lowercase, punctuation
+ * and symbols dropped, whitespace turned into hyphens
+ *
+ * @example
+ * slugifyHeading('Getting Started!') // → 'getting-started'
+ */
+export function slugifyHeading(text: string): string {
+  return text
+    .trim()
+    .toLowerCase()
+    .replace(/[^\p{L}\p{M}\p{N}\p{Pc}\s-]/gu, "")
+    .replace(/\s/g, "-");
+}
+
+/**
+ * Create a slugger that keeps anchors unique within one document
+ * Repeats get -1, -2, ... suffixes, skipping any already taken, as on GitHub
+ *
+ * @example
+ * const slug = createSlugger();
+ * [slug('A'), slug('A'), slug('A-1')] // → ['a', 'a-1', 'a-1-1']
+ */
+export function createSlugger(): (text: string) => string {
+  const occurrences = new Map<string, number>();
+
+  return (text) => {
+    const base = slugifyHeading(text);
+    let slug = base;
+    while (occurrences.has(slug)) {
+      const count = (occurrences.get(base) ?? 0) + 1;
+      occurrences.set(base, count);
+      slug = `${base}-${count}`;
+    }
+    occurrences.set(slug, 0);
+    return slug;
+  };
+}
+
+/**
+ * Check whether a link destination points outside the workspace
+ * (URLs, protocol links and same-note anchors)
+ */
+export function isExternalLink(destination: string): boolean {
+  return EXTERNAL_LINK_PATTERN.test(destination);
+}
+
+function unwrapDestination(rawDestination: string): string {
+  return rawDestination.startsWith("<") ? rawDestination.slice(1, -1) : rawDestination;
+}
+
+/**
+ * Rewrite the destinations of inline markdown links and images
+ * @param rewrite - Receives the destination without <...>; returns the new
+ *   destination, or null to keep the link as written
+ */
+export function rewriteLinkDestinations(
+  text: string,
+  rewrite: (destination: string, isImage: boolean) => string | null
+): string {
+  return text.replace(INLINE_LINK_PATTERN, (match, prefix: string, rawDestination: string, rest: string) => {
+    const rewritten = rewrite(unwrapDestination(rawDestination), prefix.startsWith("!"));
+    return rewritten === null ? match : `${prefix}${formatLinkTarget(rewritten)}${rest}`;
+  });
+}
+
+/**
+ * List the destinations of inline markdown links and images in a line
+ */
+export function findLinkDestinations(text: string): Array<{ destination: string; isImage: boolean }> {
+  return Array.from(text.matchAll(INLINE_LINK_PATTERN), (match) => ({
+    destination: unwrapDestination(match[2]),
+    isImage: match[1].startsWith("!"),
+  }));
+}
+
+/** Where a line stands relative to fenced code */
+type FenceLine =
+  | { kind: "prose" }
+  | { kind: "open"; indent: number; info: string }
+  | { kind: "code" }
+  | { kind: "close" };
+
+/**
+ * Classify lines one after another by fenced code, following CommonMark
+ * A fence opens on ``` or ~~~ (a backtick fence's info string can't hold
+ * backticks) and closes at a bare fence of the same character at least as
+ * long as the opening one, or at the end of the note
+ */
+function createFenceScanner(): (text: string) => FenceLine {
+  let open: string | null = null;
+
+  return (text) => {
+    const match = CODE_FENCE_PATTERN.exec(text);
+
+    if (open === null) {
+      if (!match || (match[2][0] === "`" && match[3].includes("`"))) {
+        return { kind: "prose" };
+      }
+      open = match[2];
+      return { kind: "open", indent: match[1].length, info: match[3].trim() };
+    }
+
+    if (match && match[2][0] === open[0] && match[2].length >= open.length && !match[3].trim()) {
+      open = null;
+      return { kind: "close" };
+    }
+    return { kind: "code" };
+  };
+}
+
+/**
+ * Get the lines of a note that hold markdown text
+ * Frontmatter and fenced code blocks are skipped
+ */
+export function getProseLines(content: string): ProseLine[] {
+  const { body } = splitFrontmatter(content);
+  const lineOffset = content.slice(0, content.length - body.length).split(/\r?\n/).length - 1;
+  const lines: ProseLine[] = [];
+  const scanFence = createFenceScanner();
+
+  body.split(/\r?\n/).forEach((text, index) => {
+    if (scanFence(text).kind === "prose") {
+      lines.push({ text, line: index + lineOffset + 1 });
+    }
+  });
+
+  return lines;
+}
+
+/**
+ * Reduce inline markdown to its visible text
+ *
+ * @example
+ * stripInlineMarkdown('**Use** [the API](api.md)') // → 'Use the API'
+ */
+export function stripInlineMarkdown(text: string): string {
+  return text
+    .replace(/!?\[([^\]]*)\]\([^)]*\)/g, "$1")
+    .replace(/<[^>]+>/g, "")
+    .replace(/[*_~`]/g, "")
+    .trim();
+}
+
+/**
+ * Parse ATX (# Title) and setext (Title / ===) headings
+ * Slugs match the heading ids the markdown renderer writes
+ */
+export function parseHeadings(content: string): OutlineHeading[] {
+  const headings: OutlineHeading[] = [];
+  const slugify = createSlugger();
+  const lines = getProseLines(content);
+
+  const addHeading = (level: number, rawText: string, line: number) => {
+    const text = stripInlineMarkdown(rawText);
+    headings.push({ level, text, slug: slugify(text), line });
+  };
+
+  lines.forEach(({ text, line }, index) => {
+    const atx = ATX_HEADING_PATTERN.exec(text);
+    if (atx) {
+      addHeading(atx[1].length, atx[2] ?? "", line);
+      return;
+    }
+
+    const previous = lines[index - 1];
+    const underline = SETEXT_UNDERLINE_PATTERN.exec(text);
+    if (
+      underline &&
+      previous &&
+      previous.line === line - 1 &&
+      previous.text.trim() &&
+      !BLOCK_START_PATTERN.test(previous.text) &&
+      headings[headings.length - 1]?.line !== previous.line
+    ) {
+      addHeading(underline[1][0] === "=" ? 1 : 2, previous.text, previous.line);
+    }
+  });
+
+  return headings;
+}
+
+/**
+ * Find the local links in a note: inline links, images and [[wikilinks]]
+ * Same-note #anchors are included; URLs and links inside code are not
+ */
+export function findLocalLinks(content: string): MarkdownLink[] {
+  const links: MarkdownLink[] = [];
+
+  for (const { text, line } of getProseLines(content)) {
+    const prose = text.replace(INLINE_CODE_PATTERN, (code) => " ".repeat(code.length));
+
+    for (const { destination } of findLinkDestinations(prose)) {
+      if (!isExternalLink(destination) || destination.startsWith("#")) {
+        links.push({ target: destination, isWikilink: false, line });
+      }
+    }
+
+    for (const match of prose.matchAll(WIKILINK_PATTERN)) {
+      links.push({ target: `${match[1]}${match[2] ?? ""}`, isWikilink: true, line });
+    }
+  }
+
+  return links;
+}
+
+/**
+ * Rewrite the text of a note's prose outside code spans
+ */
+function rewriteProse(content: string, rewrite: (text: string) => string): string {
+  const lines = content.split(/\r?\n/);
+
+  for (const { text, line } of getProseLines(content)) {
+    // Split out code spans so only the text between them is rewritten
+    const parts = text.split(/(`[^`]*`)/);
+    lines[line - 1] = parts.map((part, index) => (index % 2 === 1 ? part : rewrite(part))).join("");
+  }
+
+  return lines.join("\n");
+}
+
+/**
+ * Rewrite the [[wikilinks]] in a note's prose
+ * Wikilinks inside code spans and fenced code blocks are left alone
+ * @param rewrite - Receives the target (with any #anchor) and the |label
+ *   (null if none); returns replacement markdown, or null to keep the link
+ */
+export function rewriteWikilinks(
+  content: string,
+  rewrite: (target: string, label: string | null, isEmbed: boolean) => string | null
+): string {
+  return rewriteProse(content, (text) =>
+    text.replace(WIKILINK_PATTERN, (match, name: string, anchor?: string, label?: string) => {
+      return rewrite(`${name}${anchor ?? ""}`, label ?? null, match.startsWith("!")) ?? match;
+    })
+  );
+}
+
+/**
+ * Rewrite the destinations of the inline links and images in a note's prose
+ * Links inside code spans and fenced code blocks are left alone
+ * @param rewrite - As for rewriteLinkDestinations
+ */
+export function rewriteNoteLinkDestinations(
+  content: string,
+  rewrite: (destination: string, isImage: boolean) => string | null
+): string {
+  return rewriteProse(content, (text) => rewriteLinkDestinations(text, rewrite));
+}
+
+/**
+ * Rewrite the inline markdown links and images in a note's prose
+ * Links inside code spans and fenced code blocks are left alone
+ * @param rewrite - Receives the link text, the destination without <...> and
+ *   whether it's an image; returns replacement markdown, or null to keep the link
+ */
+export function rewriteInlineLinks(
+  content: string,
+  rewrite: (text: string, destination: string, isImage: boolean) => string | null
+): string {
+  return rewriteProse(content, (text) =>
+    text.replace(INLINE_LINK_PATTERN, (match, prefix: string, rawDestination: string) => {
+      const isImage = prefix.startsWith("!");
+      return rewrite(prefix.slice(isImage ? 2 : 1, -2), unwrapDestination(rawDestination), isImage) ?? match;
+    })
+  );
+}
+
+/**
+ * Normalize a reference label the way markdown matches them: case-insensitive,
+ * with runs of whitespace collapsed
+ */
+function normalizeReferenceLabel(label: string): string {
+  return label.trim().replace(/\s+/g, " ").toLowerCase();
+}
+
+/**
+ * Find the footnotes and reference-style link definitions of a note with
+ * the places they are used, plus references used but never defined
+ * Shortcut references ([label]) only count when the label is defined, since
+ * any bracketed text could be one
+ */
+export function parseReferences(content: string): NoteReference[] {
+  const references = new Map<string, NoteReference>();
+  const getReference = (kind: ReferenceKind, label: string): NoteReference => {
+    const key = `${kind}:${normalizeReferenceLabel(label)}`;
+    const reference = references.get(key) ?? { kind, label, line: null, definition: null, usages: [] };
+    references.set(key, reference);
+    return reference;
+  };
+
+  const lines = getProseLines(content);
+  const usageLines: Array<{ text: string; line: number }> = [];
+
+  // Definitions first, so shortcut references can be told from bracketed text
+  for (const { text, line } of lines) {
+    const footnote = FOOTNOTE_DEFINITION_PATTERN.exec(text);
+    const link = footnote ? null : LINK_DEFINITION_PATTERN.exec(text);
+    const match = footnote ?? link;
+    if (match) {
+      const reference = getReference(footnote ? "footnote" : "link", match[1]);
+      if (reference.line === null) {
+        reference.line = line;
+        reference.definition = match[2].replace(/^<(.*)>$/, "$1").trim();
+      }
+    }
+
+    // A footnote's text can use references; a link definition line can't
+    if (!link) {
+      const prefix = footnote ? footnote[0].length - footnote[2].length : 0;
+      const blanked = text
+        .replace(INLINE_CODE_PATTERN, (code) => " ".repeat(code.length))
+        .replace(WIKILINK_PATTERN, (wikilink) => " ".repeat(wikilink.length));
+      usageLines.push({ text: " ".repeat(prefix) + blanked.slice(prefix), line });
+    }
+  }
+
+  for (const { text, line } of usageLines) {
+    for (const match of text.matchAll(FOOTNOTE_USAGE_PATTERN)) {
+      getReference("footnote", match[1]).usages.push({ line, column: (match.index ?? 0) + 1 });
+    }
+
+    // [text][label] and [label][], blanked out so their parts aren't read again as shortcuts
+    const withoutFootnotes = text.replace(FOOTNOTE_USAGE_PATTERN, (match) => " ".repeat(match.length));
+    const rest = withoutFootnotes.replace(
+      FULL_REFERENCE_PATTERN,
+      (match, linkText: string, label: string, offset: number) => {
+        const column = offset + match.indexOf("[") + 1;
+        getReference("link", label || linkText).usages.push({ line, column });
+        return " ".repeat(match.length);
+      }
+    );
+
+    for (const match of rest.matchAll(SHORTCUT_REFERENCE_PATTERN)) {
+      const reference = references.get(`link:${normalizeReferenceLabel(match[1])}`);
+      if (reference) {
+        reference.usages.push({ line, column: (match.index ?? 0) + match[0].indexOf("[") + 1 });
+      }
+    }
+  }
+
+  return [...references.values()].sort(
+    (left, right) => (left.line ?? left.usages[0]?.line ?? 0) - (right.line ?? right.usages[0]?.line ?? 0)
+  );
+}
+
+/**
+ * Find the fenced code blocks in a note (frontmatter is skipped)
+ * Fences follow CommonMark, as in getProseLines
+ */
+export function findCodeBlocks(content: string): CodeBlock[] {
+  const { body } = splitFrontmatter(content);
+  const lineOffset = content.slice(0, content.length - body.length).split(/\r?\n/).length - 1;
+  const lines = body.split(/\r?\n/);
+  const blocks: CodeBlock[] = [];
+
+  const scanFence = createFenceScanner();
+  let open: { indent: number; info: string; start: number; code: string[] } | null = null;
+  const closeBlock = (endLine: number) => {
+    if (open) {
+      blocks.push({
+        language: open.info.split(/\s+/)[0] || null,
+        info: open.info,
+        content: open.code.join("\n"),
+        start_line: open.start,
+        end_line: endLine,
+      });
+    }
+    open = null;
+  };
+
+  for (let index = 0; index < lines.length; index += 1) {
+    const text = lines[index];
+    const line = index + lineOffset + 1;
+    const fenceLine = scanFence(text);
+
+    if (fenceLine.kind === "open") {
+      open = { indent: fenceLine.indent, info: fenceLine.info, start: line, code: [] };
+    } else if (fenceLine.kind === "close") {
+      closeBlock(line);
+    } else if (fenceLine.kind === "code" && open !== null) {
+      // Code lines lose up to as much indentation as the opening fence had
+      open.code.push(text.replace(new RegExp(`^ {0,${open.indent}}`), ""));
+    }
+  }
+
+  closeBlock(lines.length + lineOffset);
+  return blocks;
+}
+
+/**
+ * Find the checkbox tasks in a note: "- [ ] todo" and "- [x] done"
+ * Tasks inside fenced code blocks are skipped
+ */
+export function findTasks(content: string): MarkdownTask[] {
+  const tasks: MarkdownTask[] = [];
+
+  for (const { text, line } of getProseLines(content)) {
+    const match = TASK_PATTERN.exec(text);
+    if (!match) {
+      continue;
+    }
+
+    const taskText = (match[2] ?? "").trim();
+    tasks.push({
+      text: taskText,
+      completed: match[1] !== " ",
+      due: DUE_DATE_PATTERN.exec(taskText)?.[1] ?? null,
+      line,
+    });
+  }
+
+  return tasks;
+}
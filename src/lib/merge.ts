@@ -0,0 +1,136 @@
+/**
+ * Three-way merge - combines two edited versions of a note with their common
+ * ancestor (diff3), and finds the conflicted copies sync tools leave behind
+ * when the same note changed on two machines.
+ */
+
+import type { ConflictedCopy, ConflictedCopySource, MergeConflict, MergeResult } from "../types";
+import { matchLines, splitLines } from "./diff";
+import * as fsService from "./fs-service";
+
+/** "Note (Laptop's conflicted copy 2024-05-01).md", also used by Nextcloud */
+const DROPBOX_PATTERN = /^(.*) \([^()]*conflicted copy[^()]*\)(\.[^.]*)?$/i;
+
+/** "Note.sync-conflict-20240501-101500-ABCDEFG.md" */
+const SYNCTHING_PATTERN = /^(.*)\.sync-conflict-\d{8}-\d{6}-[A-Z0-9]{7}(\.[^.]*)?$/;
+
+const CONFLICT_PATTERNS: Array<[ConflictedCopySource, RegExp]> = [
+  ["dropbox", DROPBOX_PATTERN],
+  ["syncthing", SYNCTHING_PATTERN],
+];
+
+export interface MergeLabels {
+  ours?: string;
+  theirs?: string;
+}
+
+function sameLines(a: string[], b: string[]): boolean {
+  return a.length === b.length && a.every((line, index) => line === b[index]);
+}
+
+/**
+ * Merge two versions of a text that both started from base
+ * Changes made on only one side are taken; regions both sides changed
+ * differently become conflicts, written with markers and also returned
+ * as structured regions.
+ */
+export function mergeThreeWay(base: string, ours: string, theirs: string, labels: MergeLabels = {}): MergeResult {
+  const o = splitLines(base);
+  const a = splitLines(ours);
+  const b = splitLines(theirs);
+  const toOurs = matchLines(o, a);
+  const toTheirs = matchLines(o, b);
+
+  const output: string[] = [];
+  const conflicts: MergeConflict[] = [];
+  let baseIndex = 0;
+  let oursIndex = 0;
+  let theirsIndex = 0;
+
+  const resolve = (baseEnd: number, oursEnd: number, theirsEnd: number) => {
+    const baseChunk = o.slice(baseIndex, baseEnd);
+    const oursChunk = a.slice(oursIndex, oursEnd);
+    const theirsChunk = b.slice(theirsIndex, theirsEnd);
+
+    if (sameLines(oursChunk, baseChunk) || sameLines(oursChunk, theirsChunk)) {
+      output.push(...theirsChunk);
+    } else if (sameLines(theirsChunk, baseChunk)) {
+      output.push(...oursChunk);
+    } else {
+      conflicts.push({ line: output.length + 1, base: baseChunk, ours: oursChunk, theirs: theirsChunk });
+      output.push(
+        `<<<<<<< ${labels.ours ?? "ours"}`,
+        ...oursChunk,
+        "=======",
+        ...theirsChunk,
+        `>>>>>>> ${labels.theirs ?? "theirs"}`
+      );
+    }
+
+    baseIndex = baseEnd;
+    oursIndex = oursEnd;
+    theirsIndex = theirsEnd;
+  };
+
+  while (baseIndex < o.length || oursIndex < a.length || theirsIndex < b.length) {
+    // Lines unchanged on both sides
+    if (
+      baseIndex < o.length &&
+      toOurs[baseIndex] === oursIndex &&
+      toTheirs[baseIndex] === theirsIndex
+    ) {
+      output.push(o[baseIndex]);
+      baseIndex += 1;
+      oursIndex += 1;
+      theirsIndex += 1;
+      continue;
+    }
+
+    // The changed region runs up to the next base line both sides kept
+    let next = baseIndex;
+    while (next < o.length && (toOurs[next] === -1 || toTheirs[next] === -1)) {
+      next += 1;
+    }
+
+    if (next === o.length) {
+      resolve(o.length, a.length, b.length);
+    } else {
+      resolve(next, toOurs[next], toTheirs[next]);
+    }
+  }
+
+  return { content: output.join("\n"), conflicts };
+}
+
+/**
+ * Find the conflicted copies Dropbox, Nextcloud and Syncthing create when
+ * a file changed on two machines before they synced
+ */
+export async function findConflictedCopies(signal?: AbortSignal): Promise<ConflictedCopy[]> {
+  const files = new Set<string>();
+  for await (const entry of fsService.walkDirectory(".", { signal })) {
+    if (entry.handle.kind === "file") {
+      files.add(entry.relativePath);
+    }
+  }
+
+  const copies: ConflictedCopy[] = [];
+  for (const path of files) {
+    const slashIndex = path.lastIndexOf("/");
+    const folder = path.slice(0, slashIndex + 1);
+    const name = path.slice(slashIndex + 1);
+
+    for (const [source, pattern] of CONFLICT_PATTERNS) {
+      const match = pattern.exec(name);
+      if (!match) {
+        continue;
+      }
+
+      const original = `${folder}${match[1]}${match[2] ?? ""}`;
+      copies.push({ path, original_path: files.has(original) ? original : null, source });
+      break;
+    }
+  }
+
+  return copies.sort((left, right) => left.path.localeCompare(right.path));
+}
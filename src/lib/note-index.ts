@@ -0,0 +1,344 @@
+/**
+ * Note index - in-memory metadata for every note in the workspace
+ * Built on first use and kept current from "file-changed" events, so lookups
+ * like wikilink completion never have to read the vault again
+ */
+
+import type { LinkCompletion, NoteMatchKind, NoteMetadata, ResolvedNote, TagCompletion } from "../types";
+import { listen } from "./app-events";
+import { AppError } from "./errors";
+import { isNotePath, stripNoteExtension } from "./filenameUtils";
+import * as fsService from "./fs-service";
+import {
+  findLocalLinks,
+  findTags,
+  findTasks,
+  getFrontmatterList,
+  getNoteTitle,
+  parseFrontmatter,
+  splitFrontmatter,
+} from "./markdownUtils";
+import type { MarkdownLink, MarkdownTask } from "./markdownUtils";
+import { runWithConcurrency } from "./operations";
+
+const INDEX_CONCURRENCY = 8;
+const DEFAULT_COMPLETION_LIMIT = 50;
+
+/** Rank penalty for matching a note by its folder path instead of its title */
+const PATH_MATCH_PENALTY = 4;
+
+/** Resolution order when a name matches notes in several ways */
+const MATCH_ORDER: NoteMatchKind[] = ["path", "name", "title", "alias"];
+
+export interface IndexedNote extends NoteMetadata {
+  links: MarkdownLink[];
+  tasks: MarkdownTask[];
+}
+
+export interface NoteIndex {
+  /** Workspace the index was built from */
+  workspacePath: string;
+
+  /** Notes keyed by workspace-relative path */
+  notes: Map<string, IndexedNote>;
+}
+
+let currentIndex: NoteIndex | null = null;
+let pendingIndex: Promise<NoteIndex> | null = null;
+
+/** Bumped on invalidation so builds started before it are dropped */
+let generation = 0;
+
+function isSameOrInside(path: string, entry: string): boolean {
+  return path === entry || path.startsWith(`${entry}/`);
+}
+
+async function indexNote(path: string, file: File): Promise<IndexedNote> {
+  const content = await file.text();
+  const { frontmatter } = splitFrontmatter(content);
+  const fields = frontmatter === null ? {} : parseFrontmatter(frontmatter);
+  const title = typeof fields.title === "string" || typeof fields.title === "number" ? String(fields.title).trim() : "";
+
+  return {
+    path,
+    title: title || getNoteTitle(path),
+    aliases: [...getFrontmatterList(fields, "aliases"), ...getFrontmatterList(fields, "alias")],
+    tags: findTags(content),
+    links: findLocalLinks(content),
+    tasks: findTasks(content),
+    frontmatter: fields,
+    modified: file.lastModified,
+    size: file.size,
+  };
+}
+
+/**
+ * Index every note below a workspace folder into the map
+ */
+async function indexFolder(folder: string, notes: Map<string, IndexedNote>): Promise<void> {
+  const handles: Array<{ path: string; handle: FileSystemFileHandle }> = [];
+  for await (const entry of fsService.walkDirectory(folder)) {
+    if (entry.handle.kind === "file" && isNotePath(entry.relativePath)) {
+      handles.push({ path: entry.relativePath, handle: entry.handle });
+    }
+  }
+
+  await runWithConcurrency(handles, INDEX_CONCURRENCY, async ({ path, handle }) => {
+    notes.set(path, await indexNote(path, await handle.getFile()));
+  });
+}
+
+async function buildIndex(workspacePath: string): Promise<NoteIndex> {
+  const notes = new Map<string, IndexedNote>();
+  await indexFolder(".", notes);
+  return { workspacePath, notes };
+}
+
+function invalidateIndex(): void {
+  generation += 1;
+  currentIndex = null;
+  pendingIndex = null;
+}
+
+/**
+ * Get the index of the open workspace, building it on first use
+ */
+export async function getNoteIndex(): Promise<NoteIndex> {
+  const workspacePath = await fsService.restoreWorkspace();
+  if (currentIndex && currentIndex.workspacePath === workspacePath) {
+    return currentIndex;
+  }
+
+  if (!workspacePath) {
+    throw new AppError("no-workspace", "No workspace selected");
+  }
+
+  if (!pendingIndex) {
+    const started = generation;
+    pendingIndex = buildIndex(workspacePath).then(
+      (index) => {
+        if (started === generation) {
+          currentIndex = index;
+          pendingIndex = null;
+        }
+        return index;
+      },
+      (error) => {
+        if (started === generation) {
+          pendingIndex = null;
+        }
+        throw error;
+      }
+    );
+  }
+
+  return pendingIndex;
+}
+
+/**
+ * Re-read a changed path into the index: a note, or every note in a folder
+ */
+async function reindexPath(index: NoteIndex, path: string): Promise<void> {
+  for (const notePath of [...index.notes.keys()]) {
+    if (isSameOrInside(notePath, path)) {
+      index.notes.delete(notePath);
+    }
+  }
+
+  try {
+    if (isNotePath(path)) {
+      index.notes.set(path, await indexNote(path, await fsService.getFile(path)));
+    } else {
+      await indexFolder(path, index.notes);
+    }
+  } catch {
+    // Gone again, or a file that isn't a note
+  }
+}
+
+// Each window keeps its own index, so follow local and remote changes alike
+listen("file-changed", (change) => {
+  if (pendingIndex) {
+    // A build in flight may or may not have seen the change
+    invalidateIndex();
+    return;
+  }
+
+  const index = currentIndex;
+  if (!index || change.workspace_path !== index.workspacePath) {
+    return;
+  }
+
+  // A rename drops the old path, then reads in the new one
+  void reindexPath(index, change.path).then(() =>
+    change.new_path === null ? undefined : reindexPath(index, change.new_path)
+  );
+});
+
+listen("config-changed", () => invalidateIndex());
+
+/**
+ * Map lowercased frontmatter titles and aliases to the notes that use them
+ */
+export function getTitleLookup(notes: Map<string, IndexedNote>): Map<string, string[]> {
+  const lookup = new Map<string, string[]>();
+  for (const note of notes.values()) {
+    for (const name of new Set([note.title, ...note.aliases].map((value) => value.toLowerCase()))) {
+      lookup.set(name, [...(lookup.get(name) ?? []), note.path]);
+    }
+  }
+  return lookup;
+}
+
+/**
+ * Find the note a human-readable name refers to
+ * Workspace paths win over file names, file names over frontmatter titles
+ * and titles over aliases; ties go to the shortest path
+ * @param name - Path, file name, title or alias (a #heading or |label suffix is ignored)
+ */
+export async function resolveNote(name: string): Promise<ResolvedNote | null> {
+  const { notes } = await getNoteIndex();
+  const query = name.replace(/[#|].*$/, "").trim().replace(/^\/+/, "").toLowerCase();
+  if (!query) {
+    return null;
+  }
+
+  const matches = new Map<NoteMatchKind, IndexedNote[]>();
+  const addMatch = (kind: NoteMatchKind, note: IndexedNote) => {
+    matches.set(kind, [...(matches.get(kind) ?? []), note]);
+  };
+
+  for (const note of notes.values()) {
+    const path = note.path.toLowerCase();
+    if (path === query || stripNoteExtension(path) === query) {
+      addMatch("path", note);
+    } else if (getNoteTitle(path) === query) {
+      addMatch("name", note);
+    } else if (note.title.toLowerCase() === query) {
+      addMatch("title", note);
+    } else if (note.aliases.some((alias) => alias.toLowerCase() === query)) {
+      addMatch("alias", note);
+    }
+  }
+
+  for (const kind of MATCH_ORDER) {
+    const [best] = (matches.get(kind) ?? []).sort(
+      (left, right) => left.path.length - right.path.length || left.path.localeCompare(right.path)
+    );
+    if (best) {
+      return { path: best.path, title: best.title, matched_by: kind };
+    }
+  }
+
+  return null;
+}
+
+/**
+ * Rank how well a candidate matches a lowercased query (lower is better)
+ * @returns null when it doesn't match at all
+ */
+function rankMatch(candidate: string, query: string): number | null {
+  const text = candidate.toLowerCase();
+  if (text === query) {
+    return 0;
+  }
+  if (text.startsWith(query)) {
+    return 1;
+  }
+
+  const index = text.indexOf(query);
+  if (index === -1) {
+    return null;
+  }
+  return /[\s_./-]/.test(text[index - 1]) ? 2 : 3;
+}
+
+/**
+ * Suggest notes for a [[wikilink]] being typed
+ * Title, file name and alias matches rank before folder path matches; ties go to the most
+ * recently modified note
+ * @param prefix - Text typed after [[
+ */
+export async function getLinkCompletions(
+  prefix: string,
+  limit: number = DEFAULT_COMPLETION_LIMIT
+): Promise<LinkCompletion[]> {
+  const { notes } = await getNoteIndex();
+  const query = prefix.trim().toLowerCase();
+
+  const nameCounts = new Map<string, number>();
+  for (const note of notes.values()) {
+    const key = getNoteTitle(note.path).toLowerCase();
+    nameCounts.set(key, (nameCounts.get(key) ?? 0) + 1);
+  }
+
+  const ranked: Array<{ note: IndexedNote; rank: number }> = [];
+  for (const note of notes.values()) {
+    const titleRanks = [note.title, getNoteTitle(note.path), ...note.aliases].map((name) => rankMatch(name, query));
+    const pathRank = rankMatch(stripNoteExtension(note.path), query);
+    const rank = Math.min(
+      ...titleRanks.map((titleRank) => titleRank ?? Infinity),
+      pathRank === null ? Infinity : pathRank + PATH_MATCH_PENALTY
+    );
+    if (rank !== Infinity) {
+      ranked.push({ note, rank });
+    }
+  }
+
+  ranked.sort(
+    (left, right) =>
+      left.rank - right.rank ||
+      right.note.modified - left.note.modified ||
+      left.note.path.localeCompare(right.note.path)
+  );
+
+  return ranked.slice(0, limit).map(({ note }) => {
+    const name = getNoteTitle(note.path);
+    return {
+      path: note.path,
+      title: note.title,
+      // Ambiguous names need their folder to resolve to the right note
+      link_text: (nameCounts.get(name.toLowerCase()) ?? 0) > 1 ? stripNoteExtension(note.path) : name,
+    };
+  });
+}
+
+/**
+ * Suggest tags for a #tag being typed, most used first within each rank
+ * @param prefix - Text typed after #
+ */
+export async function getTagCompletions(
+  prefix: string,
+  limit: number = DEFAULT_COMPLETION_LIMIT
+): Promise<TagCompletion[]> {
+  const { notes } = await getNoteIndex();
+  const query = prefix.trim().replace(/^#/, "").toLowerCase();
+
+  // Tags differing only in case count as one, shown as first seen
+  const counts = new Map<string, TagCompletion>();
+  for (const note of notes.values()) {
+    for (const tag of note.tags) {
+      const key = tag.toLowerCase();
+      const entry = counts.get(key) ?? { tag, count: 0 };
+      entry.count += 1;
+      counts.set(key, entry);
+    }
+  }
+
+  const ranked: Array<{ completion: TagCompletion; rank: number }> = [];
+  for (const completion of counts.values()) {
+    const rank = rankMatch(completion.tag, query);
+    if (rank !== null) {
+      ranked.push({ completion, rank });
+    }
+  }
+
+  ranked.sort(
+    (left, right) =>
+      left.rank - right.rank ||
+      right.completion.count - left.completion.count ||
+      left.completion.tag.localeCompare(right.completion.tag)
+  );
+
+  return ranked.slice(0, limit).map(({ completion }) => completion);
+}
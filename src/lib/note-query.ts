@@ -0,0 +1,435 @@
+/**
+ * Note queries - filter notes by frontmatter, tags, paths and dates
+ * Queries are evaluated against the note index, never the files themselves.
+ *
+ * Syntax:
+ *   status == "draft" AND modified > 2025-01-01
+ *   #project/alpha OR path matches "work/**"
+ *   NOT archived AND (priority >= 2 OR tags contains "urgent")
+ *
+ * Operators are ==, !=, >, >=, <, <=, contains and matches (glob); a field
+ * on its own tests that it is set. Built-in fields are path, name, title,
+ * tags, aliases, modified and size; any other name reads the frontmatter,
+ * and "frontmatter.<key>" reads it even when a built-in has the same name.
+ */
+
+import type { FrontmatterScalar, NoteMetadata, NoteQueryOptions } from "../types";
+import { AppError } from "./errors";
+import { matchesGlob } from "./globUtils";
+import { getNoteTitle } from "./markdownUtils";
+import { getNoteIndex } from "./note-index";
+
+const DATE_PATTERN = /^(\d{4})-(\d{2})-(\d{2})(?:[T ][\d:.]+(?:Z|[+-]\d{2}:?\d{2})?)?$/;
+const NUMBER_PATTERN = /^-?\d+(?:\.\d+)?$/;
+const WORD_PATTERN = /[^\s()"'<>=!]+/y;
+const TAG_PATTERN = /#([\p{L}\p{N}_/-]+)/uy;
+const OPERATOR_PATTERN = /==|!=|>=|<=|=|>|</y;
+const FRONTMATTER_PREFIX = "frontmatter.";
+
+type ComparisonOperator = "==" | "!=" | ">" | ">=" | "<" | "<=" | "contains" | "matches";
+
+/** A literal in a query; date literals become Dates */
+type QueryLiteral = FrontmatterScalar | Date;
+
+type FieldValue = QueryLiteral | FrontmatterScalar[] | undefined;
+
+type QueryNode =
+  | { type: "and" | "or"; left: QueryNode; right: QueryNode }
+  | { type: "not"; operand: QueryNode }
+  | { type: "tag"; tag: string }
+  | { type: "exists"; field: string }
+  | { type: "compare"; field: string; operator: ComparisonOperator; value: QueryLiteral };
+
+type Token =
+  | { kind: "paren"; value: "(" | ")"; position: number }
+  | { kind: "operator"; value: string; position: number }
+  | { kind: "string"; value: string; position: number }
+  | { kind: "tag"; value: string; position: number }
+  | { kind: "word"; value: string; position: number };
+
+function queryError(message: string, position: number): AppError {
+  return new AppError("invalid-argument", `Invalid query: ${message} at position ${position + 1}`);
+}
+
+function tokenize(query: string): Token[] {
+  const tokens: Token[] = [];
+  let position = 0;
+
+  const matchAt = (pattern: RegExp) => {
+    pattern.lastIndex = position;
+    return pattern.exec(query);
+  };
+
+  while (position < query.length) {
+    const char = query[position];
+
+    if (/\s/.test(char)) {
+      position += 1;
+    } else if (char === "(" || char === ")") {
+      tokens.push({ kind: "paren", value: char, position });
+      position += 1;
+    } else if (char === '"' || char === "'") {
+      let value = "";
+      let index = position + 1;
+      while (index < query.length && query[index] !== char) {
+        if (query[index] === "\\" && index + 1 < query.length) {
+          index += 1;
+        }
+        value += query[index];
+        index += 1;
+      }
+      if (index >= query.length) {
+        throw queryError("unterminated string", position);
+      }
+      tokens.push({ kind: "string", value, position });
+      position = index + 1;
+    } else if (char === "#") {
+      const match = matchAt(TAG_PATTERN);
+      if (!match) {
+        throw queryError("expected a tag after #", position);
+      }
+      tokens.push({ kind: "tag", value: match[1], position });
+      position += match[0].length;
+    } else {
+      const operator = matchAt(OPERATOR_PATTERN);
+      const match = operator ?? matchAt(WORD_PATTERN);
+      if (!match) {
+        throw queryError(`unexpected "${char}"`, position);
+      }
+      tokens.push({ kind: operator ? "operator" : "word", value: match[0], position });
+      position += match[0].length;
+    }
+  }
+
+  return tokens;
+}
+
+/**
+ * Parse a date the way users write them: a bare YYYY-MM-DD is local midnight
+ * @returns Milliseconds since the epoch, or null if the text isn't a date
+ */
+function parseDate(text: string): number | null {
+  const match = DATE_PATTERN.exec(text.trim());
+  if (!match) {
+    return null;
+  }
+  if (text.trim().length === 10) {
+    return new Date(Number(match[1]), Number(match[2]) - 1, Number(match[3])).getTime();
+  }
+
+  const time = Date.parse(text);
+  return Number.isNaN(time) ? null : time;
+}
+
+function parseLiteral(token: Token): QueryLiteral {
+  if (token.kind === "string") {
+    return token.value;
+  }
+
+  const word = token.value;
+  const lower = word.toLowerCase();
+  if (lower === "true" || lower === "false") {
+    return lower === "true";
+  }
+  if (lower === "null") {
+    return null;
+  }
+  if (NUMBER_PATTERN.test(word)) {
+    return Number(word);
+  }
+
+  const date = parseDate(word);
+  return date === null ? word : new Date(date);
+}
+
+function isKeyword(token: Token | undefined, keyword: string): boolean {
+  return token?.kind === "word" && token.value.toUpperCase() === keyword;
+}
+
+/**
+ * Parse a query into a tree: OR binds loosest, then AND, then NOT
+ */
+function parseQuery(query: string): QueryNode | null {
+  const tokens = tokenize(query);
+  let index = 0;
+
+  const peek = () => tokens[index];
+  const endPosition = () => query.length;
+
+  function parseOr(): QueryNode {
+    let node = parseAnd();
+    while (isKeyword(peek(), "OR")) {
+      index += 1;
+      node = { type: "or", left: node, right: parseAnd() };
+    }
+    return node;
+  }
+
+  function parseAnd(): QueryNode {
+    let node = parseNot();
+    while (isKeyword(peek(), "AND")) {
+      index += 1;
+      node = { type: "and", left: node, right: parseNot() };
+    }
+    return node;
+  }
+
+  function parseNot(): QueryNode {
+    if (isKeyword(peek(), "NOT")) {
+      index += 1;
+      return { type: "not", operand: parseNot() };
+    }
+    return parsePrimary();
+  }
+
+  function parsePrimary(): QueryNode {
+    const token = peek();
+    if (!token) {
+      throw queryError("unexpected end of query", endPosition());
+    }
+    index += 1;
+
+    if (token.kind === "paren" && token.value === "(") {
+      const node = parseOr();
+      const closing = peek();
+      if (closing?.kind !== "paren" || closing.value !== ")") {
+        throw queryError('expected ")"', closing?.position ?? endPosition());
+      }
+      index += 1;
+      return node;
+    }
+    if (token.kind === "tag") {
+      return { type: "tag", tag: token.value.toLowerCase() };
+    }
+    if (token.kind !== "word") {
+      throw queryError(`expected a field name, found "${token.value}"`, token.position);
+    }
+
+    const next = peek();
+    let operator: string | null = null;
+    if (next?.kind === "operator") {
+      operator = next.value === "=" ? "==" : next.value;
+    } else if (next && (isKeyword(next, "CONTAINS") || isKeyword(next, "MATCHES"))) {
+      operator = next.value.toLowerCase();
+    }
+    if (operator === null) {
+      return { type: "exists", field: token.value };
+    }
+    index += 1;
+
+    const value = peek();
+    if (!value || (value.kind !== "word" && value.kind !== "string")) {
+      throw queryError(`expected a value after "${operator}"`, value?.position ?? endPosition());
+    }
+    index += 1;
+
+    return {
+      type: "compare",
+      field: token.value,
+      operator: operator as ComparisonOperator,
+      value: parseLiteral(value),
+    };
+  }
+
+  if (tokens.length === 0) {
+    return null;
+  }
+
+  const node = parseOr();
+  const rest = peek();
+  if (rest) {
+    throw queryError(`unexpected "${rest.value}"`, rest.position);
+  }
+  return node;
+}
+
+function getField(note: NoteMetadata, field: string): FieldValue {
+  if (field.startsWith(FRONTMATTER_PREFIX)) {
+    return note.frontmatter[field.slice(FRONTMATTER_PREFIX.length)];
+  }
+
+  switch (field) {
+    case "path":
+      return note.path;
+    case "name":
+      return getNoteTitle(note.path);
+    case "title":
+      return note.title;
+    case "tags":
+      return note.tags;
+    case "aliases":
+      return note.aliases;
+    case "modified":
+      return new Date(note.modified);
+    case "size":
+      return note.size;
+    default:
+      return note.frontmatter[field];
+  }
+}
+
+function toTime(value: QueryLiteral): number | null {
+  if (value instanceof Date) {
+    return value.getTime();
+  }
+  if (typeof value === "string") {
+    return parseDate(value);
+  }
+  return typeof value === "number" ? value : null;
+}
+
+function toNumber(value: QueryLiteral): number | null {
+  if (typeof value === "number") {
+    return value;
+  }
+  return typeof value === "string" && NUMBER_PATTERN.test(value.trim()) ? Number(value) : null;
+}
+
+function orderBy(difference: number, operator: ComparisonOperator): boolean {
+  switch (operator) {
+    case "==":
+      return difference === 0;
+    case "!=":
+      return difference !== 0;
+    case ">":
+      return difference > 0;
+    case ">=":
+      return difference >= 0;
+    case "<":
+      return difference < 0;
+    case "<=":
+      return difference <= 0;
+    default:
+      return false;
+  }
+}
+
+function compareScalar(actual: QueryLiteral, operator: ComparisonOperator, expected: QueryLiteral): boolean {
+  if (operator === "contains") {
+    return String(actual).toLowerCase().includes(String(expected).toLowerCase());
+  }
+  if (operator === "matches") {
+    return matchesGlob(String(actual), String(expected));
+  }
+
+  if (expected instanceof Date) {
+    const time = toTime(actual);
+    if (time === null) {
+      return operator === "!=";
+    }
+    // A bare date literal means the whole day for equality
+    const start = expected.getTime();
+    const isDay = expected.getHours() === 0 && expected.getMinutes() === 0 && expected.getSeconds() === 0;
+    if (isDay && (operator === "==" || operator === "!=")) {
+      const end = new Date(expected.getFullYear(), expected.getMonth(), expected.getDate() + 1).getTime();
+      return (time >= start && time < end) === (operator === "==");
+    }
+    return orderBy(time - start, operator);
+  }
+
+  if (typeof expected === "number") {
+    const number = toNumber(actual);
+    return number === null ? operator === "!=" : orderBy(number - expected, operator);
+  }
+
+  if (expected === null || typeof expected === "boolean") {
+    const equal = actual === expected || String(actual).toLowerCase() === String(expected);
+    return operator === "==" ? equal : operator === "!=" ? !equal : false;
+  }
+
+  if (actual === null || typeof actual === "boolean") {
+    return operator === "!=";
+  }
+  return orderBy(String(actual).localeCompare(expected, undefined, { sensitivity: "base" }), operator);
+}
+
+function isSet(value: FieldValue): boolean {
+  if (Array.isArray(value)) {
+    return value.length > 0;
+  }
+  return value !== undefined && value !== null && value !== false && value !== "";
+}
+
+function evaluate(node: QueryNode, note: NoteMetadata): boolean {
+  switch (node.type) {
+    case "and":
+      return evaluate(node.left, note) && evaluate(node.right, note);
+    case "or":
+      return evaluate(node.left, note) || evaluate(node.right, note);
+    case "not":
+      return !evaluate(node.operand, note);
+    case "tag":
+      return note.tags.some((tag) => {
+        const key = tag.toLowerCase();
+        return key === node.tag || key.startsWith(`${node.tag}/`);
+      });
+    case "exists":
+      return isSet(getField(note, node.field));
+    case "compare": {
+      const value = getField(note, node.field);
+      if (value === undefined) {
+        return node.operator === "!=";
+      }
+      if (!Array.isArray(value)) {
+        return compareScalar(value, node.operator, node.value);
+      }
+      // Lists: "contains" and equality test the items; != means no item equals
+      if (node.operator === "!=") {
+        return value.every((item) => compareScalar(item, "!=", node.value));
+      }
+      const itemOperator = node.operator === "contains" ? "==" : node.operator;
+      return value.some((item) => compareScalar(item, itemOperator, node.value));
+    }
+  }
+}
+
+function getSortKey(note: NoteMetadata, field: string): string | number | null {
+  const value = getField(note, field);
+  const first = Array.isArray(value) ? value[0] : value;
+  if (first === undefined || first === null) {
+    return null;
+  }
+  if (first instanceof Date) {
+    return first.getTime();
+  }
+  return typeof first === "number" ? first : String(first);
+}
+
+/**
+ * Find the notes matching a query
+ * @param filter - Query text; an empty query matches every note
+ * @throws AppError "invalid-argument" describing where the query is malformed
+ */
+export async function queryNotes(filter: string, options: NoteQueryOptions = {}): Promise<NoteMetadata[]> {
+  const query = parseQuery(filter);
+  const { notes } = await getNoteIndex();
+  const sortBy = options.sortBy ?? "path";
+  const direction = options.descending ? -1 : 1;
+
+  const matches = [...notes.values()].filter((note) => query === null || evaluate(query, note));
+  const keys = new Map(matches.map((note) => [note.path, getSortKey(note, sortBy)]));
+
+  matches.sort((left, right) => {
+    const leftKey = keys.get(left.path) ?? null;
+    const rightKey = keys.get(right.path) ?? null;
+    // Notes without the field go last either way
+    if (leftKey === null || rightKey === null) {
+      return leftKey === rightKey ? left.path.localeCompare(right.path) : leftKey === null ? 1 : -1;
+    }
+    const order =
+      typeof leftKey === "number" && typeof rightKey === "number"
+        ? leftKey - rightKey
+        : String(leftKey).localeCompare(String(rightKey), undefined, { numeric: true });
+    return order * direction || left.path.localeCompare(right.path);
+  });
+
+  return matches.slice(0, options.limit).map(({ path, title, aliases, tags, frontmatter, modified, size }) => ({
+    path,
+    title,
+    aliases,
+    tags,
+    frontmatter,
+    modified,
+    size,
+  }));
+}
@@ -0,0 +1,274 @@
+/**
+ * Notion import - unpacks a Notion Markdown or HTML export into the workspace
+ * Notion's "Title <32-hex id>" names become plain titles, HTML pages are
+ * converted to markdown, images go through the upload pipeline into
+ * the assets folder and links between pages are rewritten to the new paths.
+ */
+
+import TurndownService from "turndown";
+import type { ImportIssue, ImportReport } from "../types";
+import * as fsService from "./fs-service";
+import { isExternalLink, rewriteLinkDestinations } from "./markdownUtils";
+import { getParentPath, getRelativePath, joinPath } from "./pathUtils";
+import { readZipEntries } from "./zip";
+import type { ZipEntry } from "./zip";
+
+const NOTION_ID_PATTERN = /\s*[0-9a-f]{32}$/i;
+const NOTION_URL_ID_PATTERN = /^https?:\/\/(?:www\.)?notion\.(?:so|site)\/.*?([0-9a-f]{32})(?:[?#].*)?$/i;
+const IMAGE_EXTENSIONS = new Set(["png", "jpg", "jpeg", "gif", "webp", "svg"]);
+const IGNORED_ENTRY_PATTERN = /(^|\/)(__MACOSX|\.DS_Store)(\/|$)/;
+
+const turndown = new TurndownService({
+  headingStyle: "atx",
+  codeBlockStyle: "fenced",
+  bulletListMarker: "-",
+});
+
+type ExportKind = "page" | "html" | "image" | "file";
+
+interface ExportFile {
+  /** Path inside the archive */
+  originalPath: string;
+  entry: ZipEntry;
+  kind: ExportKind;
+}
+
+function getExtension(name: string): string {
+  const dotIndex = name.lastIndexOf(".");
+  return dotIndex <= 0 ? "" : name.slice(dotIndex + 1).toLowerCase();
+}
+
+function getExportKind(name: string): ExportKind {
+  const extension = getExtension(name);
+  if (extension === "md") {
+    return "page";
+  }
+  if (extension === "html") {
+    return "html";
+  }
+  return IMAGE_EXTENSIONS.has(extension) ? "image" : "file";
+}
+
+/**
+ * Read every file in the export, descending into the per-part archives
+ * Notion wraps large exports in ("Export-<id>-Part-1.zip", ...)
+ */
+async function collectExportFiles(archive: Blob): Promise<ExportFile[]> {
+  const files: ExportFile[] = [];
+
+  for (const entry of await readZipEntries(archive)) {
+    if (entry.isDirectory || IGNORED_ENTRY_PATTERN.test(entry.name)) {
+      continue;
+    }
+
+    if (getExtension(entry.name) === "zip") {
+      files.push(...(await collectExportFiles(await entry.read())));
+      continue;
+    }
+
+    files.push({ originalPath: entry.name, entry, kind: getExportKind(entry.name) });
+  }
+
+  return files;
+}
+
+/**
+ * Plans clean names for the export: ids are stripped, and a page and its
+ * subpage folder ("Page <id>.md" and "Page <id>/") keep sharing one name
+ */
+function createPathPlanner(destination: string) {
+  const baseNames = new Map<string, string>();
+  const usedNames = new Map<string, Set<string>>();
+
+  function cleanSegment(parent: string, segment: string, extension: string): string {
+    const originalBase = extension ? segment.slice(0, -(extension.length + 1)) : segment;
+    const key = `${parent}\0${originalBase}`;
+
+    let base = baseNames.get(key);
+    if (base === undefined) {
+      const used = usedNames.get(parent) ?? new Set<string>();
+      usedNames.set(parent, used);
+
+      const title = originalBase.replace(NOTION_ID_PATTERN, "").trim() || "Untitled";
+      base = title;
+      for (let counter = 1; used.has(base.toLowerCase()); counter += 1) {
+        base = `${title}-${counter}`;
+      }
+
+      used.add(base.toLowerCase());
+      baseNames.set(key, base);
+    }
+
+    return base;
+  }
+
+  return (originalPath: string, extension: string): string => {
+    const segments = originalPath.split("/");
+    let current = destination;
+
+    segments.forEach((segment, index) => {
+      const isLast = index === segments.length - 1;
+      const sourceExtension = isLast ? getExtension(segment) : "";
+      const base = cleanSegment(current, segment, sourceExtension);
+      current = `${current}/${isLast && extension ? `${base}.${extension}` : base}`;
+    });
+
+    return current;
+  };
+}
+
+function getNotionId(path: string): string | null {
+  const name = path.split("/").pop() ?? path;
+  const base = name.includes(".") ? name.slice(0, name.lastIndexOf(".")) : name;
+  return NOTION_ID_PATTERN.exec(base)?.[0].trim().toLowerCase() ?? null;
+}
+
+function convertHtmlPage(html: string): string {
+  const document = new DOMParser().parseFromString(html, "text/html");
+
+  // Notion wraps every image in a link to itself; keep just the image
+  for (const link of Array.from(document.querySelectorAll("a"))) {
+    const image = link.querySelector("img");
+    if (image && link.children.length === 1 && !link.textContent?.trim()) {
+      link.replaceWith(image);
+    }
+  }
+
+  const title = document.querySelector(".page-title")?.textContent?.trim();
+  const body = document.querySelector(".page-body") ?? document.body;
+  const markdown = turndown.turndown(body as HTMLElement);
+
+  return title ? `# ${title}\n\n${markdown}` : markdown;
+}
+
+/**
+ * Import a Notion export archive picked by the user into a workspace folder
+ * @param destinationPath - Workspace folder to create the import folder in
+ * @returns Import report, or null if the file picker was cancelled
+ */
+export async function importNotionExport(destinationPath: string): Promise<ImportReport | null> {
+  const archive = await fsService.showImportFileDialog([{ name: "Notion export", extensions: ["zip"] }]);
+  if (!archive) {
+    return null;
+  }
+
+  const files = await collectExportFiles(archive);
+  const parentFolder = await fsService.getRelativeWorkspacePath(destinationPath);
+  const folderName = archive.name.replace(/\.zip$/i, "").replace(NOTION_ID_PATTERN, "").trim() || "Notion";
+  const destination = await fsService.getAvailablePath(joinPath(parentFolder, folderName) ?? folderName);
+  const planPath = createPathPlanner(destination);
+
+  // Original archive path -> workspace-relative path, and Notion id -> page path
+  const pathMap = new Map<string, string>();
+  const pageIds = new Map<string, string>();
+  const report: ImportReport = {
+    destination,
+    imported_notes: 0,
+    imported_attachments: 0,
+    issues: [],
+  };
+
+  await fsService.createFolder(destination);
+
+  // Attachments first so page links can point at their final location
+  for (const file of files) {
+    if (file.kind === "page" || file.kind === "html") {
+      const targetPath = planPath(file.originalPath, "md");
+      pathMap.set(file.originalPath, targetPath);
+
+      const id = getNotionId(file.originalPath);
+      if (id) {
+        pageIds.set(id, targetPath);
+      }
+      continue;
+    }
+
+    const name = file.originalPath.split("/").pop() ?? file.originalPath;
+    const data = await file.entry.read();
+    let uploadError: unknown = null;
+
+    if (file.kind === "image") {
+      try {
+        const image = new File([data], name, { lastModified: file.entry.modified.getTime() });
+        pathMap.set(file.originalPath, (await fsService.uploadImage(image)).path);
+        report.imported_attachments += 1;
+        continue;
+      } catch (error) {
+        // Oversized images stay next to their page instead
+        uploadError = error;
+      }
+    }
+
+    const targetPath = planPath(file.originalPath, getExtension(name));
+    await fsService.writeFile(targetPath, data);
+    pathMap.set(file.originalPath, targetPath);
+    report.imported_attachments += 1;
+
+    if (uploadError) {
+      report.issues.push({
+        path: targetPath,
+        line: null,
+        construct: name,
+        message: `Image kept beside its page instead of assets/: ${
+          uploadError instanceof Error ? uploadError.message : String(uploadError)
+        }`,
+      });
+    }
+  }
+
+  for (const file of files) {
+    if (file.kind !== "page" && file.kind !== "html") {
+      continue;
+    }
+
+    const targetPath = pathMap.get(file.originalPath) ?? planPath(file.originalPath, "md");
+    const text = await (await file.entry.read()).text();
+    const markdown = file.kind === "html" ? convertHtmlPage(text) : text;
+    const issues: ImportIssue[] = [];
+
+    const converted = markdown
+      .split("\n")
+      .map((line, index) =>
+        rewriteLinkDestinations(line, (href) => {
+          const notionId = NOTION_URL_ID_PATTERN.exec(href)?.[1].toLowerCase();
+          if (notionId) {
+            const page = pageIds.get(notionId);
+            return page ? getRelativePath(targetPath, page) : null;
+          }
+          if (isExternalLink(href)) {
+            return null;
+          }
+
+          const hashIndex = href.indexOf("#");
+          const linkPath = hashIndex === -1 ? href : href.slice(0, hashIndex);
+          let decoded = linkPath;
+          try {
+            decoded = decodeURIComponent(linkPath);
+          } catch {
+            // Keep malformed escapes as written
+          }
+
+          const original = joinPath(getParentPath(file.originalPath), decoded);
+          const mapped = original === null ? undefined : pathMap.get(original);
+          if (!mapped) {
+            issues.push({
+              path: targetPath,
+              line: index + 1,
+              construct: href,
+              message: "Link target not found in the export",
+            });
+            return null;
+          }
+
+          return `${getRelativePath(targetPath, mapped)}${hashIndex === -1 ? "" : href.slice(hashIndex)}`;
+        })
+      )
+      .join("\n");
+
+    await fsService.writeFile(targetPath, converted);
+    report.imported_notes += 1;
+    report.issues.push(...issues);
+  }
+
+  return report;
+}
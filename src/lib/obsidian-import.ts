@@ -0,0 +1,398 @@
+/**
+ * Obsidian import - copies a vault into the workspace
+ * Wikilinks and embeds become relative markdown links, callouts become
+ * admonition directives and attachments move under assets/<vault>/.
+ * Constructs without an equivalent are left in place and reported.
+ */
+
+import type { ImportIssue, ImportReport } from "../types";
+import * as fsService from "./fs-service";
+import { resolveWikilinkTarget } from "./link-checker";
+import type { WikilinkIndex } from "./link-checker";
+import {
+  getNoteTitle,
+  isExternalLink,
+  rewriteLinkDestinations,
+  slugifyHeading,
+  splitFrontmatter,
+} from "./markdownUtils";
+import { formatLinkTarget, getParentPath, getRelativePath, joinPath } from "./pathUtils";
+
+const NOTE_PATTERN = /\.md$/i;
+const FENCE_PATTERN = /^\s*(```|~~~)\s*([\w-]*)/;
+const INLINE_CODE_PATTERN = /(`[^`]*`)/;
+const WIKILINK_PATTERN = /(!?)\[\[([^\]|#]*)(#[^\]|]*)?(?:\|([^\]]*))?\]\]/g;
+const CALLOUT_PATTERN = /^>\s*\[!([\w-]+)\][+-]?[ \t]*(.*)$/;
+const BLOCK_ID_PATTERN = /\s\^[\w-]+\s*$/;
+const EMBED_SIZE_PATTERN = /^\d+(x\d+)?$/;
+const FRONTMATTER_KEY_PATTERN = /^([\w-]+):/;
+
+/** Editor admonition type for each Obsidian callout type */
+const CALLOUT_TYPES: Record<string, string> = {
+  note: "note",
+  abstract: "info",
+  summary: "info",
+  tldr: "info",
+  info: "info",
+  todo: "info",
+  question: "info",
+  help: "info",
+  faq: "info",
+  tip: "tip",
+  hint: "tip",
+  important: "tip",
+  success: "tip",
+  check: "tip",
+  done: "tip",
+  warning: "caution",
+  caution: "caution",
+  attention: "caution",
+  failure: "danger",
+  fail: "danger",
+  missing: "danger",
+  danger: "danger",
+  error: "danger",
+  bug: "danger",
+};
+
+/** Deprecated singular frontmatter keys and their current names */
+const FRONTMATTER_RENAMES: Record<string, string> = {
+  alias: "aliases",
+  tag: "tags",
+};
+
+/** Frontmatter keys that only affect Obsidian's own rendering */
+const OBSIDIAN_ONLY_KEYS = new Set(["cssclass", "cssclasses"]);
+
+interface VaultFile {
+  /** Path relative to the vault root */
+  relativePath: string;
+  handle: FileSystemFileHandle;
+  isNote: boolean;
+}
+
+type VaultIndex = WikilinkIndex<VaultFile>;
+
+interface ConversionContext {
+  /** Vault-relative path of the note being converted */
+  sourcePath: string;
+
+  /** Workspace-relative path the note is written to */
+  targetPath: string;
+
+  index: VaultIndex;
+
+  /** Workspace-relative destination of a vault file */
+  destinationOf: (file: VaultFile) => string;
+}
+
+function buildVaultIndex(files: VaultFile[]): VaultIndex {
+  const byLowerPath = new Map<string, VaultFile>();
+  const byName = new Map<string, VaultFile[]>();
+
+  for (const file of files) {
+    byLowerPath.set(file.relativePath.toLowerCase(), file);
+
+    const name = (file.relativePath.split("/").pop() ?? file.relativePath).toLowerCase();
+    const names = file.isNote ? [name, name.replace(NOTE_PATTERN, "")] : [name];
+    for (const key of names) {
+      byName.set(key, [...(byName.get(key) ?? []), file]);
+    }
+  }
+
+  return { byLowerPath, byName };
+}
+
+/**
+ * Resolve a standard markdown link relative to the note, then the vault root
+ */
+function resolveMarkdownTarget(target: string, sourcePath: string, index: VaultIndex): VaultFile | null {
+  let decoded = target;
+  try {
+    decoded = decodeURI(target);
+  } catch {
+    // Keep malformed escapes as written
+  }
+
+  for (const candidate of [joinPath(getParentPath(sourcePath), decoded), joinPath(".", decoded)]) {
+    const match = candidate === null ? undefined : index.byLowerPath.get(candidate.toLowerCase());
+    if (match) {
+      return match;
+    }
+  }
+
+  return null;
+}
+
+function convertFrontmatter(frontmatter: string, context: ConversionContext, issues: ImportIssue[]): string {
+  const output: string[] = [];
+  let skipping = false;
+
+  frontmatter.split(/\r?\n/).forEach((line, index) => {
+    const key = FRONTMATTER_KEY_PATTERN.exec(line)?.[1];
+
+    if (key === undefined) {
+      // Indented values and list items belong to the previous key
+      if (!skipping) {
+        output.push(line);
+      }
+      return;
+    }
+
+    skipping = OBSIDIAN_ONLY_KEYS.has(key);
+    if (skipping) {
+      issues.push({
+        path: context.targetPath,
+        line: index + 2,
+        construct: line,
+        message: `Removed Obsidian-only frontmatter key "${key}"`,
+      });
+      return;
+    }
+
+    const renamed = FRONTMATTER_RENAMES[key];
+    output.push(renamed ? `${renamed}${line.slice(key.length)}` : line);
+  });
+
+  return output.join("\n");
+}
+
+function convertLinks(text: string, line: number, context: ConversionContext, issues: ImportIssue[]): string {
+  const { sourcePath, targetPath, index } = context;
+  const report = (construct: string, message: string) =>
+    issues.push({ path: targetPath, line, construct, message });
+  const linkTo = (file: VaultFile, suffix = "") =>
+    formatLinkTarget(`${getRelativePath(targetPath, context.destinationOf(file))}${suffix}`);
+
+  // Standard links first, so the links generated from wikilinks are not rewritten twice
+  const withMarkdownLinks = rewriteLinkDestinations(text, (destination) => {
+    if (isExternalLink(destination)) {
+      return null;
+    }
+
+    const hashIndex = destination.indexOf("#");
+    const path = hashIndex === -1 ? destination : destination.slice(0, hashIndex);
+    const suffix = hashIndex === -1 ? "" : destination.slice(hashIndex);
+    const file = resolveMarkdownTarget(path, sourcePath, index);
+
+    return file ? `${getRelativePath(targetPath, context.destinationOf(file))}${suffix}` : null;
+  });
+
+  return withMarkdownLinks.replace(
+    WIKILINK_PATTERN,
+    (match, bang: string, target: string, anchor: string | undefined, label: string | undefined) => {
+      const isEmbed = bang === "!";
+      const heading = anchor?.slice(1) ?? "";
+      const isBlockReference = heading.startsWith("^");
+
+      if (!target.trim()) {
+        if (isEmbed || isBlockReference || !heading) {
+          report(match, "Links to blocks within the same note are not supported");
+          return match;
+        }
+        return `[${label ?? heading}](#${slugifyHeading(heading)})`;
+      }
+
+      const file = resolveWikilinkTarget(sourcePath, target, index, (vaultFile) => vaultFile.relativePath);
+      if (!file) {
+        report(match, "Link target not found in the vault");
+        return match;
+      }
+
+      if (isEmbed && !file.isNote) {
+        const alt = label && !EMBED_SIZE_PATTERN.test(label) ? label : getNoteTitle(file.relativePath);
+        return `![${alt}](${linkTo(file)})`;
+      }
+
+      if (isEmbed) {
+        report(match, "Note embeds are not supported; converted to a link");
+      }
+      if (isBlockReference) {
+        report(match, "Block references are not supported; linked to the note instead");
+        return `[${label ?? getNoteTitle(target.trim())}](${linkTo(file)})`;
+      }
+
+      const title = getNoteTitle(target.trim());
+      if (!heading) {
+        return `[${label ?? title}](${linkTo(file)})`;
+      }
+      return `[${label ?? `${title} > ${heading}`}](${linkTo(file, `#${slugifyHeading(heading)}`)})`;
+    }
+  );
+}
+
+/**
+ * Convert links outside inline code spans and flag inline-only constructs
+ */
+function convertLine(text: string, line: number, context: ConversionContext, issues: ImportIssue[]): string {
+  if (text.includes("%%")) {
+    issues.push({
+      path: context.targetPath,
+      line,
+      construct: text.trim(),
+      message: "Obsidian comments are kept as plain text",
+    });
+  }
+  if (BLOCK_ID_PATTERN.test(text)) {
+    issues.push({
+      path: context.targetPath,
+      line,
+      construct: text.trim(),
+      message: "Block identifiers are kept as plain text",
+    });
+  }
+
+  return text
+    .split(INLINE_CODE_PATTERN)
+    .map((part, index) => (index % 2 === 1 ? part : convertLinks(part, line, context, issues)))
+    .join("");
+}
+
+/**
+ * Convert a note's Obsidian-specific syntax to the editor's markdown flavor
+ */
+function convertNote(content: string, context: ConversionContext): { content: string; issues: ImportIssue[] } {
+  const issues: ImportIssue[] = [];
+  const { frontmatter, body } = splitFrontmatter(content);
+
+  // Line numbers in the report refer to the original file
+  const lineOffset = content.slice(0, content.length - body.length).split(/\r?\n/).length - 1;
+  const lines = body.split(/\r?\n/);
+  const output: string[] = [];
+  let fence: string | null = null;
+
+  for (let index = 0; index < lines.length; index += 1) {
+    const line = lines[index];
+    const lineNumber = index + lineOffset + 1;
+    const fenceMatch = FENCE_PATTERN.exec(line);
+
+    if (fence) {
+      if (fenceMatch?.[1] === fence) {
+        fence = null;
+      }
+      output.push(line);
+      continue;
+    }
+
+    if (fenceMatch) {
+      fence = fenceMatch[1];
+      const language = fenceMatch[2].toLowerCase();
+      if (language === "dataview" || language === "dataviewjs") {
+        issues.push({
+          path: context.targetPath,
+          line: lineNumber,
+          construct: line.trim(),
+          message: "Dataview queries are kept as code blocks",
+        });
+      }
+      output.push(line);
+      continue;
+    }
+
+    const callout = CALLOUT_PATTERN.exec(line);
+    if (!callout) {
+      output.push(convertLine(line, lineNumber, context, issues));
+      continue;
+    }
+
+    const calloutType = callout[1].toLowerCase();
+    const admonition = CALLOUT_TYPES[calloutType];
+    if (!admonition) {
+      issues.push({
+        path: context.targetPath,
+        line: lineNumber,
+        construct: line.trim(),
+        message: `Callout type "${calloutType}" has no equivalent; converted to a note`,
+      });
+    }
+
+    output.push(`:::${admonition ?? "note"}`);
+    if (callout[2].trim()) {
+      output.push(`**${convertLine(callout[2].trim(), lineNumber, context, issues)}**`, "");
+    }
+
+    while (
+      index + 1 < lines.length &&
+      lines[index + 1].startsWith(">") &&
+      !CALLOUT_PATTERN.test(lines[index + 1])
+    ) {
+      index += 1;
+      output.push(convertLine(lines[index].replace(/^>[ \t]?/, ""), index + lineOffset + 1, context, issues));
+    }
+    output.push(":::");
+  }
+
+  const convertedBody = output.join("\n");
+  if (frontmatter === null) {
+    return { content: convertedBody, issues };
+  }
+
+  const convertedFrontmatter = convertFrontmatter(frontmatter, context, issues);
+  return { content: `---\n${convertedFrontmatter}\n---\n${convertedBody}`, issues };
+}
+
+/**
+ * Import an Obsidian vault picked by the user into a workspace folder
+ * The vault lands in a new folder named after it; hidden folders such as
+ * .obsidian and .trash are skipped.
+ * @param destinationPath - Workspace folder to create the vault folder in
+ * @returns Import report, or null if the folder picker was cancelled
+ */
+export async function importObsidianVault(destinationPath: string): Promise<ImportReport | null> {
+  const source = await fsService.showImportFolderDialog();
+  if (!source) {
+    return null;
+  }
+
+  const files: VaultFile[] = [];
+  for await (const entry of fsService.walkExternalDirectory(source)) {
+    if (entry.handle.kind === "file") {
+      files.push({
+        relativePath: entry.relativePath,
+        handle: entry.handle,
+        isNote: NOTE_PATTERN.test(entry.relativePath),
+      });
+    }
+  }
+
+  const parentFolder = await fsService.getRelativeWorkspacePath(destinationPath);
+  const destination = await fsService.getAvailablePath(joinPath(parentFolder, source.name) ?? source.name);
+  const { assets } = await fsService.getWorkspaceSettings();
+  const assetsFolder = `${await fsService.getRelativeWorkspacePath(assets.root)}/${destination.split("/").pop()}`;
+  const index = buildVaultIndex(files);
+
+  const destinationOf = (file: VaultFile) =>
+    file.isNote ? `${destination}/${file.relativePath}` : `${assetsFolder}/${file.relativePath}`;
+
+  await fsService.createFolder(destination);
+
+  const report: ImportReport = {
+    destination,
+    imported_notes: 0,
+    imported_attachments: 0,
+    issues: [],
+  };
+
+  for (const file of files) {
+    const targetPath = destinationOf(file);
+    const sourceFile = await file.handle.getFile();
+
+    if (!file.isNote) {
+      await fsService.writeFile(targetPath, sourceFile);
+      report.imported_attachments += 1;
+      continue;
+    }
+
+    const converted = convertNote(await sourceFile.text(), {
+      sourcePath: file.relativePath,
+      targetPath,
+      index,
+      destinationOf,
+    });
+    await fsService.writeFile(targetPath, converted.content);
+    report.imported_notes += 1;
+    report.issues.push(...converted.issues);
+  }
+
+  return report;
+}
@@ -0,0 +1,74 @@
+/**
+ * Long-running operations - cancellable by id
+ * Callers pick an id with createOperationId(), pass it to the operation and
+ * can later hand the same id to cancelOperation(); the operation stops at its
+ * next checkpoint with an AbortError.
+ */
+
+const activeOperations = new Map<string, AbortController>();
+
+export function createOperationId(): string {
+  return typeof crypto !== "undefined" && "randomUUID" in crypto
+    ? crypto.randomUUID()
+    : `${Date.now()}-${Math.random().toString(36).slice(2)}`;
+}
+
+/**
+ * Register an operation
+ * @returns Signal that aborts when the operation is cancelled (undefined without an id)
+ */
+export function beginOperation(id: string | undefined): AbortSignal | undefined {
+  if (id === undefined) {
+    return undefined;
+  }
+  if (activeOperations.has(id)) {
+    throw new Error(`Operation already running: ${id}`);
+  }
+
+  const controller = new AbortController();
+  activeOperations.set(id, controller);
+  return controller.signal;
+}
+
+/**
+ * Unregister an operation
+ * A cancelled operation's id is free for reuse before it ends, so only the
+ * registration that handed out this signal is removed.
+ * @param signal - Signal beginOperation() returned for this run
+ */
+export function endOperation(id: string | undefined, signal: AbortSignal | undefined): void {
+  if (id !== undefined && signal !== undefined && activeOperations.get(id)?.signal === signal) {
+    activeOperations.delete(id);
+  }
+}
+
+/**
+ * Cancel a running operation
+ * @returns False if no operation with this id is running
+ */
+export function cancelOperation(id: string): boolean {
+  const controller = activeOperations.get(id);
+  if (!controller) {
+    return false;
+  }
+
+  controller.abort(new DOMException("Operation cancelled", "AbortError"));
+  activeOperations.delete(id);
+  return true;
+}
+
+/**
+ * Run a task over items with at most `limit` of them in flight
+ */
+export async function runWithConcurrency<T>(items: T[], limit: number, task: (item: T) => Promise<void>): Promise<void> {
+  let next = 0;
+  const workers = Array.from({ length: Math.min(limit, items.length) }, async () => {
+    while (next < items.length) {
+      const item = items[next];
+      next += 1;
+      await task(item);
+    }
+  });
+
+  await Promise.all(workers);
+}
@@ -0,0 +1,80 @@
+/**
+ * Note structure - headings, references and code blocks parsed once per file version
+ * The cache is keyed by path and invalidated by size/lastModified, so
+ * repeated requests while a note is open do not re-parse it
+ */
+
+import type { CodeBlock, NoteReference, OutlineHeading } from "../types";
+import * as fsService from "./fs-service";
+import { findCodeBlocks, parseHeadings, parseReferences } from "./markdownUtils";
+
+const MAX_CACHED_NOTES = 100;
+
+/** What has been parsed out of one version of a note so far */
+interface NoteStructure {
+  headings?: OutlineHeading[];
+  references?: NoteReference[];
+  codeBlocks?: CodeBlock[];
+}
+
+interface CachedNote {
+  size: number;
+  lastModified: number;
+  structure: NoteStructure;
+}
+
+const noteCache = new Map<string, CachedNote>();
+
+/**
+ * Get the cached structure of a note's current version (empty if the note changed)
+ */
+async function getCachedNote(path: string): Promise<{ file: File; structure: NoteStructure }> {
+  const key = await fsService.getRelativeWorkspacePath(path);
+  const file = await fsService.getFile(key);
+
+  const cached = noteCache.get(key);
+  const isCurrent = cached !== undefined && cached.size === file.size && cached.lastModified === file.lastModified;
+  const structure = isCurrent ? cached.structure : {};
+
+  // Re-insert so the Map's order tracks recency; evict the oldest entry
+  noteCache.delete(key);
+  noteCache.set(key, { size: file.size, lastModified: file.lastModified, structure });
+  if (noteCache.size > MAX_CACHED_NOTES) {
+    const oldest = noteCache.keys().next().value;
+    if (oldest !== undefined) {
+      noteCache.delete(oldest);
+    }
+  }
+
+  return { file, structure };
+}
+
+export async function getOutline(path: string): Promise<OutlineHeading[]> {
+  const { file, structure } = await getCachedNote(path);
+  if (!structure.headings) {
+    structure.headings = parseHeadings(await file.text());
+  }
+  return structure.headings;
+}
+
+/**
+ * List a note's footnotes and link reference definitions with their usages
+ */
+export async function getReferences(path: string): Promise<NoteReference[]> {
+  const { file, structure } = await getCachedNote(path);
+  if (!structure.references) {
+    structure.references = parseReferences(await file.text());
+  }
+  return structure.references;
+}
+
+/**
+ * List a note's fenced code blocks in order
+ */
+export async function getCodeBlocks(path: string): Promise<CodeBlock[]> {
+  const { file, structure } = await getCachedNote(path);
+  if (!structure.codeBlocks) {
+    structure.codeBlocks = findCodeBlocks(await file.text());
+  }
+  return structure.codeBlocks;
+}
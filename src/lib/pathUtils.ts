@@ -0,0 +1,69 @@
+/**
+ * Workspace-relative path helpers (forward slashes, no leading "./")
+ */
+
+/**
+ * Get the parent folder of a workspace-relative path ("." for top level)
+ */
+export function getParentPath(path: string): string {
+  return path.includes("/") ? path.substring(0, path.lastIndexOf("/")) : ".";
+}
+
+/**
+ * Join a folder and a relative path, resolving "." and ".." segments
+ * Returns null when the result would escape the workspace root
+ *
+ * @example
+ * joinPath('notes/daily', '../assets/a.png') // → 'notes/assets/a.png'
+ */
+export function joinPath(folder: string, relativePath: string): string | null {
+  const segments = folder === "." ? [] : folder.split("/").filter(Boolean);
+
+  for (const segment of relativePath.split("/")) {
+    if (segment === "" || segment === ".") {
+      continue;
+    }
+    if (segment === "..") {
+      if (segments.length === 0) {
+        return null;
+      }
+      segments.pop();
+      continue;
+    }
+    segments.push(segment);
+  }
+
+  return segments.join("/");
+}
+
+/**
+ * Build the relative path that leads from a file to another workspace path
+ *
+ * @example
+ * getRelativePath('notes/daily/today.md', 'assets/a.png') // → '../../assets/a.png'
+ * getRelativePath('notes/a.md', 'notes/b.md') // → 'b.md'
+ */
+export function getRelativePath(fromFile: string, toPath: string): string {
+  const fromSegments = getParentPath(fromFile) === "." ? [] : getParentPath(fromFile).split("/");
+  const toSegments = toPath.split("/");
+
+  let common = 0;
+  while (
+    common < fromSegments.length &&
+    common < toSegments.length - 1 &&
+    fromSegments[common] === toSegments[common]
+  ) {
+    common += 1;
+  }
+
+  const upward = fromSegments.slice(common).map(() => "..");
+  return [...upward, ...toSegments.slice(common)].join("/");
+}
+
+/**
+ * Format a path as a markdown link destination
+ * Destinations containing spaces or parentheses are wrapped in <...>
+ */
+export function formatLinkTarget(target: string): string {
+  return /[\s()]/.test(target) ? `<${target}>` : target;
+}
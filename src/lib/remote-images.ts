@@ -0,0 +1,187 @@
+/**
+ * Remote images - downloads http(s) images into assets/ so notes stay
+ * readable offline. Downloads are subject to CORS: servers that do not allow
+ * cross-origin reads are reported as failures and their links left alone.
+ */
+
+import type { LocalizeImagesReport } from "../types";
+import { AppError, isAppError } from "./errors";
+import * as fsService from "./fs-service";
+import { findLinkDestinations, getProseLines, rewriteLinkDestinations } from "./markdownUtils";
+import { getRelativePath } from "./pathUtils";
+
+const REMOTE_IMAGE_PATTERN = /^https?:\/\//i;
+
+const FETCH_TIMEOUT_MS = 30_000;
+
+/** File extension for each image content type accepted by uploadImage */
+const IMAGE_EXTENSIONS: Record<string, string> = {
+  "image/png": "png",
+  "image/jpeg": "jpg",
+  "image/gif": "gif",
+  "image/webp": "webp",
+  "image/svg+xml": "svg",
+};
+
+function getImageFilename(url: URL, extension: string): string {
+  let name = url.pathname.split("/").pop() ?? "";
+  try {
+    name = decodeURIComponent(name);
+  } catch {
+    // Keep the raw segment
+  }
+
+  const stem = name.replace(/\.[^.]*$/, "") || "image";
+  return `${stem}.${extension}`;
+}
+
+function tooLargeError(): AppError {
+  return new AppError("too-large", `Image exceeds maximum of ${fsService.MAX_IMAGE_SIZE} bytes (10MB)`);
+}
+
+/**
+ * Read a response body, giving up as soon as it passes the image size limit
+ */
+async function readImageBody(response: Response): Promise<Uint8Array[]> {
+  if (Number(response.headers.get("content-length")) > fsService.MAX_IMAGE_SIZE) {
+    throw tooLargeError();
+  }
+
+  const reader = response.body?.getReader();
+  if (!reader) {
+    const bytes = new Uint8Array(await response.arrayBuffer());
+    if (bytes.length > fsService.MAX_IMAGE_SIZE) {
+      throw tooLargeError();
+    }
+    return [bytes];
+  }
+
+  const chunks: Uint8Array[] = [];
+  let size = 0;
+  try {
+    for (let result = await reader.read(); !result.done; result = await reader.read()) {
+      size += result.value.length;
+      if (size > fsService.MAX_IMAGE_SIZE) {
+        throw tooLargeError();
+      }
+      chunks.push(result.value);
+    }
+  } finally {
+    await reader.cancel().catch(() => undefined);
+  }
+  return chunks;
+}
+
+async function downloadImage(url: URL, signal: AbortSignal): Promise<File> {
+  const response = await fetch(url, { signal, credentials: "omit" });
+  if (!response.ok) {
+    throw new AppError("io", `Download failed with HTTP ${response.status}`);
+  }
+
+  const contentType = response.headers.get("content-type")?.split(";")[0].trim().toLowerCase() ?? "";
+  const extension = IMAGE_EXTENSIONS[contentType];
+  if (!extension) {
+    throw new AppError("unsupported", `Unsupported content type: ${contentType || "unknown"}`);
+  }
+
+  return new File(await readImageBody(response), getImageFilename(url, extension), { type: contentType });
+}
+
+/**
+ * Download an image and store it through the upload pipeline
+ * Gives up on images over the upload size limit before reading them whole,
+ * and on servers that don't answer in time.
+ * @returns Workspace-relative asset path
+ */
+export async function localizeRemoteImage(url: string, notePath: string | null = null): Promise<string> {
+  const parsed = new URL(url);
+  if (parsed.protocol !== "http:" && parsed.protocol !== "https:") {
+    throw new AppError("invalid-argument", "Only http(s) images can be downloaded");
+  }
+
+  const controller = new AbortController();
+  const timeout = setTimeout(() => controller.abort(), FETCH_TIMEOUT_MS);
+  let file: File;
+  try {
+    file = await downloadImage(parsed, controller.signal);
+  } catch (error) {
+    if (controller.signal.aborted) {
+      throw new AppError("timeout", "The image took too long to download", { cause: error });
+    }
+    throw error;
+  } finally {
+    clearTimeout(timeout);
+  }
+
+  const { path } = await fsService.uploadImage(file, { notePath });
+  return path;
+}
+
+/**
+ * Download every remote image in a note and point its links at the local copies
+ * Each URL is downloaded once; images inside code blocks are left alone. If the
+ * note changes during the downloads its links are left as they are, and the
+ * call fails with a "conflict" error.
+ */
+export async function localizeRemoteImages(path: string): Promise<LocalizeImagesReport> {
+  const notePath = await fsService.getRelativeWorkspacePath(path);
+  const { content, hash } = await fsService.readFileWithHash(notePath);
+  const proseLines = getProseLines(content);
+
+  const urls = new Set<string>();
+  for (const { text } of proseLines) {
+    for (const { destination, isImage } of findLinkDestinations(text)) {
+      if (isImage && REMOTE_IMAGE_PATTERN.test(destination)) {
+        urls.add(destination);
+      }
+    }
+  }
+
+  const report: LocalizeImagesReport = { localized: 0, failed: [] };
+  if (urls.size === 0) {
+    return report;
+  }
+
+  const localPaths = new Map<string, string>();
+  for (const url of urls) {
+    try {
+      const assetPath = await localizeRemoteImage(url, notePath);
+      localPaths.set(url, getRelativePath(notePath, assetPath));
+    } catch (error) {
+      report.failed.push({ url, message: error instanceof Error ? error.message : String(error) });
+    }
+  }
+
+  if (localPaths.size === 0) {
+    return report;
+  }
+
+  const proseLineNumbers = new Set(proseLines.map(({ line }) => line));
+  const updated = content
+    .split("\n")
+    .map((text, index) =>
+      proseLineNumbers.has(index + 1)
+        ? rewriteLinkDestinations(text, (destination, isImage) => {
+            const localPath = isImage ? localPaths.get(destination) : undefined;
+            if (localPath) {
+              report.localized += 1;
+            }
+            return localPath ?? null;
+          })
+        : text
+    )
+    .join("\n");
+
+  try {
+    await fsService.applyEdits(notePath, [{ start: 0, end: content.length, replacement: updated }], hash);
+  } catch (error) {
+    if (isAppError(error, "conflict")) {
+      throw new AppError("conflict", "The note changed while its images downloaded; try again to update its links", {
+        path: notePath,
+        cause: error,
+      });
+    }
+    throw error;
+  }
+  return report;
+}
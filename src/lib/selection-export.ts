@@ -0,0 +1,188 @@
+/**
+ * Selection export - copies chosen notes out of the workspace with the files
+ * they link to, for sharing one project out of a large workspace
+ * Files keep their workspace-relative paths, so links between the exported
+ * notes and to their images keep working.
+ */
+
+import type {
+  OperationProgress,
+  SelectionExportFormat,
+  SelectionExportOptions,
+  SelectionExportReport,
+} from "../types";
+import { AppError } from "./errors";
+import { isNotePath } from "./filenameUtils";
+import * as fsService from "./fs-service";
+import { buildWorkspaceIndex, resolveLink } from "./link-checker";
+import type { WorkspaceIndex } from "./link-checker";
+import { findLocalLinks } from "./markdownUtils";
+import { createZipWriter } from "./zip";
+
+/** Where exported files go: a folder, or entries of an archive */
+interface ExportSink {
+  name: string;
+  write: (path: string, file: File) => Promise<void>;
+  close: () => Promise<void>;
+  abort: () => Promise<void>;
+}
+
+/**
+ * Expand the selection to note paths: notes as given, folders to every note inside
+ */
+function expandSelection(paths: string[], index: WorkspaceIndex): Set<string> {
+  const notes = new Set<string>();
+
+  for (const path of paths) {
+    if (index.files.has(path)) {
+      if (!isNotePath(path)) {
+        throw new AppError("invalid-argument", `Only notes and folders can be exported: ${path}`, { path });
+      }
+      notes.add(path);
+    } else if (path === "." || index.folders.has(path)) {
+      for (const file of index.files) {
+        if (isNotePath(file) && (path === "." || file.startsWith(`${path}/`))) {
+          notes.add(file);
+        }
+      }
+    } else {
+      throw new AppError("not-found", `Path not found: ${path}`, { path });
+    }
+  }
+
+  return notes;
+}
+
+/**
+ * Find the files linked from the selected notes
+ * With followLinks, linked notes join the export and their links are followed in turn.
+ */
+async function collectLinkedFiles(
+  notes: Set<string>,
+  index: WorkspaceIndex,
+  followLinks: boolean,
+  signal?: AbortSignal
+): Promise<Set<string>> {
+  const assets = new Set<string>();
+  const pending = [...notes];
+
+  for (let note = pending.pop(); note !== undefined; note = pending.pop()) {
+    signal?.throwIfAborted();
+
+    for (const link of findLocalLinks(await fsService.readFile(note))) {
+      const { path } = resolveLink(note, link, index);
+      if (path === null || path === note || !index.files.has(path)) {
+        continue;
+      }
+
+      if (!isNotePath(path)) {
+        assets.add(path);
+      } else if (followLinks && !notes.has(path)) {
+        notes.add(path);
+        pending.push(path);
+      }
+    }
+  }
+
+  return assets;
+}
+
+async function openFolderSink(): Promise<ExportSink | null> {
+  const root = await fsService.showExportFolderDialog();
+  if (!root) {
+    return null;
+  }
+
+  return {
+    name: root.name,
+    write: async (path, file) => {
+      const segments = path.split("/");
+      const name = segments.pop() ?? path;
+
+      let directory = root;
+      for (const segment of segments) {
+        directory = await directory.getDirectoryHandle(segment, { create: true });
+      }
+      await fsService.writeFileHandle(await directory.getFileHandle(name, { create: true }), file);
+    },
+    close: async () => undefined,
+    // Files already written stay; the folder was the user's to begin with
+    abort: async () => undefined,
+  };
+}
+
+async function openZipSink(workspaceName: string): Promise<ExportSink | null> {
+  const target = await fsService.showSaveDialog({
+    suggestedName: `${workspaceName}-selection.zip`,
+    filters: [{ name: "ZIP archive", extensions: ["zip"] }],
+  });
+  if (!target) {
+    return null;
+  }
+
+  const writable = await target.handle.createWritable();
+  const zip = createZipWriter({ write: (chunk) => writable.write(chunk) });
+  return {
+    name: target.name,
+    write: async (path, file) =>
+      zip.addFile(path, new Uint8Array(await file.arrayBuffer()), new Date(file.lastModified)),
+    close: async () => {
+      await zip.close();
+      await writable.close();
+    },
+    abort: () => writable.abort().catch(() => undefined),
+  };
+}
+
+/**
+ * Export chosen notes, and the images and files they link to, into a folder
+ * or ZIP file picked by the user
+ * A cancelled or failed ZIP export discards the partial archive.
+ * @param paths - Notes and folders to export; a folder stands for every note inside it
+ * @returns Summary of the export, or null if the picker was cancelled
+ */
+export async function exportSelection(
+  paths: string[],
+  format: SelectionExportFormat,
+  options: SelectionExportOptions = {},
+  onProgress?: (progress: OperationProgress) => void,
+  signal?: AbortSignal
+): Promise<SelectionExportReport | null> {
+  const workspaceName = await fsService.restoreWorkspace();
+  if (!workspaceName) {
+    throw new AppError("no-workspace", "No workspace selected");
+  }
+
+  // Resolve everything before asking for a destination, so a bad selection fails first
+  const index = await buildWorkspaceIndex(signal);
+  const relativePaths = await Promise.all(paths.map((path) => fsService.getRelativeWorkspacePath(path)));
+  const notes = expandSelection(relativePaths, index);
+  if (notes.size === 0) {
+    throw new AppError("invalid-argument", "The selection holds no notes");
+  }
+  const assets = await collectLinkedFiles(notes, index, options.followLinks ?? false, signal);
+
+  const sink = format === "zip" ? await openZipSink(workspaceName) : await openFolderSink();
+  if (!sink) {
+    return null;
+  }
+
+  const files = [...notes, ...assets].sort((left, right) => left.localeCompare(right));
+  let bytes = 0;
+  try {
+    for (const [position, path] of files.entries()) {
+      signal?.throwIfAborted();
+
+      const file = await fsService.getFile(path);
+      await sink.write(path, file);
+      bytes += file.size;
+      onProgress?.({ processed: position + 1, total: files.length, current_path: path, bytes });
+    }
+    await sink.close();
+  } catch (error) {
+    await sink.abort();
+    throw error;
+  }
+
+  return { destination: sink.name, notes: notes.size, assets: assets.size };
+}
@@ -0,0 +1,45 @@
+/**
+ * Session persistence - remembers where the user left off in each workspace
+ * Stored in localStorage keyed by workspace id, so folders sharing a name
+ * keep separate sessions
+ */
+
+import type { WorkspaceSession } from "../types";
+
+const SESSION_KEY_PREFIX = "mdx-session:";
+
+function getSessionKey(workspaceId: string): string {
+  return `${SESSION_KEY_PREFIX}${workspaceId}`;
+}
+
+function isWorkspaceSession(value: unknown): value is WorkspaceSession {
+  if (!value || typeof value !== "object") {
+    return false;
+  }
+
+  const session = value as Partial<WorkspaceSession>;
+  return (
+    (session.activePath === null || typeof session.activePath === "string") &&
+    Array.isArray(session.expandedFolders) &&
+    session.expandedFolders.every((path) => typeof path === "string")
+  );
+}
+
+export function saveSession(workspaceId: string, session: WorkspaceSession): void {
+  localStorage.setItem(getSessionKey(workspaceId), JSON.stringify(session));
+}
+
+export function loadSession(workspaceId: string): WorkspaceSession | null {
+  const stored = localStorage.getItem(getSessionKey(workspaceId));
+  if (!stored) {
+    return null;
+  }
+
+  try {
+    const parsed: unknown = JSON.parse(stored);
+    return isWorkspaceSession(parsed) ? parsed : null;
+  } catch (error) {
+    console.warn("[Session] Ignoring corrupt session data:", error);
+    return null;
+  }
+}
@@ -0,0 +1,288 @@
+/**
+ * Static site export - publishes notes as a folder of linked HTML pages
+ * Each note becomes a page next to where it lives, every folder gets an
+ * index page, links between notes (wikilinks included) point at the pages,
+ * and linked images and files are copied alongside them
+ */
+
+import type { OperationProgress, SiteExportOptions, SiteExportReport } from "../types";
+import { resolveCitations } from "./bibliography";
+import { AppError } from "./errors";
+import { isNotePath, stripNoteExtension } from "./filenameUtils";
+import * as fsService from "./fs-service";
+import { buildHtmlPage, escapeHtml } from "./html-export";
+import { indexPaths, resolveLink } from "./link-checker";
+import type { WorkspaceIndex } from "./link-checker";
+import { renderMarkdown } from "./markdown-render";
+import { isExternalLink, rewriteWikilinks, slugifyHeading } from "./markdownUtils";
+import { getNoteIndex, getTitleLookup } from "./note-index";
+import type { IndexedNote } from "./note-index";
+import { formatLinkTarget, getParentPath, getRelativePath } from "./pathUtils";
+
+const FOLDER_PAGE = "index.html";
+
+interface SiteContext {
+  resolver: WorkspaceIndex;
+  published: Map<string, IndexedNote>;
+  assets: Set<string>;
+}
+
+function getPagePath(notePath: string): string {
+  return `${stripNoteExtension(notePath)}.html`;
+}
+
+function getFolderPagePath(folder: string): string {
+  return folder === "." ? FOLDER_PAGE : `${folder}/${FOLDER_PAGE}`;
+}
+
+function toHref(fromPath: string, toPath: string, anchor: string = ""): string {
+  const relative = getRelativePath(fromPath, toPath).split("/").map(encodeURIComponent).join("/");
+  return anchor ? `${relative}#${anchor}` : relative;
+}
+
+function getFolderLabel(folder: string, siteName: string): string {
+  return folder === "." ? siteName : (folder.split("/").pop() ?? folder);
+}
+
+function isDraft(note: IndexedNote): boolean {
+  return note.frontmatter.draft === true || note.frontmatter.publish === false;
+}
+
+function isInScope(path: string, scopes: string[]): boolean {
+  return scopes.some((scope) => scope === "." || path === scope || path.startsWith(`${scope}/`));
+}
+
+/**
+ * List a folder and every folder above it, innermost first
+ */
+function getAncestorFolders(path: string): string[] {
+  const folders: string[] = [];
+  let folder = getParentPath(path);
+  while (folder !== ".") {
+    folders.push(folder);
+    folder = getParentPath(folder);
+  }
+  return [...folders, "."];
+}
+
+/**
+ * Turn wikilinks into markdown links to the workspace files they resolve to,
+ * so they are mapped to pages along with every other link
+ */
+function linkWikilinks(content: string, notePath: string, context: SiteContext): string {
+  return rewriteWikilinks(content, (target, label, isEmbed) => {
+    const resolved = resolveLink(notePath, { target, isWikilink: true, line: 0 }, context.resolver);
+    const text = label ?? target.replace("#", " > ");
+    if (resolved.path === null) {
+      return text;
+    }
+
+    // Heading anchors are written as text; block references have no anchor on the page
+    const anchor = resolved.anchor && !resolved.anchor.startsWith("^") ? `#${slugifyHeading(resolved.anchor)}` : "";
+    const destination = formatLinkTarget(`${getRelativePath(notePath, resolved.path)}${anchor}`);
+    return isEmbed && !isNotePath(resolved.path) ? `![${text}](${destination})` : `[${text}](${destination})`;
+  });
+}
+
+/**
+ * Point local links and images at the published pages and copied files
+ * Links to notes that aren't published are reduced to their text
+ */
+function rewritePageLinks(html: string, notePath: string, context: SiteContext): string {
+  const parsed = new DOMParser().parseFromString(html, "text/html");
+
+  for (const element of Array.from(parsed.querySelectorAll("a[href], img[src]"))) {
+    const attribute = element.tagName === "IMG" ? "src" : "href";
+    const destination = element.getAttribute(attribute) ?? "";
+    if (!destination || isExternalLink(destination)) {
+      continue;
+    }
+
+    const link = { target: destination, isWikilink: false, line: 0 };
+    const { path, anchor } = resolveLink(notePath, link, context.resolver);
+    if (path === null) {
+      continue;
+    }
+
+    if (isNotePath(path)) {
+      if (context.published.has(path)) {
+        element.setAttribute(attribute, toHref(notePath, getPagePath(path), anchor));
+      } else {
+        element.replaceWith(...Array.from(element.childNodes));
+      }
+    } else if (context.resolver.files.has(path)) {
+      context.assets.add(path);
+      element.setAttribute(attribute, toHref(notePath, path, anchor));
+    } else {
+      element.setAttribute(attribute, toHref(notePath, getFolderPagePath(path)));
+    }
+  }
+
+  return parsed.body.innerHTML;
+}
+
+/**
+ * Breadcrumb links from a page up to the site's front page
+ */
+function renderNavigation(pagePath: string, siteName: string): string {
+  const folders = getAncestorFolders(pagePath).reverse();
+  const links = folders.map((folder) => {
+    const href = toHref(pagePath, getFolderPagePath(folder));
+    return `<a href="${escapeHtml(href)}">${escapeHtml(getFolderLabel(folder, siteName))}</a>`;
+  });
+  return `<nav class="site-nav">${links.join(" / ")}</nav>`;
+}
+
+function renderFolderPage(folder: string, folders: string[], notes: IndexedNote[], siteName: string): string {
+  const pagePath = getFolderPagePath(folder);
+  const items = [
+    ...folders.map((child) => ({ href: getFolderPagePath(child), label: `${getFolderLabel(child, siteName)}/` })),
+    ...notes.map((note) => ({ href: getPagePath(note.path), label: note.title })),
+  ].map((item) => `<li><a href="${escapeHtml(toHref(pagePath, item.href))}">${escapeHtml(item.label)}</a></li>`);
+
+  const body = [
+    folder === "." ? "" : renderNavigation(pagePath, siteName),
+    `<h1>${escapeHtml(getFolderLabel(folder, siteName))}</h1>`,
+    `<ul>${items.join("")}</ul>`,
+  ];
+  return body.filter(Boolean).join("\n");
+}
+
+/**
+ * Write a file below the site folder, creating folders on the way
+ */
+async function writeSiteFile(root: FileSystemDirectoryHandle, path: string, data: string | Blob): Promise<void> {
+  const segments = path.split("/");
+  const name = segments.pop() ?? path;
+
+  let directory = root;
+  for (const segment of segments) {
+    directory = await directory.getDirectoryHandle(segment, { create: true });
+  }
+
+  await fsService.writeFileHandle(await directory.getFileHandle(name, { create: true }), data);
+}
+
+/**
+ * Publish notes as a static HTML site into a folder chosen through the folder picker
+ * A note named index.md takes the place of its folder's generated index page.
+ * @returns Summary of the export, or null if the picker was cancelled
+ */
+export async function publishStaticSite(
+  options: SiteExportOptions = {},
+  onProgress?: (progress: OperationProgress) => void,
+  signal?: AbortSignal
+): Promise<SiteExportReport | null> {
+  const siteName = await fsService.restoreWorkspace();
+  if (!siteName) {
+    throw new AppError("no-workspace", "No workspace selected");
+  }
+
+  const target = await fsService.showExportFolderDialog();
+  if (!target) {
+    return null;
+  }
+
+  const scopes = await Promise.all((options.paths ?? ["."]).map((path) => fsService.getRelativeWorkspacePath(path)));
+  const { notes } = await getNoteIndex();
+
+  const published = new Map<string, IndexedNote>();
+  let drafts = 0;
+  for (const note of notes.values()) {
+    if (!isInScope(note.path, scopes)) {
+      continue;
+    }
+    if (options.excludeDrafts && isDraft(note)) {
+      drafts += 1;
+      continue;
+    }
+    published.set(note.path, note);
+  }
+
+  const files: string[] = [];
+  const folders: string[] = [];
+  for await (const entry of fsService.walkDirectory(".", { signal })) {
+    (entry.handle.kind === "directory" ? folders : files).push(entry.relativePath);
+  }
+
+  const context: SiteContext = {
+    resolver: indexPaths(files, folders, getTitleLookup(notes)),
+    published,
+    assets: new Set(),
+  };
+
+  // Every folder on the way to a page gets an index listing its contents
+  const folderNotes = new Map<string, IndexedNote[]>();
+  const folderChildren = new Map<string, Set<string>>([[".", new Set()]]);
+  for (const note of published.values()) {
+    const ancestors = getAncestorFolders(note.path);
+    folderNotes.set(ancestors[0], [...(folderNotes.get(ancestors[0]) ?? []), note]);
+    ancestors.forEach((folder, index) => {
+      const children = folderChildren.get(folder) ?? new Set();
+      if (index > 0) {
+        children.add(ancestors[index - 1]);
+      }
+      folderChildren.set(folder, children);
+    });
+  }
+
+  const pages = [...published.values()].sort((left, right) => left.path.localeCompare(right.path));
+  const pagePaths = new Set(pages.map((note) => getPagePath(note.path)));
+  const folderPages = [...folderChildren.keys()].filter((folder) => !pagePaths.has(getFolderPagePath(folder)));
+  let total = pages.length + folderPages.length;
+  let processed = 0;
+  let bytes = 0;
+  const report = (path: string, size: number) => {
+    processed += 1;
+    bytes += size;
+    onProgress?.({ processed, total, current_path: path, bytes });
+  };
+
+  for (const note of pages) {
+    signal?.throwIfAborted();
+
+    const source = await resolveCitations(await fsService.readFile(note.path));
+    const content = linkWikilinks(source, note.path, context);
+    const body = rewritePageLinks(await renderMarkdown(content), note.path, context);
+    const pagePath = getPagePath(note.path);
+    const html = buildHtmlPage(note.title, `${renderNavigation(pagePath, siteName)}\n${body}`, options.theme);
+
+    await writeSiteFile(target, pagePath, html);
+    report(note.path, html.length);
+  }
+
+  for (const folder of folderPages) {
+    signal?.throwIfAborted();
+
+    const children = [...(folderChildren.get(folder) ?? [])].sort((left, right) => left.localeCompare(right));
+    const folderNoteList = [...(folderNotes.get(folder) ?? [])].sort((left, right) =>
+      left.title.localeCompare(right.title)
+    );
+    const html = buildHtmlPage(
+      getFolderLabel(folder, siteName),
+      renderFolderPage(folder, children, folderNoteList, siteName),
+      options.theme
+    );
+
+    const pagePath = getFolderPagePath(folder);
+    await writeSiteFile(target, pagePath, html);
+    report(pagePath, html.length);
+  }
+
+  // Assets are only known once every page has been rendered
+  total += context.assets.size;
+  for (const path of context.assets) {
+    signal?.throwIfAborted();
+
+    const file = await fsService.getFile(path);
+    await writeSiteFile(target, path, file);
+    report(path, file.size);
+  }
+
+  return {
+    destination: target.name,
+    pages: pages.length,
+    assets: context.assets.size,
+    drafts,
+  };
+}
@@ -0,0 +1,68 @@
+/**
+ * Storage report - disk usage of the workspace per folder and kind of file,
+ * shaped as a tree for a treemap view
+ */
+
+import type { StorageCategory, StorageNode } from "../types";
+import { isNotePath } from "./filenameUtils";
+import * as fsService from "./fs-service";
+import { getParentPath } from "./pathUtils";
+
+const IMAGE_PATTERN = /\.(png|jpe?g|gif|webp|svg|avif|bmp|ico|tiff?|heic)$/i;
+
+function getCategory(path: string): StorageCategory {
+  if (isNotePath(path)) {
+    return "notes";
+  }
+  return IMAGE_PATTERN.test(path) ? "images" : "other";
+}
+
+function createNode(name: string, path: string): StorageNode {
+  return { name, path, size: 0, file_count: 0, by_category: { notes: 0, images: 0, other: 0 }, children: [] };
+}
+
+function sortBySize(node: StorageNode): void {
+  node.children.sort((left, right) => right.size - left.size || left.name.localeCompare(right.name));
+  node.children.forEach(sortBySize);
+}
+
+/**
+ * Add up file sizes for every folder of the workspace
+ * @param includeHidden - Count dot-folders such as .git too
+ */
+export async function getStorageBreakdown(
+  includeHidden: boolean = false,
+  signal?: AbortSignal
+): Promise<StorageNode> {
+  const workspaceName = await fsService.restoreWorkspace();
+  const root = createNode(workspaceName ?? ".", ".");
+  const folders = new Map<string, StorageNode>([[".", root]]);
+
+  for await (const entry of fsService.walkDirectory(".", { includeHidden, signal })) {
+    const parent = folders.get(getParentPath(entry.relativePath)) ?? root;
+
+    if (entry.handle.kind === "directory") {
+      const node = createNode(entry.handle.name, entry.relativePath);
+      folders.set(entry.relativePath, node);
+      parent.children.push(node);
+      continue;
+    }
+
+    const { size } = await entry.handle.getFile();
+    const category = getCategory(entry.relativePath);
+
+    // Count the file in its folder and every folder above it
+    for (let folder: string | null = getParentPath(entry.relativePath); folder !== null; ) {
+      const node = folders.get(folder);
+      if (node) {
+        node.size += size;
+        node.file_count += 1;
+        node.by_category[category] += size;
+      }
+      folder = folder === "." ? null : getParentPath(folder);
+    }
+  }
+
+  sortBySize(root);
+  return root;
+}
@@ -0,0 +1,274 @@
+/**
+ * Markdown tables - parsing GFM pipe tables out of a note, writing them
+ * back with aligned columns, and structural edits on a note's tables
+ */
+
+import type { TableAlignment } from "../types";
+import { AppError } from "./errors";
+import * as fsService from "./fs-service";
+import { getProseLines } from "./markdownUtils";
+
+const DELIMITER_CELL_PATTERN = /^:?-+:?$/;
+
+export interface MarkdownTable {
+  /** 1-based line of the header row */
+  startLine: number;
+
+  /** 1-based line of the last row */
+  endLine: number;
+
+  /** Indentation of the header row, kept when the table is rewritten */
+  indent: string;
+
+  alignments: TableAlignment[];
+
+  /** Header cells, trimmed */
+  header: string[];
+
+  /** Body rows, trimmed; rows may have fewer or more cells than the header */
+  rows: string[][];
+}
+
+/**
+ * Split a table row into trimmed cells
+ * Pipes that are escaped or inside code spans don't separate cells
+ */
+export function splitTableRow(row: string): string[] {
+  const cells: string[] = [];
+  let current = "";
+  let inCode = false;
+
+  for (let index = 0; index < row.length; index += 1) {
+    const char = row[index];
+    if (char === "\\" && row[index + 1] === "|") {
+      current += "\\|";
+      index += 1;
+    } else if (char === "`") {
+      inCode = !inCode;
+      current += char;
+    } else if (char === "|" && !inCode) {
+      cells.push(current);
+      current = "";
+    } else {
+      current += char;
+    }
+  }
+  cells.push(current);
+
+  // Leading and trailing pipes are optional and don't open an empty cell
+  const trimmed = cells.map((cell) => cell.trim());
+  if (row.trim().startsWith("|")) {
+    trimmed.shift();
+  }
+  if (row.trim().endsWith("|") && !row.trim().endsWith("\\|") && trimmed.length > 0) {
+    trimmed.pop();
+  }
+  return trimmed;
+}
+
+function parseAlignment(cell: string): TableAlignment {
+  const left = cell.startsWith(":");
+  const right = cell.endsWith(":");
+  if (left && right) {
+    return "center";
+  }
+  return left ? "left" : right ? "right" : null;
+}
+
+/**
+ * Find the pipe tables in a note, in order
+ * A table is a header row followed by a delimiter row with as many cells;
+ * it runs until a blank line or a line without a pipe
+ */
+export function findTables(content: string): MarkdownTable[] {
+  const lines = getProseLines(content);
+  const tables: MarkdownTable[] = [];
+
+  for (let index = 0; index + 1 < lines.length; index += 1) {
+    const headerLine = lines[index];
+    const delimiterLine = lines[index + 1];
+    if (!headerLine.text.includes("|") || delimiterLine.line !== headerLine.line + 1) {
+      continue;
+    }
+
+    const header = splitTableRow(headerLine.text);
+    const delimiters = splitTableRow(delimiterLine.text);
+    if (
+      header.length === 0 ||
+      delimiters.length !== header.length ||
+      !delimiters.every((cell) => DELIMITER_CELL_PATTERN.test(cell))
+    ) {
+      continue;
+    }
+
+    const rows: string[][] = [];
+    let end = index + 1;
+    while (
+      end + 1 < lines.length &&
+      lines[end + 1].line === lines[end].line + 1 &&
+      lines[end + 1].text.trim() &&
+      lines[end + 1].text.includes("|")
+    ) {
+      end += 1;
+      rows.push(splitTableRow(lines[end].text));
+    }
+
+    tables.push({
+      startLine: headerLine.line,
+      endLine: lines[end].line,
+      indent: /^\s*/.exec(headerLine.text)?.[0] ?? "",
+      alignments: delimiters.map(parseAlignment),
+      header,
+      rows,
+    });
+    index = end;
+  }
+
+  return tables;
+}
+
+function padCell(text: string, width: number, alignment: TableAlignment): string {
+  const padding = width - [...text].length;
+  if (alignment === "right") {
+    return " ".repeat(padding) + text;
+  }
+  if (alignment === "center") {
+    const left = Math.floor(padding / 2);
+    return " ".repeat(left) + text + " ".repeat(padding - left);
+  }
+  return text + " ".repeat(padding);
+}
+
+function formatDelimiter(width: number, alignment: TableAlignment): string {
+  if (alignment === "center") {
+    return `:${"-".repeat(width - 2)}:`;
+  }
+  if (alignment === "left") {
+    return `:${"-".repeat(width - 1)}`;
+  }
+  return alignment === "right" ? `${"-".repeat(width - 1)}:` : "-".repeat(width);
+}
+
+/**
+ * Write a table with every column padded to its widest cell
+ * Rows are evened out to the header's column count
+ * @returns The table's lines
+ */
+export function formatTable(table: MarkdownTable): string[] {
+  const columns = table.header.length;
+  const rows = [table.header, ...table.rows].map((row) =>
+    Array.from({ length: columns }, (_, column) => row[column] ?? "")
+  );
+
+  // Delimiters need at least three characters, with room for the colons
+  const widths = Array.from({ length: columns }, (_, column) =>
+    Math.max(3, ...rows.map((row) => [...row[column]].length))
+  );
+
+  const formatRow = (cells: string[]) => `${table.indent}| ${cells.join(" | ")} |`;
+  const formatCells = (row: string[]) =>
+    formatRow(row.map((cell, column) => padCell(cell, widths[column], table.alignments[column])));
+  const [header, ...body] = rows;
+
+  return [
+    formatCells(header),
+    formatRow(widths.map((width, column) => formatDelimiter(width, table.alignments[column]))),
+    ...body.map(formatCells),
+  ];
+}
+
+/**
+ * Rewrite one table of a note, addressed by its position among the note's tables
+ * The file is replaced only if it didn't change while the table was edited
+ * @param tableIndex - 0-based index of the table in the note
+ */
+async function editTable(
+  path: string,
+  tableIndex: number,
+  edit: (table: MarkdownTable) => MarkdownTable
+): Promise<void> {
+  const { content, hash } = await fsService.readFileWithHash(path);
+  const table = findTables(content)[tableIndex];
+  if (!table) {
+    throw new AppError("invalid-argument", `No table ${tableIndex} in note`, { path });
+  }
+
+  // Offsets of every line start, so the table's lines can be replaced in place
+  const lineStarts = [0];
+  for (const match of content.matchAll(/\r?\n/g)) {
+    lineStarts.push((match.index ?? 0) + match[0].length);
+  }
+  const start = lineStarts[table.startLine - 1];
+  const nextStart = lineStarts[table.endLine];
+  const end = nextStart === undefined ? content.length : nextStart - (content[nextStart - 2] === "\r" ? 2 : 1);
+  const eol = content.includes("\r\n") ? "\r\n" : "\n";
+
+  await fsService.applyEdits(path, [{ start, end, replacement: formatTable(edit(table)).join(eol) }], hash);
+}
+
+/**
+ * Align the columns of a table in a note
+ */
+export async function formatTableInNote(path: string, tableIndex: number): Promise<void> {
+  await editTable(path, tableIndex, (table) => table);
+}
+
+/**
+ * Insert an empty column into a table in a note
+ * @param column - 0-based position of the new column (the column count appends it)
+ */
+export async function insertTableColumn(
+  path: string,
+  tableIndex: number,
+  column: number,
+  header: string = "",
+  alignment: TableAlignment = null
+): Promise<void> {
+  await editTable(path, tableIndex, (table) => {
+    if (!Number.isInteger(column) || column < 0 || column > table.header.length) {
+      throw new AppError("invalid-argument", `Column ${column} is out of range`, { path });
+    }
+
+    const insert = <T>(cells: T[], value: T) => [...cells.slice(0, column), value, ...cells.slice(column)];
+    return {
+      ...table,
+      alignments: insert(table.alignments, alignment),
+      header: insert(table.header, header),
+      // Short rows are padded first so the new cell lands in the right column
+      rows: table.rows.map((row) =>
+        insert(Array.from({ length: Math.max(row.length, column) }, (_, index) => row[index] ?? ""), "")
+      ),
+    };
+  });
+}
+
+/**
+ * Sort the rows of a table in a note by one column
+ * Numbers compare by value and text ignores case; empty cells sort last
+ */
+export async function sortTableByColumn(
+  path: string,
+  tableIndex: number,
+  column: number,
+  descending: boolean = false
+): Promise<void> {
+  await editTable(path, tableIndex, (table) => {
+    if (!Number.isInteger(column) || column < 0 || column >= table.header.length) {
+      throw new AppError("invalid-argument", `Column ${column} is out of range`, { path });
+    }
+
+    const rows = [...table.rows].sort((left, right) => {
+      const a = left[column] ?? "";
+      const b = right[column] ?? "";
+      if (!a || !b) {
+        return (a ? 0 : 1) - (b ? 0 : 1);
+      }
+      const numbers = [Number(a), Number(b)];
+      const order = numbers.every(Number.isFinite)
+        ? numbers[0] - numbers[1]
+        : a.localeCompare(b, undefined, { numeric: true, sensitivity: "base" });
+      return descending ? -order : order;
+    });
+    return { ...table, rows };
+  });
+}
@@ -0,0 +1,50 @@
+/**
+ * Task extraction - checkbox tasks from every note, served from the note
+ * index so a task dashboard never re-reads the workspace
+ */
+
+import type { Task, TaskFilters } from "../types";
+import * as fsService from "./fs-service";
+import { getNoteIndex } from "./note-index";
+
+function matchesFilters(task: Task, filters: TaskFilters): boolean {
+  if (filters.completed !== undefined && task.completed !== filters.completed) {
+    return false;
+  }
+  if (filters.hasDue !== undefined && (task.due !== null) !== filters.hasDue) {
+    return false;
+  }
+  // YYYY-MM-DD strings compare correctly as text
+  if (filters.dueBefore !== undefined && (task.due === null || task.due > filters.dueBefore)) {
+    return false;
+  }
+  if (filters.dueAfter !== undefined && (task.due === null || task.due < filters.dueAfter)) {
+    return false;
+  }
+  return true;
+}
+
+/**
+ * List the tasks in a note or folder, by path then line
+ * @param scope - Workspace folder or note path ("." for the whole workspace)
+ */
+export async function getTasks(scope: string, filters: TaskFilters = {}): Promise<Task[]> {
+  const { notes } = await getNoteIndex();
+  const scopePath = await fsService.getRelativeWorkspacePath(scope);
+
+  const tasks: Task[] = [];
+  for (const note of notes.values()) {
+    if (scopePath !== "." && note.path !== scopePath && !note.path.startsWith(`${scopePath}/`)) {
+      continue;
+    }
+
+    for (const { text, completed, due, line } of note.tasks) {
+      const task: Task = { path: note.path, line, text, completed, due };
+      if (matchesFilters(task, filters)) {
+        tasks.push(task);
+      }
+    }
+  }
+
+  return tasks.sort((left, right) => left.path.localeCompare(right.path) || left.line - right.line);
+}
@@ -0,0 +1,122 @@
+/**
+ * Thumbnails - small previews of workspace images for gallery views
+ * Generated thumbnails are persisted in Cache Storage, keyed by workspace id,
+ * path, size and file version, so they survive reloads and are regenerated
+ * only when the image changes; older versions are dropped then. SVGs are
+ * vector already and returned as-is.
+ */
+
+import * as fsService from "./fs-service";
+
+const THUMBNAIL_CACHE_NAME = "mdx-thumbnails";
+const THUMBNAIL_TYPE = "image/webp";
+const THUMBNAIL_QUALITY = 0.8;
+const MIN_THUMBNAIL_SIZE = 16;
+const MAX_THUMBNAIL_SIZE = 1024;
+
+/** Object URLs handed out this session, by cache key */
+const thumbnailUrls = new Map<string, string>();
+
+function getCacheKey(workspaceId: string, path: string, maxSize: number, file: File): string {
+  const params = new URLSearchParams({
+    size: String(maxSize),
+    version: `${file.size}-${file.lastModified}`,
+  });
+  return `/__thumbnails/${encodeURIComponent(workspaceId)}/${encodeURIComponent(path)}?${params}`;
+}
+
+/**
+ * Delete stored thumbnails of older versions of an image at one size
+ */
+async function deleteStaleThumbnails(cache: Cache, key: string): Promise<void> {
+  const current = new URL(key, location.origin).searchParams;
+  for (const request of await cache.keys(key, { ignoreSearch: true })) {
+    const params = new URL(request.url).searchParams;
+    if (params.get("size") === current.get("size") && params.get("version") !== current.get("version")) {
+      await cache.delete(request);
+    }
+  }
+}
+
+async function openThumbnailCache(): Promise<Cache | null> {
+  if (typeof caches === "undefined") {
+    return null;
+  }
+
+  try {
+    return await caches.open(THUMBNAIL_CACHE_NAME);
+  } catch {
+    // Cache Storage is unavailable in some private browsing modes
+    return null;
+  }
+}
+
+async function renderThumbnail(file: File, maxSize: number): Promise<Blob> {
+  const bitmap = await createImageBitmap(file);
+
+  try {
+    const scale = Math.min(1, maxSize / Math.max(bitmap.width, bitmap.height));
+    const width = Math.max(1, Math.round(bitmap.width * scale));
+    const height = Math.max(1, Math.round(bitmap.height * scale));
+
+    const canvas = new OffscreenCanvas(width, height);
+    const context = canvas.getContext("2d");
+    if (!context) {
+      throw new Error("Canvas 2D context is not available");
+    }
+
+    context.drawImage(bitmap, 0, 0, width, height);
+    return await canvas.convertToBlob({ type: THUMBNAIL_TYPE, quality: THUMBNAIL_QUALITY });
+  } finally {
+    bitmap.close();
+  }
+}
+
+/**
+ * Get a thumbnail for a workspace image
+ * @param maxSize - Longest side of the thumbnail in pixels
+ * @returns Object URL of the thumbnail
+ */
+export async function getThumbnail(path: string, maxSize: number): Promise<string> {
+  const size = Math.round(Math.min(Math.max(maxSize, MIN_THUMBNAIL_SIZE), MAX_THUMBNAIL_SIZE));
+  const relativePath = await fsService.getRelativeWorkspacePath(path);
+  const file = await fsService.getFile(relativePath);
+
+  const key = getCacheKey(await fsService.getWorkspaceId(), relativePath, size, file);
+  const existing = thumbnailUrls.get(key);
+  if (existing) {
+    return existing;
+  }
+
+  let thumbnail: Blob;
+  if (file.type === "image/svg+xml" || /\.svg$/i.test(relativePath)) {
+    thumbnail = file;
+  } else {
+    const cache = await openThumbnailCache();
+    const cached = await cache?.match(key);
+
+    if (cached) {
+      thumbnail = await cached.blob();
+    } else {
+      thumbnail = await renderThumbnail(file, size);
+      if (cache) {
+        await cache.put(key, new Response(thumbnail, { headers: { "Content-Type": thumbnail.type } }));
+        await deleteStaleThumbnails(cache, key);
+      }
+    }
+  }
+
+  // Drop URLs for older versions of the same image and size; the prefix ends
+  // at "size=64&" so other sizes such as 640 keep theirs
+  const keyPrefix = key.slice(0, key.indexOf("version="));
+  for (const [cachedKey, url] of thumbnailUrls) {
+    if (cachedKey.startsWith(keyPrefix)) {
+      URL.revokeObjectURL(url);
+      thumbnailUrls.delete(cachedKey);
+    }
+  }
+
+  const url = URL.createObjectURL(thumbnail);
+  thumbnailUrls.set(key, url);
+  return url;
+}
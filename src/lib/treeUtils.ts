@@ -2,7 +2,7 @@
  * Tree utilities for virtualized rendering
  */
 
-import type { FileNode } from "../types";
+import type { FileNode, ViewPreferences } from "../types";
 
 /**
  * Flattened tree node for virtualized rendering
@@ -31,19 +31,23 @@ export interface FlatNode {
  * 
  * @param nodes - Root nodes to flatten
  * @param expandedFolders - Set of expanded folder paths
+ * @param compare - Order for siblings (kept as given when omitted)
  * @param depth - Current nesting depth (internal use)
  * @returns Flat array of nodes in display order
  */
 export function flattenTree(
   nodes: FileNode[],
   expandedFolders: Set<string>,
+  compare?: (a: FileNode, b: FileNode) => number,
   depth = 0
 ): FlatNode[] {
   const flat: FlatNode[] = [];
   const stack: Array<{ node: FileNode; depth: number }> = [];
+  const order = (siblings: FileNode[]) => (compare ? [...siblings].sort(compare) : siblings);
 
-  for (let index = nodes.length - 1; index >= 0; index -= 1) {
-    stack.push({ node: nodes[index], depth });
+  const roots = order(nodes);
+  for (let index = roots.length - 1; index >= 0; index -= 1) {
+    stack.push({ node: roots[index], depth });
   }
 
   while (stack.length > 0) {
@@ -53,7 +57,9 @@ export function flattenTree(
     }
 
     const isExpanded = expandedFolders.has(current.node.path);
-    const hasChildren = !current.node.is_file && (current.node.children?.length ?? 0) > 0;
+    // Unloaded folders rely on the scan's hint so the chevron shows before they are opened
+    const { children, has_children: hintsChildren } = current.node;
+    const hasChildren = !current.node.is_file && (children ? children.length > 0 : hintsChildren);
 
     flat.push({
       node: current.node,
@@ -64,9 +70,10 @@ export function flattenTree(
     });
 
     if (isExpanded && current.node.children) {
-      for (let index = current.node.children.length - 1; index >= 0; index -= 1) {
+      const children = order(current.node.children);
+      for (let index = children.length - 1; index >= 0; index -= 1) {
         stack.push({
-          node: current.node.children[index],
+          node: children[index],
           depth: current.depth + 1,
         });
       }
@@ -75,3 +82,26 @@ export function flattenTree(
 
   return flat;
 }
+
+/**
+ * Order sibling nodes by the tree's view preferences
+ * Dates sort newest first and sizes largest first; ties fall back to the name
+ */
+export function compareNodes(preferences: ViewPreferences): (a: FileNode, b: FileNode) => number {
+  const byName = (a: FileNode, b: FileNode) => a.name.toLowerCase().localeCompare(b.name.toLowerCase());
+
+  return (a, b) => {
+    if (preferences.folders_first && a.is_file !== b.is_file) {
+      return a.is_file ? 1 : -1;
+    }
+
+    switch (preferences.sort_order) {
+      case "modified":
+        return (b.modified ?? 0) - (a.modified ?? 0) || byName(a, b);
+      case "size":
+        return (b.size ?? 0) - (a.size ?? 0) || byName(a, b);
+      default:
+        return byName(a, b);
+    }
+  };
+}
@@ -0,0 +1,99 @@
+/**
+ * App updates - the service worker downloads new builds in the background,
+ * and a downloaded build waits until the user chooses to restart into it
+ * rather than replacing the running app mid-edit. Status changes are
+ * broadcast as "update-status" events so every window can show the banner.
+ */
+
+import { registerSW } from "virtual:pwa-register";
+import type { UpdateStatus } from "../types";
+import { emit } from "./app-events";
+import { AppError } from "./errors";
+
+const UPDATE_CHECK_INTERVAL_MS = 60 * 60 * 1000;
+
+let status: UpdateStatus = "idle";
+let registration: ServiceWorkerRegistration | undefined;
+
+function setStatus(next: UpdateStatus): void {
+  if (status !== next) {
+    status = next;
+    emit("update-status", next);
+  }
+}
+
+const updateServiceWorker = registerSW({
+  immediate: true,
+  onNeedRefresh: () => setStatus("ready"),
+  onRegisteredSW: (_url, registered) => {
+    registration = registered;
+    if (!registered) {
+      return;
+    }
+
+    registered.addEventListener("updatefound", () => {
+      // The first install, when no worker controls the page yet, isn't an update
+      const worker = registered.installing;
+      if (!worker || !navigator.serviceWorker.controller) {
+        return;
+      }
+
+      setStatus("downloading");
+      worker.addEventListener("statechange", () => {
+        if (worker.state === "redundant") {
+          setStatus("idle");
+        }
+      });
+    });
+    setInterval(() => {
+      if (navigator.onLine) {
+        registered.update().catch((error) => console.warn("Background update check failed:", error));
+      }
+    }, UPDATE_CHECK_INTERVAL_MS);
+  },
+  onRegisterError: (error) => console.error("Failed to register the service worker:", error),
+});
+
+export function getUpdateStatus(): UpdateStatus {
+  return status;
+}
+
+/**
+ * Ask the server for a newer build; one that exists starts downloading
+ * @returns The status after the check ("idle" when already up to date)
+ */
+export async function checkForUpdates(): Promise<UpdateStatus> {
+  if (!registration) {
+    throw new AppError("unsupported", "Updates aren't available without a service worker");
+  }
+  if (status === "ready") {
+    return status;
+  }
+
+  setStatus("checking");
+  try {
+    await registration.update();
+  } catch (error) {
+    setStatus("idle");
+    throw new AppError("io", "Couldn't reach the server to check for updates", { cause: error });
+  }
+
+  if (registration.waiting) {
+    setStatus("ready");
+  } else if (registration.installing) {
+    setStatus("downloading");
+  } else {
+    setStatus("idle");
+  }
+  return status;
+}
+
+/**
+ * Switch to the downloaded build, reloading the app
+ */
+export async function installUpdate(): Promise<void> {
+  if (status !== "ready") {
+    throw new AppError("invalid-argument", "No update has been downloaded yet");
+  }
+  await updateServiceWorker(true);
+}
@@ -0,0 +1,146 @@
+/**
+ * URL metadata - reads a web page's title, description and preview image so
+ * a pasted URL can become a [Page Title](url) link
+ * Requests are subject to CORS: sites that don't allow cross-origin reads
+ * fail with an "unsupported" error and the URL is pasted as is.
+ */
+
+import type { UrlMetadata } from "../types";
+import { AppError } from "./errors";
+
+const FETCH_TIMEOUT_MS = 5000;
+
+/** The head of a page is near its start; nothing past this is read */
+const MAX_HTML_BYTES = 512 * 1024;
+
+const HTML_CONTENT_TYPES = new Set(["text/html", "application/xhtml+xml"]);
+
+/**
+ * Read a response body up to a byte limit, stopping early once the head is complete
+ */
+async function readHead(response: Response): Promise<Uint8Array> {
+  const reader = response.body?.getReader();
+  if (!reader) {
+    return new Uint8Array(await response.arrayBuffer()).slice(0, MAX_HTML_BYTES);
+  }
+
+  const chunks: Uint8Array[] = [];
+  const decoder = new TextDecoder();
+  let size = 0;
+  try {
+    while (size < MAX_HTML_BYTES) {
+      const { done, value } = await reader.read();
+      if (done) {
+        break;
+      }
+      chunks.push(value);
+      size += value.length;
+      if (/<\/head>|<body[\s>]/i.test(decoder.decode(value, { stream: true }))) {
+        break;
+      }
+    }
+  } finally {
+    await reader.cancel().catch(() => undefined);
+  }
+
+  const bytes = new Uint8Array(Math.min(size, MAX_HTML_BYTES));
+  let offset = 0;
+  for (const chunk of chunks) {
+    const part = chunk.subarray(0, bytes.length - offset);
+    bytes.set(part, offset);
+    offset += part.length;
+  }
+  return bytes;
+}
+
+function decodeHtml(bytes: Uint8Array, contentType: string): string {
+  const charset = /charset=["']?([\w-]+)/i.exec(contentType)?.[1];
+  try {
+    return new TextDecoder(charset ?? "utf-8").decode(bytes);
+  } catch {
+    // Unknown charset label
+    return new TextDecoder().decode(bytes);
+  }
+}
+
+function getMetaContent(document: Document, names: string[]): string | null {
+  for (const name of names) {
+    const element = document.querySelector(`meta[property="${name}"], meta[name="${name}"]`);
+    const content = element?.getAttribute("content")?.replace(/\s+/g, " ").trim();
+    if (content) {
+      return content;
+    }
+  }
+  return null;
+}
+
+async function readMetadata(url: URL, signal: AbortSignal): Promise<UrlMetadata> {
+  let response: Response;
+  try {
+    response = await fetch(url, { signal, credentials: "omit" });
+  } catch (error) {
+    if (signal.aborted) {
+      throw error;
+    }
+    // fetch reports CORS refusals and network failures alike
+    throw new AppError("unsupported", "The page could not be read from the browser", { cause: error });
+  }
+
+  if (!response.ok) {
+    throw new AppError("io", `Request failed with HTTP ${response.status}`);
+  }
+
+  const finalUrl = response.url || url.href;
+  const contentType = response.headers.get("content-type") ?? "";
+  if (!HTML_CONTENT_TYPES.has(contentType.split(";")[0].trim().toLowerCase())) {
+    return { url: finalUrl, title: null, description: null, image: null };
+  }
+
+  const html = decodeHtml(await readHead(response), contentType);
+  const document = new DOMParser().parseFromString(html, "text/html");
+  const title = getMetaContent(document, ["og:title", "twitter:title"]) ?? document.title.replace(/\s+/g, " ").trim();
+  const image = getMetaContent(document, ["og:image", "og:image:url", "twitter:image"]);
+
+  let imageUrl: string | null = null;
+  try {
+    imageUrl = image ? new URL(image, finalUrl).href : null;
+  } catch {
+    // Malformed image URL
+  }
+
+  return {
+    url: finalUrl,
+    title: title || null,
+    description: getMetaContent(document, ["og:description", "description", "twitter:description"]),
+    image: imageUrl,
+  };
+}
+
+/**
+ * Fetch a page and read its metadata
+ * Gives up after a few seconds; only the start of the page is downloaded.
+ */
+export async function fetchUrlMetadata(url: string, signal?: AbortSignal): Promise<UrlMetadata> {
+  const parsed = new URL(url);
+  if (parsed.protocol !== "http:" && parsed.protocol !== "https:") {
+    throw new AppError("invalid-argument", "Only http(s) pages can be fetched");
+  }
+
+  const controller = new AbortController();
+  const timeout = setTimeout(() => controller.abort(), FETCH_TIMEOUT_MS);
+  const onAbort = () => controller.abort();
+  signal?.addEventListener("abort", onAbort);
+
+  try {
+    return await readMetadata(parsed, controller.signal);
+  } catch (error) {
+    signal?.throwIfAborted();
+    if (controller.signal.aborted) {
+      throw new AppError("io", "The page took too long to respond", { cause: error });
+    }
+    throw error;
+  } finally {
+    clearTimeout(timeout);
+    signal?.removeEventListener("abort", onAbort);
+  }
+}
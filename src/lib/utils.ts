@@ -4,3 +4,33 @@ import { twMerge } from "tailwind-merge"
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs))
 }
+
+export function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`
+}
+
+const RELATIVE_TIME_UNITS: [Intl.RelativeTimeFormatUnit, number][] = [
+  ["year", 365 * 24 * 60 * 60 * 1000],
+  ["month", 30 * 24 * 60 * 60 * 1000],
+  ["week", 7 * 24 * 60 * 60 * 1000],
+  ["day", 24 * 60 * 60 * 1000],
+  ["hour", 60 * 60 * 1000],
+  ["minute", 60 * 1000],
+]
+
+/**
+ * Describe a time relative to now, e.g. "3 hours ago" or "yesterday"
+ * @param timestamp - Time in ms since the epoch
+ */
+export function formatRelativeTime(timestamp: number, now: number = Date.now()): string {
+  const elapsed = timestamp - now
+  const format = new Intl.RelativeTimeFormat(undefined, { numeric: "auto" })
+  for (const [unit, size] of RELATIVE_TIME_UNITS) {
+    if (Math.abs(elapsed) >= size) {
+      return format.format(Math.round(elapsed / size), unit)
+    }
+  }
+  return format.format(0, "second")
+}
@@ -0,0 +1,50 @@
+/**
+ * View preferences - how the file tree lists entries, remembered across
+ * restarts in localStorage and kept in step between windows
+ */
+
+import type { ViewPreferences } from "../types";
+import { emit } from "./app-events";
+
+const PREFERENCES_KEY = "mdx-view-preferences";
+const SORT_ORDERS = new Set<string>(["name", "modified", "size"]);
+
+export const DEFAULT_VIEW_PREFERENCES: ViewPreferences = {
+  show_hidden_files: false,
+  sort_order: "name",
+  folders_first: true,
+};
+
+function parseViewPreferences(value: unknown): ViewPreferences {
+  const stored = (value && typeof value === "object" ? value : {}) as Partial<Record<keyof ViewPreferences, unknown>>;
+  const defaults = DEFAULT_VIEW_PREFERENCES;
+
+  return {
+    show_hidden_files:
+      typeof stored.show_hidden_files === "boolean" ? stored.show_hidden_files : defaults.show_hidden_files,
+    sort_order:
+      typeof stored.sort_order === "string" && SORT_ORDERS.has(stored.sort_order)
+        ? (stored.sort_order as ViewPreferences["sort_order"])
+        : defaults.sort_order,
+    folders_first: typeof stored.folders_first === "boolean" ? stored.folders_first : defaults.folders_first,
+  };
+}
+
+export function getViewPreferences(): ViewPreferences {
+  try {
+    return parseViewPreferences(JSON.parse(localStorage.getItem(PREFERENCES_KEY) ?? "null"));
+  } catch {
+    return DEFAULT_VIEW_PREFERENCES;
+  }
+}
+
+/**
+ * Change some preferences, keeping the rest
+ * @returns The preferences now in effect
+ */
+export function setViewPreferences(changes: Partial<ViewPreferences>): ViewPreferences {
+  const preferences = parseViewPreferences({ ...getViewPreferences(), ...changes });
+  localStorage.setItem(PREFERENCES_KEY, JSON.stringify(preferences));
+  emit("view-preferences-changed", preferences);
+  return preferences;
+}
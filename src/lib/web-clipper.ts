@@ -0,0 +1,129 @@
+/**
+ * Web clipper - saves pages sent by a browser extension or bookmarklet
+ * The sender opens the app at a clip link,
+ *   /?clip=<token>&title=...&url=...&markdown=...
+ * which works like the desktop app's local endpoint: clipping is off until
+ * enabled, and a link is only accepted with the workspace's secret token.
+ */
+
+import type { WebClipping } from "../types";
+import { AppError } from "./errors";
+import { getReservedNameError } from "./filenameUtils";
+import * as fsService from "./fs-service";
+
+/** Query parameter that carries the token and marks a launch as a clipping */
+export const CLIP_PARAM = "clip";
+
+const CLIP_FIELDS = ["title", "url", "markdown"] as const;
+const MAX_TITLE_LENGTH = 100;
+const INVALID_FILENAME_CHARS = /[<>:"/\\|?*\u0000-\u001f]+/g;
+
+/**
+ * Read a clipping out of the URL the app was opened with
+ * @returns Null if the URL isn't a clip link
+ */
+export function parseClipLink(targetURL: string): { token: string; clipping: WebClipping } | null {
+  let params: URLSearchParams;
+  try {
+    params = new URL(targetURL).searchParams;
+  } catch {
+    return null;
+  }
+
+  const token = params.get(CLIP_PARAM);
+  if (token === null) {
+    return null;
+  }
+
+  return {
+    token,
+    clipping: {
+      title: params.get("title") ?? "",
+      url: params.get("url") ?? "",
+      markdown: params.get("markdown") ?? "",
+    },
+  };
+}
+
+/**
+ * Remove the clip parameters from a URL, so a reload doesn't save the page again
+ */
+export function stripClipParams(url: URL): URL {
+  const stripped = new URL(url);
+  for (const name of [CLIP_PARAM, ...CLIP_FIELDS]) {
+    stripped.searchParams.delete(name);
+  }
+  return stripped;
+}
+
+function getClippingFilename(title: string): string {
+  const name = title
+    .replace(INVALID_FILENAME_CHARS, " ")
+    .replace(/\s+/g, " ")
+    .slice(0, MAX_TITLE_LENGTH)
+    .replace(/^[\s.]+|[\s.]+$/g, "");
+  if (!name) {
+    return "Clipping.md";
+  }
+  return getReservedNameError(`${name}.md`) ? `${name} clipping.md` : `${name}.md`;
+}
+
+function formatClipping(clipping: WebClipping, title: string): string {
+  const lines = ["---"];
+  if (/^https?:\/\//i.test(clipping.url) && !/\s/.test(clipping.url)) {
+    lines.push(`source: ${clipping.url}`);
+  }
+  lines.push(`clipped: ${new Date().toISOString().slice(0, 10)}`, "---", "", `# ${title}`, "");
+  if (clipping.markdown.trim()) {
+    lines.push(clipping.markdown.trim(), "");
+  }
+  return lines.join("\n");
+}
+
+/**
+ * Compare tokens in time that doesn't depend on where they differ
+ */
+function tokensMatch(expected: string, actual: string): boolean {
+  let difference = expected.length ^ actual.length;
+  for (let index = 0; index < expected.length; index += 1) {
+    difference |= expected.charCodeAt(index) ^ actual.charCodeAt(index % Math.max(actual.length, 1));
+  }
+  return difference === 0;
+}
+
+/**
+ * Save a clipping as a new note in the clippings folder
+ * @param token - Token the clip link carried
+ * @returns Workspace-relative path of the new note
+ */
+export async function saveClipping(clipping: WebClipping, token: string): Promise<string> {
+  const { clipper } = await fsService.getWorkspaceSettings();
+  if (!clipper.enabled) {
+    throw new AppError("permission-denied", "Web clipping is turned off for this workspace");
+  }
+  if (!clipper.token || !tokensMatch(clipper.token, token)) {
+    throw new AppError("permission-denied", "The clip link's token doesn't match this workspace");
+  }
+
+  const title = clipping.title.replace(/\s+/g, " ").trim() || clipping.url || "Clipping";
+  const folder = await fsService.getRelativeWorkspacePath(clipper.folder);
+  const path = await fsService.getAvailablePath(
+    folder === "." ? getClippingFilename(title) : `${folder}/${getClippingFilename(title)}`
+  );
+
+  await fsService.writeFile(path, formatClipping(clipping, title));
+  return path;
+}
+
+/**
+ * Turn clipping on with a new token, replacing any earlier one
+ * @returns The token clip links have to carry
+ */
+export async function createClipperToken(): Promise<string> {
+  const bytes = crypto.getRandomValues(new Uint8Array(24));
+  const token = Array.from(bytes, (byte) => byte.toString(16).padStart(2, "0")).join("");
+
+  const settings = await fsService.getWorkspaceSettings();
+  await fsService.saveWorkspaceSettings({ ...settings, clipper: { ...settings.clipper, enabled: true, token } });
+  return token;
+}
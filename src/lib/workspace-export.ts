@@ -0,0 +1,89 @@
+/**
+ * Workspace export - archives the workspace into a ZIP file
+ */
+
+import type { OperationProgress, ZipExportOptions } from "../types";
+import * as fsService from "./fs-service";
+import type { WorkspaceEntry } from "./fs-service";
+import { matchesGlob } from "./globUtils";
+import { createZipWriter } from "./zip";
+
+function isExcluded(relativePath: string, options: ZipExportOptions, assetsRoot: string): boolean {
+  if (options.excludeAssets && relativePath === assetsRoot) {
+    return true;
+  }
+
+  return (options.ignorePatterns ?? []).some((pattern) => matchesGlob(relativePath, pattern));
+}
+
+/**
+ * Stream the workspace into a ZIP file chosen through the save dialog
+ * A cancelled or failed export discards the partially written archive.
+ * @returns Name of the written archive, or null if the dialog was cancelled
+ */
+export async function exportWorkspaceZip(
+  options: ZipExportOptions = {},
+  onProgress?: (progress: OperationProgress) => void,
+  signal?: AbortSignal
+): Promise<string | null> {
+  const workspaceName = await fsService.restoreWorkspace();
+  if (!workspaceName) {
+    throw new Error("No workspace selected");
+  }
+
+  const target = await fsService.showSaveDialog({
+    suggestedName: `${workspaceName}.zip`,
+    filters: [{ name: "ZIP archive", extensions: ["zip"] }],
+  });
+  if (!target) {
+    return null;
+  }
+
+  const { assets } = await fsService.getWorkspaceSettings();
+  const assetsRoot = await fsService.getRelativeWorkspacePath(assets.root);
+
+  // Collect entries up front so progress can report a total
+  const entries: WorkspaceEntry[] = [];
+  for await (const entry of fsService.walkDirectory(".", {
+    includeHidden: options.includeHidden,
+    exclude: (relativePath) => isExcluded(relativePath, options, assetsRoot),
+    signal,
+  })) {
+    entries.push(entry);
+  }
+
+  const writable = await target.handle.createWritable();
+  const zip = createZipWriter({ write: (chunk) => writable.write(chunk) });
+  let bytes = 0;
+
+  try {
+    for (let index = 0; index < entries.length; index += 1) {
+      signal?.throwIfAborted();
+
+      const { relativePath, handle } = entries[index];
+      if (handle.kind === "directory") {
+        await zip.addDirectory(relativePath, new Date());
+      } else {
+        const file = await handle.getFile();
+        await zip.addFile(relativePath, new Uint8Array(await file.arrayBuffer()), new Date(file.lastModified));
+        bytes += file.size;
+      }
+
+      onProgress?.({
+        processed: index + 1,
+        total: entries.length,
+        current_path: relativePath,
+        bytes,
+      });
+    }
+
+    await zip.close();
+    await writable.close();
+  } catch (error) {
+    // Discard the partial archive; the original error is what matters
+    await writable.abort().catch(() => undefined);
+    throw error;
+  }
+
+  return target.name;
+}
@@ -0,0 +1,127 @@
+/**
+ * Workspace lock - detects another app instance (another browser, or another
+ * machine through a synced folder) editing the same workspace
+ * The holder refreshes a heartbeat in .mdx/lock; a lock whose heartbeat is
+ * older than STALE_AFTER_MS is treated as abandoned. Windows of the same
+ * browser profile share an instance id and never lock each other out.
+ */
+
+import type { WorkspaceLockInfo, WorkspaceLockStatus } from "../types";
+import * as fsService from "./fs-service";
+
+const LOCK_PATH = ".mdx/lock";
+const INSTANCE_ID_KEY = "mdx-instance-id";
+const HEARTBEAT_INTERVAL_MS = 30_000;
+const STALE_AFTER_MS = 2 * 60_000;
+
+let heartbeatTimer: ReturnType<typeof setInterval> | null = null;
+
+function getInstanceId(): string {
+  let id = localStorage.getItem(INSTANCE_ID_KEY);
+  if (!id) {
+    id = crypto.randomUUID();
+    localStorage.setItem(INSTANCE_ID_KEY, id);
+  }
+  return id;
+}
+
+async function readLock(): Promise<WorkspaceLockInfo | null> {
+  try {
+    const parsed: unknown = JSON.parse(await fsService.readFile(LOCK_PATH));
+    const lock = parsed as Partial<WorkspaceLockInfo> | null;
+    return lock && typeof lock.instance_id === "string" && typeof lock.heartbeat_at === "string"
+      ? (lock as WorkspaceLockInfo)
+      : null;
+  } catch (error) {
+    if (error instanceof SyntaxError || (error instanceof DOMException && error.name === "NotFoundError")) {
+      return null;
+    }
+    throw error;
+  }
+}
+
+async function writeLock(acquiredAt: string): Promise<void> {
+  const lock: WorkspaceLockInfo = {
+    instance_id: getInstanceId(),
+    user_agent: navigator.userAgent,
+    acquired_at: acquiredAt,
+    heartbeat_at: new Date().toISOString(),
+  };
+  await fsService.writeFile(LOCK_PATH, `${JSON.stringify(lock, null, 2)}\n`);
+}
+
+function isHeldByOther(lock: WorkspaceLockInfo | null): lock is WorkspaceLockInfo {
+  return (
+    lock !== null &&
+    lock.instance_id !== getInstanceId() &&
+    Date.now() - Date.parse(lock.heartbeat_at) < STALE_AFTER_MS
+  );
+}
+
+function stopHeartbeat(): void {
+  if (heartbeatTimer !== null) {
+    clearInterval(heartbeatTimer);
+    heartbeatTimer = null;
+  }
+}
+
+function startHeartbeat(acquiredAt: string): void {
+  stopHeartbeat();
+  heartbeatTimer = setInterval(async () => {
+    try {
+      const lock = await readLock();
+      if (isHeldByOther(lock)) {
+        // Another instance forced the lock away from us
+        console.warn("[WorkspaceLock] Lock taken over by another instance:", lock);
+        stopHeartbeat();
+        return;
+      }
+      await writeLock(acquiredAt);
+    } catch (error) {
+      console.warn("[WorkspaceLock] Heartbeat failed:", error);
+    }
+  }, HEARTBEAT_INTERVAL_MS);
+}
+
+/**
+ * Take the lock of the current workspace unless a live instance holds it
+ */
+export async function acquireWorkspaceLock(): Promise<WorkspaceLockStatus> {
+  stopHeartbeat();
+
+  const lock = await readLock();
+  if (isHeldByOther(lock)) {
+    return { acquired: false, holder: lock };
+  }
+
+  const acquiredAt = lock?.instance_id === getInstanceId() ? lock.acquired_at : new Date().toISOString();
+  await writeLock(acquiredAt);
+  startHeartbeat(acquiredAt);
+  return { acquired: true, holder: null };
+}
+
+/**
+ * Take the lock even though another instance appears to hold it
+ * Meant for stale locks left behind by a crash or an offline machine
+ */
+export async function forceUnlock(): Promise<void> {
+  const acquiredAt = new Date().toISOString();
+  await writeLock(acquiredAt);
+  startHeartbeat(acquiredAt);
+}
+
+/**
+ * Give up the lock when this window closes (best effort)
+ * Other windows of this instance re-create it on their next heartbeat
+ */
+export async function releaseWorkspaceLock(): Promise<void> {
+  if (heartbeatTimer === null) {
+    return;
+  }
+
+  stopHeartbeat();
+  const lock = await readLock();
+  if (lock?.instance_id === getInstanceId()) {
+    await fsService.deletePath(LOCK_PATH);
+  }
+}
@@ -0,0 +1,161 @@
+/**
+ * Workspace settings - defaults, parsing and asset path patterns
+ * Settings live in the workspace itself (.mdx/settings.json) so they travel
+ * with the folder; fs-service reads and writes the file.
+ */
+
+import type {
+  AssetLayout,
+  ClipperSettings,
+  FormatSettings,
+  ImageOutputFormat,
+  ImageProcessingOptions,
+  WorkspaceSettings,
+} from "../types";
+
+export const SETTINGS_PATH = ".mdx/settings.json";
+
+/** Last settings file that parsed, restored when settings.json is corrupt */
+export const SETTINGS_BACKUP_PATH = ".mdx/settings.json.bak";
+
+/** Large images are downscaled; PNG screenshots stay lossless unless a format is chosen */
+const DEFAULT_IMAGE_PROCESSING: ImageProcessingOptions = {
+  maxDimension: 2560,
+  pngFormat: "original",
+  quality: 0.85,
+};
+
+export const DEFAULT_WORKSPACE_SETTINGS: WorkspaceSettings = {
+  assets: {
+    root: "assets",
+    layout: "date",
+    pattern: "{date}/{name}",
+    filename: null,
+    processing: DEFAULT_IMAGE_PROCESSING,
+    ocr: false,
+  },
+  bibliography: null,
+  format: {
+    onSave: false,
+    listMarker: "-",
+    alignTables: true,
+    renumberLists: true,
+    lineWidth: null,
+  },
+  clipper: {
+    enabled: false,
+    folder: "Clippings",
+    token: null,
+  },
+};
+
+const ASSET_LAYOUT_PATTERNS: Record<Exclude<AssetLayout, "custom">, string> = {
+  flat: "{name}",
+  "per-note": "{note}/{name}",
+  date: "{date}/{name}",
+};
+
+const ASSET_LAYOUTS = new Set<string>(["flat", "per-note", "date", "custom"]);
+const LIST_MARKERS = new Set<string>(["-", "*", "+"]);
+const IMAGE_OUTPUT_FORMATS = new Set<string>(["original", "webp", "jpeg"]);
+
+function parseImageProcessing(processing: unknown): ImageProcessingOptions | null {
+  const defaults = DEFAULT_IMAGE_PROCESSING;
+  if (processing === null) {
+    return null;
+  }
+  if (!processing || typeof processing !== "object") {
+    return defaults;
+  }
+
+  const { maxDimension, pngFormat, quality } = processing as Record<string, unknown>;
+  return {
+    maxDimension:
+      maxDimension === null || (typeof maxDimension === "number" && Number.isInteger(maxDimension) && maxDimension > 0)
+        ? maxDimension
+        : defaults.maxDimension,
+    pngFormat:
+      typeof pngFormat === "string" && IMAGE_OUTPUT_FORMATS.has(pngFormat)
+        ? (pngFormat as ImageOutputFormat)
+        : defaults.pngFormat,
+    quality: typeof quality === "number" && quality > 0 && quality <= 1 ? quality : defaults.quality,
+  };
+}
+
+function parseFormatSettings(format: Record<string, unknown>): FormatSettings {
+  const defaults = DEFAULT_WORKSPACE_SETTINGS.format;
+  const lineWidth = format.lineWidth;
+
+  return {
+    onSave: typeof format.onSave === "boolean" ? format.onSave : defaults.onSave,
+    listMarker:
+      typeof format.listMarker === "string" && LIST_MARKERS.has(format.listMarker)
+        ? (format.listMarker as FormatSettings["listMarker"])
+        : defaults.listMarker,
+    alignTables: typeof format.alignTables === "boolean" ? format.alignTables : defaults.alignTables,
+    renumberLists: typeof format.renumberLists === "boolean" ? format.renumberLists : defaults.renumberLists,
+    lineWidth:
+      typeof lineWidth === "number" && Number.isInteger(lineWidth) && lineWidth > 0 ? lineWidth : defaults.lineWidth,
+  };
+}
+
+function parseClipperSettings(clipper: Record<string, unknown>): ClipperSettings {
+  const defaults = DEFAULT_WORKSPACE_SETTINGS.clipper;
+
+  return {
+    enabled: typeof clipper.enabled === "boolean" ? clipper.enabled : defaults.enabled,
+    folder: typeof clipper.folder === "string" && clipper.folder.trim() ? clipper.folder.trim() : defaults.folder,
+    token: typeof clipper.token === "string" && clipper.token ? clipper.token : defaults.token,
+  };
+}
+
+/**
+ * Parse settings.json, falling back to the default for any missing or invalid field
+ * @returns Null if the file is not valid JSON (e.g. truncated by a crash)
+ */
+export function parseWorkspaceSettings(json: string): WorkspaceSettings | null {
+  let parsed: unknown;
+  try {
+    parsed = JSON.parse(json);
+  } catch {
+    return null;
+  }
+
+  const assets = (parsed as { assets?: Record<string, unknown> } | null)?.assets ?? {};
+  const bibliography = (parsed as { bibliography?: unknown } | null)?.bibliography;
+  const format = (parsed as { format?: Record<string, unknown> } | null)?.format ?? {};
+  const clipper = (parsed as { clipper?: Record<string, unknown> } | null)?.clipper ?? {};
+  const defaults = DEFAULT_WORKSPACE_SETTINGS.assets;
+
+  return {
+    assets: {
+      root: typeof assets.root === "string" && assets.root.trim() ? assets.root.trim() : defaults.root,
+      layout:
+        typeof assets.layout === "string" && ASSET_LAYOUTS.has(assets.layout)
+          ? (assets.layout as AssetLayout)
+          : defaults.layout,
+      pattern: typeof assets.pattern === "string" && assets.pattern.trim() ? assets.pattern.trim() : defaults.pattern,
+      filename:
+        typeof assets.filename === "string" && assets.filename.trim() ? assets.filename.trim() : defaults.filename,
+      processing: parseImageProcessing(assets.processing),
+      ocr: typeof assets.ocr === "boolean" ? assets.ocr : defaults.ocr,
+    },
+    bibliography: typeof bibliography === "string" && bibliography.trim() ? bibliography.trim() : null,
+    format: parseFormatSettings(format),
+    clipper: parseClipperSettings(clipper),
+  };
+}
+
+export function getAssetPattern(settings: WorkspaceSettings): string {
+  const { layout, pattern } = settings.assets;
+  return layout === "custom" ? pattern : ASSET_LAYOUT_PATTERNS[layout];
+}
+
+/**
+ * Fill in the variables of an asset pattern
+ * Unknown variables are left as written; path separators inside values are kept
+ * so {note} mirrors the note's folder structure
+ */
+export function expandAssetPattern(pattern: string, variables: Record<string, string>): string {
+  return pattern.replace(/\{(\w+)\}/g, (match, name: string) => variables[name] ?? match);
+}
@@ -0,0 +1,195 @@
+/**
+ * Writing stats - counts notes created and edited and words written per
+ * day, for a writing streak dashboard
+ * Off until the user opts in. The counts stay in the origin private file
+ * system and are never sent anywhere.
+ */
+
+import type { WritingDay, WritingStats } from "../types";
+import { AppError } from "./errors";
+import { isNotePath } from "./filenameUtils";
+import * as fsService from "./fs-service";
+
+const SETTINGS_KEY = "mdx-writing-stats";
+const STATS_DIRECTORY = "stats";
+const STATS_FILE = "writing.json";
+const WORD_PATTERN = /[\p{L}\p{N}][\p{L}\p{N}'’-]*/gu;
+const DATE_PATTERN = /^\d{4}-\d{2}-\d{2}$/;
+
+/** One day's activity as stored; edited notes are kept by path so each counts once */
+interface StoredDay {
+  created: number;
+  edited: string[];
+  words: number;
+}
+
+type StoredStats = Record<string, StoredDay>;
+
+let enabled = typeof localStorage !== "undefined" && localStorage.getItem(SETTINGS_KEY) === "on";
+
+function toDateKey(date: Date): string {
+  const month = String(date.getMonth() + 1).padStart(2, "0");
+  const day = String(date.getDate()).padStart(2, "0");
+  return `${date.getFullYear()}-${month}-${day}`;
+}
+
+function fromDateKey(key: string): Date {
+  const [year, month, day] = key.split("-").map(Number);
+  return new Date(year, month - 1, day);
+}
+
+/** The day after a date key; built from parts so daylight saving changes can't skip a day */
+function nextDateKey(key: string): string {
+  const date = fromDateKey(key);
+  date.setDate(date.getDate() + 1);
+  return toDateKey(date);
+}
+
+function countWords(content: string): number {
+  return content.match(WORD_PATTERN)?.length ?? 0;
+}
+
+async function getStatsDirectory(): Promise<FileSystemDirectoryHandle | null> {
+  if (typeof navigator === "undefined" || !navigator.storage?.getDirectory) {
+    return null;
+  }
+
+  const root = await navigator.storage.getDirectory();
+  return root.getDirectoryHandle(STATS_DIRECTORY, { create: true });
+}
+
+async function readStats(directory: FileSystemDirectoryHandle): Promise<StoredStats> {
+  try {
+    const text = await (await (await directory.getFileHandle(STATS_FILE)).getFile()).text();
+    const parsed: unknown = JSON.parse(text);
+    return parsed && typeof parsed === "object" ? (parsed as StoredStats) : {};
+  } catch {
+    return {};
+  }
+}
+
+/**
+ * Update today's counts; windows share the file, so updates are serialized
+ */
+async function updateToday(update: (day: StoredDay) => void): Promise<void> {
+  try {
+    const directory = await getStatsDirectory();
+    if (!directory) {
+      return;
+    }
+
+    const task = async () => {
+      const stats = await readStats(directory);
+      const today = toDateKey(new Date());
+      const day = stats[today] ?? { created: 0, edited: [], words: 0 };
+      update(day);
+      stats[today] = day;
+
+      const handle = await directory.getFileHandle(STATS_FILE, { create: true });
+      await fsService.writeFileHandle(handle, JSON.stringify(stats));
+    };
+    await (navigator.locks ? navigator.locks.request("mdx:writing-stats", task) : task());
+  } catch (error) {
+    console.warn("[WritingStats] Failed to record activity:", error);
+  }
+}
+
+export function isWritingStatsEnabled(): boolean {
+  return enabled;
+}
+
+/**
+ * Opt in to or out of writing stats
+ * Opting out deletes the counts recorded so far.
+ */
+export async function setWritingStatsEnabled(value: boolean): Promise<void> {
+  enabled = value;
+  localStorage.setItem(SETTINGS_KEY, value ? "on" : "off");
+  if (!value) {
+    const root = await navigator.storage?.getDirectory?.();
+    await root?.removeEntry(STATS_DIRECTORY, { recursive: true }).catch(() => undefined);
+  }
+}
+
+/**
+ * Count a new note toward today's stats
+ */
+export async function recordNoteCreated(path: string): Promise<void> {
+  if (enabled && isNotePath(path)) {
+    await updateToday((day) => {
+      day.created += 1;
+    });
+  }
+}
+
+/**
+ * Count a saved note toward today's stats
+ * Words written are the words the save added; deleting text doesn't subtract.
+ */
+export async function recordNoteEdited(path: string, before: string, after: string): Promise<void> {
+  if (!enabled || !isNotePath(path) || before === after) {
+    return;
+  }
+
+  const added = Math.max(0, countWords(after) - countWords(before));
+  await updateToday((day) => {
+    if (!day.edited.includes(path)) {
+      day.edited.push(path);
+    }
+    day.words += added;
+  });
+}
+
+function isActive(day: StoredDay | undefined): boolean {
+  return day !== undefined && (day.created > 0 || day.edited.length > 0);
+}
+
+/**
+ * Writing activity for every day in a range, with the writing streaks
+ * A streak still counts as current when nothing has been written yet today.
+ * @param from - First day, YYYY-MM-DD
+ * @param to - Last day, YYYY-MM-DD
+ */
+export async function getWritingStats(from: string, to: string): Promise<WritingStats> {
+  if (!DATE_PATTERN.test(from) || !DATE_PATTERN.test(to) || from > to) {
+    throw new AppError("invalid-argument", "Expected a date range as YYYY-MM-DD, with from before to");
+  }
+
+  const directory = enabled ? await getStatsDirectory() : null;
+  const stats = directory ? await readStats(directory) : {};
+
+  const days: WritingDay[] = [];
+  for (let key = from; key <= to; key = nextDateKey(key)) {
+    const day = stats[key];
+    days.push({
+      date: key,
+      notes_created: day?.created ?? 0,
+      notes_edited: day?.edited.length ?? 0,
+      words_written: day?.words ?? 0,
+    });
+  }
+
+  let longestStreak = 0;
+  let streak = 0;
+  let previous: string | null = null;
+  for (const key of Object.keys(stats).sort()) {
+    if (!isActive(stats[key])) {
+      continue;
+    }
+    streak = previous !== null && nextDateKey(previous) === key ? streak + 1 : 1;
+    longestStreak = Math.max(longestStreak, streak);
+    previous = key;
+  }
+
+  let currentStreak = 0;
+  const cursor = new Date();
+  if (!isActive(stats[toDateKey(cursor)])) {
+    cursor.setDate(cursor.getDate() - 1);
+  }
+  while (isActive(stats[toDateKey(cursor)])) {
+    currentStreak += 1;
+    cursor.setDate(cursor.getDate() - 1);
+  }
+
+  return { days, current_streak: currentStreak, longest_streak: longestStreak };
+}
@@ -0,0 +1,331 @@
+/**
+ * Minimal ZIP support
+ * The writer deflates entries with the native CompressionStream and writes
+ * them to the output as they are added, so large archives never sit fully
+ * in memory. The reader slices entries out of a Blob on demand.
+ * No ZIP64 support: archives are limited to 65535 entries and 4 GB.
+ */
+
+const LOCAL_FILE_HEADER_SIGNATURE = 0x04034b50;
+const CENTRAL_DIRECTORY_SIGNATURE = 0x02014b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE = 0x06054b50;
+
+const ZIP_VERSION = 20;
+const UTF8_FILENAME_FLAG = 0x0800;
+const METHOD_STORE = 0;
+const METHOD_DEFLATE = 8;
+const DIRECTORY_ATTRIBUTE = 0x10;
+const ENCRYPTED_FLAG = 0x0001;
+
+const LOCAL_FILE_HEADER_SIZE = 30;
+const CENTRAL_DIRECTORY_RECORD_SIZE = 46;
+const END_OF_CENTRAL_DIRECTORY_SIZE = 22;
+const MAX_COMMENT_SIZE = 0xffff;
+
+const MAX_ENTRIES = 0xffff;
+const MAX_OFFSET = 0xffffffff;
+
+export interface ZipOutput {
+  write(chunk: Uint8Array): Promise<void>;
+}
+
+export interface ZipWriter {
+  /** Add a file entry (deflated unless compression would not help) */
+  addFile(name: string, data: Uint8Array, modified: Date): Promise<void>;
+
+  /** Add an explicit directory entry (keeps empty folders) */
+  addDirectory(name: string, modified: Date): Promise<void>;
+
+  /** Write the central directory; no entries can be added afterwards */
+  close(): Promise<void>;
+}
+
+export interface ZipEntry {
+  /** Path inside the archive, forward slashes */
+  name: string;
+
+  isDirectory: boolean;
+
+  /** Uncompressed size in bytes */
+  size: number;
+
+  modified: Date;
+
+  /** Read and decompress the entry's contents */
+  read(): Promise<Blob>;
+}
+
+interface CentralDirectoryEntry {
+  name: Uint8Array;
+  method: number;
+  crc: number;
+  compressedSize: number;
+  size: number;
+  time: number;
+  date: number;
+  externalAttributes: number;
+  offset: number;
+}
+
+const CRC_TABLE = (() => {
+  const table = new Uint32Array(256);
+  for (let index = 0; index < 256; index += 1) {
+    let value = index;
+    for (let bit = 0; bit < 8; bit += 1) {
+      value = value & 1 ? 0xedb88320 ^ (value >>> 1) : value >>> 1;
+    }
+    table[index] = value >>> 0;
+  }
+  return table;
+})();
+
+function crc32(data: Uint8Array): number {
+  let crc = 0xffffffff;
+  for (let index = 0; index < data.length; index += 1) {
+    crc = CRC_TABLE[(crc ^ data[index]) & 0xff] ^ (crc >>> 8);
+  }
+  return (crc ^ 0xffffffff) >>> 0;
+}
+
+function toDosDateTime(value: Date): { time: number; date: number } {
+  const year = Math.max(value.getFullYear(), 1980);
+  return {
+    time: (value.getHours() << 11) | (value.getMinutes() << 5) | Math.floor(value.getSeconds() / 2),
+    date: ((year - 1980) << 9) | ((value.getMonth() + 1) << 5) | value.getDate(),
+  };
+}
+
+function fromDosDateTime(time: number, date: number): Date {
+  return new Date(
+    (date >> 9) + 1980,
+    ((date >> 5) & 0x0f) - 1,
+    date & 0x1f,
+    time >> 11,
+    (time >> 5) & 0x3f,
+    (time & 0x1f) * 2
+  );
+}
+
+async function deflateRaw(data: Uint8Array): Promise<Uint8Array> {
+  const stream = new Blob([data]).stream().pipeThrough(new CompressionStream("deflate-raw"));
+  return new Uint8Array(await new Response(stream).arrayBuffer());
+}
+
+export function createZipWriter(output: ZipOutput): ZipWriter {
+  const encoder = new TextEncoder();
+  const entries: CentralDirectoryEntry[] = [];
+  let offset = 0;
+  let closed = false;
+
+  async function write(chunk: Uint8Array): Promise<void> {
+    await output.write(chunk);
+    offset += chunk.length;
+  }
+
+  async function addEntry(
+    name: string,
+    data: Uint8Array,
+    modified: Date,
+    externalAttributes: number
+  ): Promise<void> {
+    if (closed) {
+      throw new Error("Cannot add entries to a closed archive");
+    }
+    if (entries.length >= MAX_ENTRIES) {
+      throw new Error(`Archive cannot hold more than ${MAX_ENTRIES} entries`);
+    }
+
+    const encodedName = encoder.encode(name);
+    const { time, date } = toDosDateTime(modified);
+    const crc = crc32(data);
+
+    let method = METHOD_STORE;
+    let payload = data;
+    if (data.length > 0) {
+      const compressed = await deflateRaw(data);
+      if (compressed.length < data.length) {
+        method = METHOD_DEFLATE;
+        payload = compressed;
+      }
+    }
+
+    if (offset + 30 + encodedName.length + payload.length > MAX_OFFSET) {
+      throw new Error("Archive exceeds the 4 GB ZIP limit");
+    }
+
+    const header = new Uint8Array(30 + encodedName.length);
+    const view = new DataView(header.buffer);
+    view.setUint32(0, LOCAL_FILE_HEADER_SIGNATURE, true);
+    view.setUint16(4, ZIP_VERSION, true);
+    view.setUint16(6, UTF8_FILENAME_FLAG, true);
+    view.setUint16(8, method, true);
+    view.setUint16(10, time, true);
+    view.setUint16(12, date, true);
+    view.setUint32(14, crc, true);
+    view.setUint32(18, payload.length, true);
+    view.setUint32(22, data.length, true);
+    view.setUint16(26, encodedName.length, true);
+    view.setUint16(28, 0, true);
+    header.set(encodedName, 30);
+
+    entries.push({
+      name: encodedName,
+      method,
+      crc,
+      compressedSize: payload.length,
+      size: data.length,
+      time,
+      date,
+      externalAttributes,
+      offset,
+    });
+
+    await write(header);
+    await write(payload);
+  }
+
+  return {
+    addFile: (name, data, modified) => addEntry(name, data, modified, 0),
+
+    addDirectory: (name, modified) =>
+      addEntry(name.endsWith("/") ? name : `${name}/`, new Uint8Array(0), modified, DIRECTORY_ATTRIBUTE),
+
+    close: async () => {
+      if (closed) {
+        return;
+      }
+      closed = true;
+
+      const centralDirectoryOffset = offset;
+
+      for (const entry of entries) {
+        const record = new Uint8Array(46 + entry.name.length);
+        const view = new DataView(record.buffer);
+        view.setUint32(0, CENTRAL_DIRECTORY_SIGNATURE, true);
+        view.setUint16(4, ZIP_VERSION, true);
+        view.setUint16(6, ZIP_VERSION, true);
+        view.setUint16(8, UTF8_FILENAME_FLAG, true);
+        view.setUint16(10, entry.method, true);
+        view.setUint16(12, entry.time, true);
+        view.setUint16(14, entry.date, true);
+        view.setUint32(16, entry.crc, true);
+        view.setUint32(20, entry.compressedSize, true);
+        view.setUint32(24, entry.size, true);
+        view.setUint16(28, entry.name.length, true);
+        view.setUint16(30, 0, true);
+        view.setUint16(32, 0, true);
+        view.setUint16(34, 0, true);
+        view.setUint16(36, 0, true);
+        view.setUint32(38, entry.externalAttributes, true);
+        view.setUint32(42, entry.offset, true);
+        record.set(entry.name, 46);
+        await write(record);
+      }
+
+      const centralDirectorySize = offset - centralDirectoryOffset;
+      const end = new Uint8Array(22);
+      const view = new DataView(end.buffer);
+      view.setUint32(0, END_OF_CENTRAL_DIRECTORY_SIGNATURE, true);
+      view.setUint16(4, 0, true);
+      view.setUint16(6, 0, true);
+      view.setUint16(8, entries.length, true);
+      view.setUint16(10, entries.length, true);
+      view.setUint32(12, centralDirectorySize, true);
+      view.setUint32(16, centralDirectoryOffset, true);
+      view.setUint16(20, 0, true);
+      await write(end);
+    },
+  };
+}
+
+async function readBytes(archive: Blob, start: number, end: number): Promise<DataView> {
+  return new DataView(await archive.slice(start, end).arrayBuffer());
+}
+
+async function findEndOfCentralDirectory(archive: Blob): Promise<DataView> {
+  const searchStart = Math.max(0, archive.size - END_OF_CENTRAL_DIRECTORY_SIZE - MAX_COMMENT_SIZE);
+  const tail = await readBytes(archive, searchStart, archive.size);
+
+  for (let position = tail.byteLength - END_OF_CENTRAL_DIRECTORY_SIZE; position >= 0; position -= 1) {
+    if (tail.getUint32(position, true) === END_OF_CENTRAL_DIRECTORY_SIGNATURE) {
+      return new DataView(tail.buffer, position, END_OF_CENTRAL_DIRECTORY_SIZE);
+    }
+  }
+
+  throw new Error("Not a ZIP archive");
+}
+
+/**
+ * List the entries of a ZIP archive without extracting them
+ */
+export async function readZipEntries(archive: Blob): Promise<ZipEntry[]> {
+  const end = await findEndOfCentralDirectory(archive);
+  const entryCount = end.getUint16(10, true);
+  const centralDirectorySize = end.getUint32(12, true);
+  const centralDirectoryOffset = end.getUint32(16, true);
+
+  if (entryCount === MAX_ENTRIES || centralDirectoryOffset === MAX_OFFSET) {
+    throw new Error("ZIP64 archives are not supported");
+  }
+
+  const directory = await readBytes(
+    archive,
+    centralDirectoryOffset,
+    centralDirectoryOffset + centralDirectorySize
+  );
+  const decoder = new TextDecoder();
+  const entries: ZipEntry[] = [];
+  let position = 0;
+
+  for (let index = 0; index < entryCount; index += 1) {
+    if (directory.getUint32(position, true) !== CENTRAL_DIRECTORY_SIGNATURE) {
+      throw new Error("Corrupt ZIP central directory");
+    }
+
+    const flags = directory.getUint16(position + 8, true);
+    const method = directory.getUint16(position + 10, true);
+    const time = directory.getUint16(position + 12, true);
+    const date = directory.getUint16(position + 14, true);
+    const compressedSize = directory.getUint32(position + 20, true);
+    const size = directory.getUint32(position + 24, true);
+    const nameLength = directory.getUint16(position + 28, true);
+    const extraLength = directory.getUint16(position + 30, true);
+    const commentLength = directory.getUint16(position + 32, true);
+    const offset = directory.getUint32(position + 42, true);
+    const nameStart = directory.byteOffset + position + CENTRAL_DIRECTORY_RECORD_SIZE;
+    const name = decoder.decode(new Uint8Array(directory.buffer, nameStart, nameLength)).replace(/\\/g, "/");
+
+    position += CENTRAL_DIRECTORY_RECORD_SIZE + nameLength + extraLength + commentLength;
+
+    entries.push({
+      name,
+      isDirectory: name.endsWith("/"),
+      size,
+      modified: fromDosDateTime(time, date),
+      read: async () => {
+        if (flags & ENCRYPTED_FLAG) {
+          throw new Error(`Encrypted entry is not supported: ${name}`);
+        }
+
+        const header = await readBytes(archive, offset, offset + LOCAL_FILE_HEADER_SIZE);
+        if (header.getUint32(0, true) !== LOCAL_FILE_HEADER_SIGNATURE) {
+          throw new Error(`Corrupt ZIP entry: ${name}`);
+        }
+
+        const dataStart =
+          offset + LOCAL_FILE_HEADER_SIZE + header.getUint16(26, true) + header.getUint16(28, true);
+        const data = archive.slice(dataStart, dataStart + compressedSize);
+
+        if (method === METHOD_STORE) {
+          return data;
+        }
+        if (method === METHOD_DEFLATE) {
+          return new Response(data.stream().pipeThrough(new DecompressionStream("deflate-raw"))).blob();
+        }
+        throw new Error(`Unsupported compression method ${method} for ${name}`);
+      },
+    });
+  }
+
+  return entries;
+}
@@ -3,7 +3,9 @@
  */
 
 import { create } from "zustand";
-import { readFile, writeFile } from "../lib/api";
+import { formatDocument, getWorkspaceSettings, readFile, writeFile } from "../lib/api";
+import { listen } from "../lib/app-events";
+import { useFileTreeStore } from "./fileTreeStore";
 import { toast } from "sonner";
 import { EDITOR_CONFIG } from "../config/editor";
 import type { RefObject } from "react";
@@ -46,7 +48,7 @@ interface EditorStore {
   /** Path of the file that has a conflict */
   conflictFilePath: string | null;
   
-  /** Show deletion modal when file deleted externally (unused - kept for DeletionModal component) */
+  /** Show deletion modal when the open file is deleted by another window with unsaved changes */
   showDeletionModal: boolean;
 
   /** Reference to the MDXEditor instance for programmatic control */
@@ -113,7 +115,7 @@ interface EditorStore {
   keepMyChanges: () => void;
   
   /**
-   * Close deletion modal (unused stub for DeletionModal component)
+   * Close deletion modal and the deleted file
    */
   closeDeletionModal: () => void;
 }
@@ -229,6 +231,23 @@ export const useEditorStore = create<EditorStore>((set, get) => ({
   manualSave: async () => {
     // Cancel pending auto-save to avoid double saves
     get().cancelAutoSave();
+
+    // Format on explicit saves only: auto-save runs mid-typing
+    const { currentPath, content } = get();
+    if (content !== null) {
+      try {
+        const { format } = await getWorkspaceSettings();
+        const formatted = format.onSave ? formatDocument(content, format) : content;
+        // Anything typed while the settings loaded wins over the formatting of the older text
+        const current = get();
+        if (formatted !== content && current.currentPath === currentPath && current.content === content) {
+          set({ content: formatted, isDirty: formatted !== current.originalContent });
+        }
+      } catch (error) {
+        console.warn("Failed to format on save:", error);
+      }
+    }
+
     return await get().saveFile();
   },
 
@@ -244,12 +263,11 @@ export const useEditorStore = create<EditorStore>((set, get) => ({
     
     try {
       await writeFile(currentPath, content);
-      set({
-        originalContent: content,
-        isDirty: false,
-        isSaving: false,
-        lastSaved: new Date(),
-      });
+      // Edits made during the write stay dirty for the next save
+      if (get().currentPath === currentPath) {
+        set({ originalContent: content, isDirty: get().content !== content });
+      }
+      set({ isSaving: false, lastSaved: new Date() });
       // We don't show toast for auto-saves to avoid spamming the user
       // But we will handle visual feedback via the toolbar indicator
       return true;
@@ -311,10 +329,64 @@ export const useEditorStore = create<EditorStore>((set, get) => ({
       conflictFilePath: null 
     });
     toast.info("Keeping your changes");
+    if (get().isDirty) {
+      get().scheduleAutoSave();
+    }
   },
   
   closeDeletionModal: () => {
-    // Stub for DeletionModal component (not actively used)
     set({ showDeletionModal: false });
+    get().resetEditor();
   },
 }));
+
+// Follow changes other windows make to the open file
+listen("file-changed", async (change, { remote }) => {
+  const { currentPath, isDirty, isSaving } = useEditorStore.getState();
+  if (
+    !remote ||
+    !currentPath ||
+    change.workspace_path !== useFileTreeStore.getState().workspaceRootPath ||
+    (currentPath !== change.path && !(change.entry_kind === 'directory' && currentPath.startsWith(`${change.path}/`)))
+  ) {
+    return;
+  }
+
+  switch (change.kind) {
+    case 'renamed':
+      if (change.new_path !== null) {
+        useEditorStore.setState({ currentPath: change.new_path + currentPath.slice(change.path.length) });
+      }
+      break;
+
+    case 'deleted':
+      if (isDirty) {
+        useEditorStore.getState().cancelAutoSave();
+        useEditorStore.setState({ showDeletionModal: true });
+      } else {
+        useEditorStore.getState().resetEditor();
+      }
+      break;
+
+    case 'modified':
+    case 'created':
+      if (isDirty || isSaving) {
+        // Hold the pending auto-save so it can't overwrite the other window's change unasked
+        useEditorStore.getState().cancelAutoSave();
+        useEditorStore.setState({ showConflictModal: true, conflictFilePath: currentPath });
+        return;
+      }
+
+      try {
+        const fileContent = await readFile(currentPath);
+        // Skip if the file was switched or edited while reading
+        const state = useEditorStore.getState();
+        if (state.currentPath === currentPath && !state.isDirty && fileContent !== state.originalContent) {
+          useEditorStore.setState({ content: fileContent, originalContent: fileContent });
+        }
+      } catch (error) {
+        console.error('Failed to reload file changed in another window:', error);
+      }
+      break;
+  }
+});
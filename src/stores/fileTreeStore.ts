@@ -4,10 +4,13 @@
 
 import { create } from "zustand";
 import { toast } from "sonner";
-import type { FileNode } from "../types";
+import type { FileNode, WorkspaceSession } from "../types";
 import * as api from "../lib/api";
-import { readDirectory } from "../lib/api";
-import { flattenTree, type FlatNode } from "../lib/treeUtils";
+import { getViewPreferences, readDirectory } from "../lib/api";
+import { listen } from "../lib/app-events";
+import { getExtension, getFileKind } from "../lib/filenameUtils";
+import { getParentPath } from "../lib/pathUtils";
+import { compareNodes, flattenTree, type FlatNode } from "../lib/treeUtils";
 import { createTreeSnapshot, restoreTreeSnapshot } from "../lib/treeSnapshot";
 import { useUndoStore } from "./undoStore";
 
@@ -145,7 +148,8 @@ interface FileTreeStore {
   /**
    * Update a node optimistically in the tree
    * @param path - Path of node to update
-   * @param updates - Partial node updates to apply
+   * @param updates - Partial node updates to apply; a new path also updates
+   *   the name, relative path, extension and kind unless given
    */
   updateOptimisticNode: (path: string, updates: Partial<FileNode>) => void;
 
@@ -209,6 +213,20 @@ interface FileTreeStore {
    * @param path - Path of the folder to refresh (or '.' for root)
    */
   refreshNode: (path: string) => Promise<void>;
+
+  /**
+   * Restore expanded folders and the active file from the saved workspace session
+   * Folders are loaded parent-first so nested expansions can be resolved
+   */
+  restoreSession: () => Promise<void>;
+
+  /**
+   * Expand the folders above a file and make it the active file
+   * Used when a file is opened from outside the tree (launches, deep links)
+   * @param path - Workspace path of the file
+   * @returns false if the file is not in the tree
+   */
+  revealFile: (path: string) => Promise<boolean>;
 }
 
 export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
@@ -225,12 +243,12 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
     set({ isLoading: true, error: null });
     try {
       // Read root directory (use "." for workspace root)
-      const rootNode = await readDirectory(".", false);
+      const rootNode = await readDirectory(".", getViewPreferences().show_hidden_files);
       const workspaceRootPath = normalizeStorePath(rootNode.path);
 
       // Set root nodes to children of the root directory, filtered to only markdown files
       const nodes = filterMarkdownFiles(
-        normalizeNodesToRelative(rootNode.children || [], workspaceRootPath)
+        normalizeNodesToRelative(rootNode.children || [])
       );
       set({ nodes, workspaceRootPath, isLoading: false });
     } catch (error) {
@@ -264,11 +282,11 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
       if (node.children === null) {
         set({ isLoading: true, error: null });
         try {
-          const dirNode = await readDirectory(normalizedPath, false);
+          const dirNode = await readDirectory(normalizedPath, getViewPreferences().show_hidden_files);
           
           // Update node with children, filtered to only markdown files
           const children = filterMarkdownFiles(
-            normalizeNodesToRelative(dirNode.children || [], get().workspaceRootPath)
+            normalizeNodesToRelative(dirNode.children || [])
           );
           get().updateNode(normalizedPath, children);
           
@@ -313,7 +331,7 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
 
   getFlatNodes: () => {
     const { nodes, expandedFolders } = get();
-    return flattenTree(nodes, expandedFolders);
+    return flattenTree(nodes, expandedFolders, compareNodes(getViewPreferences()));
   },
 
   addPendingOperation: (operation: PendingOperation) => {
@@ -399,13 +417,21 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
 
   updateOptimisticNode: (path: string, updates: Partial<FileNode>) => {
     const normalizedPath = normalizeTreePath(path, get().workspaceRootPath);
-    const normalizedUpdates = {
-      ...updates,
-      path:
-        typeof updates.path === "string"
-          ? normalizeTreePath(updates.path, get().workspaceRootPath)
-          : updates.path,
-    };
+    let normalizedUpdates = updates;
+    if (typeof updates.path === "string") {
+      // Everything derived from the path moves with it
+      const newPath = normalizeTreePath(updates.path, get().workspaceRootPath);
+      const name = newPath.slice(newPath.lastIndexOf('/') + 1);
+      const isFile = findNodeByPath(get().nodes, normalizedPath)?.is_file ?? true;
+      normalizedUpdates = {
+        name,
+        relative_path: newPath,
+        extension: isFile ? getExtension(name) : '',
+        kind: isFile ? getFileKind(name) : 'directory',
+        ...updates,
+        path: newPath,
+      };
+    }
 
     set((state) => ({
       nodes: updateNodeInTree(state.nodes, normalizedPath, normalizedUpdates),
@@ -447,10 +473,15 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
     get().addOptimisticNode(normalizedParentPath, {
       name: fileName,
       path: fullPath,
+      relative_path: fullPath,
       is_file: true,
       size: 0,
-      modified: new Date().toISOString(),
+      modified: Date.now(),
+      created: null,
+      extension: getExtension(fileName),
       children: null,
+      has_children: false,
+      kind: getFileKind(fileName),
       isPending: true,
     });
 
@@ -511,10 +542,15 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
     get().addOptimisticNode(normalizedParentPath, {
       name: folderName,
       path: fullPath,
+      relative_path: fullPath,
       is_file: false,
       size: null,
-      modified: new Date().toISOString(),
+      modified: null,
+      created: null,
+      extension: '',
       children: [],
+      has_children: false,
+      kind: 'directory',
       isPending: true,
     });
 
@@ -594,7 +630,7 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
     }
 
     // Backend call with toast
-    const promise = api.renamePath(normalizedOldPath, newPath);
+    const promise = api.renamePath(normalizedOldPath, newPath, operationId);
 
     toast.promise(promise, {
       loading: `Renaming to ${newName}...`,
@@ -702,7 +738,7 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
     }
 
     // Backend call with toast
-    const promise = api.deletePath(normalizedPath);
+    const promise = api.deletePath(normalizedPath, operationId);
 
     // Calculate parent path for refresh
     const parentPath = normalizedPath.includes('/') 
@@ -809,10 +845,10 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
 
       if (isRoot) {
         // If refreshing root, reuse loadRootDirectory logic but keep it silent
-        const rootNode = await readDirectory(".", false);
+        const rootNode = await readDirectory(".", getViewPreferences().show_hidden_files);
         const workspaceRootPath = normalizeStorePath(rootNode.path);
         const nodes = filterMarkdownFiles(
-          normalizeNodesToRelative(rootNode.children || [], workspaceRootPath)
+          normalizeNodesToRelative(rootNode.children || [])
         );
         set({ nodes, workspaceRootPath });
         return;
@@ -825,9 +861,9 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
       }
 
       // Refresh specific folder
-      const dirNode = await readDirectory(normalizedPath, false);
+      const dirNode = await readDirectory(normalizedPath, getViewPreferences().show_hidden_files);
       const children = filterMarkdownFiles(
-        normalizeNodesToRelative(dirNode.children || [], get().workspaceRootPath)
+        normalizeNodesToRelative(dirNode.children || [])
       );
       get().updateNode(normalizedPath, children);
     } catch (error) {
@@ -835,8 +871,165 @@ export const useFileTreeStore = create<FileTreeStore>((set, get) => ({
       // Don't show error to user as this is a background sync
     }
   },
+
+  restoreSession: async () => {
+    const { workspaceRootPath } = get();
+    if (!workspaceRootPath) return;
+
+    let session: WorkspaceSession | null = null;
+    try {
+      session = await api.loadSession();
+    } catch (error) {
+      console.warn('[FileTreeStore] Could not load session:', error);
+    }
+
+    // Folders above a file revealed while the session loaded stay open
+    const revealedFolders = (get().activePath?.split('/').slice(0, -1) ?? []).map((_, index, segments) =>
+      segments.slice(0, index + 1).join('/')
+    );
+
+    if (!session) {
+      set({ expandedFolders: new Set(revealedFolders) });
+      return;
+    }
+
+    const folders = [...session.expandedFolders].sort(
+      (a, b) => a.split('/').length - b.split('/').length
+    );
+    const expanded = new Set<string>(revealedFolders);
+
+    for (const folderPath of folders) {
+      const node = findNodeByPath(get().nodes, folderPath);
+      if (!node || node.is_file) continue;
+
+      if (node.children === null) {
+        try {
+          const dirNode = await readDirectory(folderPath, getViewPreferences().show_hidden_files);
+          const children = filterMarkdownFiles(
+            normalizeNodesToRelative(dirNode.children || [])
+          );
+          get().updateNode(folderPath, children);
+        } catch (error) {
+          // Folder was removed or renamed since the session was saved
+          console.warn(`[FileTreeStore] Skipping stale session folder ${folderPath}:`, error);
+          continue;
+        }
+      }
+
+      expanded.add(folderPath);
+    }
+
+    set({ expandedFolders: expanded });
+
+    // A file opened by a launch while the session loaded wins over the saved one
+    if (session.activePath && !get().activePath && findNodeByPath(get().nodes, session.activePath)) {
+      get().setActiveFile(session.activePath);
+    }
+  },
+
+  revealFile: async (path: string) => {
+    const { workspaceRootPath } = get();
+    const normalizedPath = normalizeTreePath(path, workspaceRootPath);
+    const segments = normalizedPath.split('/');
+    const expanded = new Set(get().expandedFolders);
+
+    for (let depth = 1; depth < segments.length; depth += 1) {
+      const folderPath = segments.slice(0, depth).join('/');
+      const node = findNodeByPath(get().nodes, folderPath);
+      if (!node || node.is_file) return false;
+
+      if (node.children === null) {
+        try {
+          const dirNode = await readDirectory(folderPath, getViewPreferences().show_hidden_files);
+          const children = filterMarkdownFiles(
+            normalizeNodesToRelative(dirNode.children || [])
+          );
+          get().updateNode(folderPath, children);
+        } catch (error) {
+          console.warn(`[FileTreeStore] Could not load ${folderPath}:`, error);
+          return false;
+        }
+      }
+
+      expanded.add(folderPath);
+    }
+
+    const node = findNodeByPath(get().nodes, normalizedPath);
+    if (!node?.is_file) return false;
+
+    set({ expandedFolders: expanded });
+    get().setActiveFile(normalizedPath);
+    return true;
+  },
 }));
 
+// Hidden files are filtered when folders are read, so showing them means reading the tree again
+let showingHiddenFiles = getViewPreferences().show_hidden_files;
+listen("view-preferences-changed", (preferences) => {
+  if (preferences.show_hidden_files === showingHiddenFiles) {
+    return;
+  }
+  showingHiddenFiles = preferences.show_hidden_files;
+
+  const { workspaceRootPath, loadRootDirectory, restoreSession } = useFileTreeStore.getState();
+  if (workspaceRootPath) {
+    void loadRootDirectory().then(() => restoreSession());
+  }
+});
+
+// Bulk operations in another window send a change per file; each folder is read again once per burst
+const REFRESH_DELAY_MS = 100;
+const pendingRefreshes = new Set<string>();
+let refreshTimer: ReturnType<typeof setTimeout> | null = null;
+
+function scheduleRefresh(path: string): void {
+  pendingRefreshes.add(path);
+  refreshTimer ??= setTimeout(() => {
+    refreshTimer = null;
+    const { refreshNode } = useFileTreeStore.getState();
+    for (const parent of pendingRefreshes) {
+      void refreshNode(parent);
+    }
+    pendingRefreshes.clear();
+  }, REFRESH_DELAY_MS);
+}
+
+// Keep the tree in step with changes made in other windows of the workspace
+// This window's own changes are already applied optimistically
+listen("file-changed", (change, { remote }) => {
+  const { workspaceRootPath, activePath, expandedFolders } = useFileTreeStore.getState();
+  if (!remote || change.workspace_path !== workspaceRootPath) {
+    return;
+  }
+
+  if (change.kind === 'renamed' || change.kind === 'deleted') {
+    // The refreshed parent comes back with the moved folder's children unloaded
+    const nextExpanded = new Set(
+      [...expandedFolders].filter((folder) => !isSameOrInside(folder, change.path))
+    );
+    const movedActivePath = activePath && isSameOrInside(activePath, change.path)
+      ? change.new_path && change.new_path + activePath.slice(change.path.length)
+      : activePath;
+
+    useFileTreeStore.setState({ expandedFolders: nextExpanded, activePath: movedActivePath });
+  }
+
+  const parents = new Set([getParentPath(change.path)]);
+  if (change.new_path !== null) {
+    parents.add(getParentPath(change.new_path));
+  }
+  for (const parent of parents) {
+    scheduleRefresh(parent);
+  }
+});
+
+/**
+ * Whether a path is the given entry or lies inside it
+ */
+function isSameOrInside(path: string, entry: string): boolean {
+  return path === entry || path.startsWith(`${entry}/`);
+}
+
 function normalizeStorePath(path: string): string {
   const normalized = path.replace(/\\/g, "/").replace(/^\.\//, "").replace(/^\/+|\/+$/g, "");
   return normalized === "" ? "." : normalized;
@@ -862,14 +1055,11 @@ function normalizeTreePath(path: string, workspaceRootPath: string | null): stri
   return normalizedPath;
 }
 
-function normalizeNodesToRelative(
-  nodes: FileNode[],
-  workspaceRootPath: string | null
-): FileNode[] {
+function normalizeNodesToRelative(nodes: FileNode[]): FileNode[] {
   return nodes.map((node) => ({
     ...node,
-    path: normalizeTreePath(node.path, workspaceRootPath),
-    children: node.children ? normalizeNodesToRelative(node.children, workspaceRootPath) : node.children,
+    path: node.relative_path || ".",
+    children: node.children ? normalizeNodesToRelative(node.children) : node.children,
   }));
 }
 
@@ -882,9 +1072,8 @@ function filterMarkdownFiles(nodes: FileNode[]): FileNode[] {
     if (!node.is_file) {
       return true;
     }
-    // Keep only .md and .mdx files
-    const ext = node.name.toLowerCase();
-    return ext.endsWith('.md') || ext.endsWith('.mdx');
+    // Keep only notes
+    return node.kind === 'markdown';
   }).map(node => {
     // Recursively filter children if they exist
     if (node.children) {
@@ -3,12 +3,18 @@
  */
 
 import { create } from "zustand";
+import { toast } from "sonner";
 import { 
   showOpenDialog, 
   getWorkspace,
   requestWorkspacePermission,
   hasStoredWorkspace,
+  acquireWorkspaceLock,
+  forceUnlockWorkspace,
+  releaseWorkspaceLock,
 } from "../lib/api";
+import { listen } from "../lib/app-events";
+import type { WorkspaceLockInfo } from "../types";
 
 interface WorkspaceStore {
   /** Current workspace path (null if no workspace selected) */
@@ -23,6 +29,9 @@ interface WorkspaceStore {
   /** True when a remembered workspace needs permission re-grant */
   needsPermissionGrant: boolean;
 
+  /** Another app instance editing the workspace (null if we hold the lock) */
+  lockHolder: WorkspaceLockInfo | null;
+
   /**
    * Open workspace selection dialog and set workspace
    */
@@ -38,17 +47,28 @@ interface WorkspaceStore {
    */
   regrantWorkspacePermission: () => Promise<void>;
 
+  /**
+   * Take the workspace lock, warning if another instance holds it
+   */
+  checkWorkspaceLock: () => Promise<void>;
+
+  /**
+   * Take over the lock from another (presumably stale) instance
+   */
+  forceUnlock: () => Promise<void>;
+
   /**
    * Clear error message
    */
   clearError: () => void;
 }
 
-export const useWorkspaceStore = create<WorkspaceStore>((set) => ({
+export const useWorkspaceStore = create<WorkspaceStore>((set, get) => ({
   workspacePath: null,
   isLoading: false,
   error: null,
   needsPermissionGrant: false,
+  lockHolder: null,
 
   selectWorkspace: async () => {
     set({ isLoading: true, error: null, needsPermissionGrant: false });
@@ -56,6 +76,7 @@ export const useWorkspaceStore = create<WorkspaceStore>((set) => ({
       const path = await showOpenDialog();
       if (path) {
         set({ workspacePath: path, isLoading: false, needsPermissionGrant: false });
+        void get().checkWorkspaceLock();
       } else {
         // User cancelled dialog
         set({ isLoading: false });
@@ -73,12 +94,14 @@ export const useWorkspaceStore = create<WorkspaceStore>((set) => ({
 
       if (path) {
         set({ workspacePath: path, isLoading: false, needsPermissionGrant: false });
+        void get().checkWorkspaceLock();
         return;
       }
 
       const restoredAfterGrant = await requestWorkspacePermission();
       if (restoredAfterGrant) {
         set({ workspacePath: restoredAfterGrant, isLoading: false, needsPermissionGrant: false });
+        void get().checkWorkspaceLock();
         return;
       }
 
@@ -101,6 +124,7 @@ export const useWorkspaceStore = create<WorkspaceStore>((set) => ({
 
       if (path) {
         set({ workspacePath: path, isLoading: false, needsPermissionGrant: false });
+        void get().checkWorkspaceLock();
         return;
       }
 
@@ -111,5 +135,50 @@ export const useWorkspaceStore = create<WorkspaceStore>((set) => ({
     }
   },
 
+  checkWorkspaceLock: async () => {
+    try {
+      const { holder } = await acquireWorkspaceLock();
+      set({ lockHolder: holder });
+
+      if (holder) {
+        const since = new Date(holder.heartbeat_at).toLocaleTimeString();
+        toast.warning(`This workspace is open in another app instance (last active ${since})`, {
+          description: 'Edits from both instances may overwrite each other.',
+          duration: Infinity,
+          action: {
+            label: 'Force unlock',
+            onClick: () => {
+              void get().forceUnlock();
+            },
+          },
+        });
+      }
+    } catch (error) {
+      // A read-only or unreachable .mdx folder should not block editing
+      console.warn("[WorkspaceStore] Could not take workspace lock:", error);
+    }
+  },
+
+  forceUnlock: async () => {
+    try {
+      await forceUnlockWorkspace();
+      set({ lockHolder: null });
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      toast.error(errorMessage);
+    }
+  },
+
   clearError: () => set({ error: null }),
 }));
+
+window.addEventListener("pagehide", () => {
+  void releaseWorkspaceLock().catch(() => undefined);
+});
+
+// Follow workspace switches made in other windows
+listen("config-changed", (config, { remote }) => {
+  if (remote && config.workspace_path !== useWorkspaceStore.getState().workspacePath) {
+    useWorkspaceStore.getState().loadWorkspace();
+  }
+});
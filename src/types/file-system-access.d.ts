@@ -4,9 +4,16 @@ interface FileSystemHandlePermissionDescriptor {
   mode?: FileSystemPermissionMode;
 }
 
+interface FileSystemWriteChunkParams {
+  type: "write";
+  position?: number;
+  data: BufferSource | Blob | string;
+}
+
 interface FileSystemWritableFileStream {
-  write(data: BufferSource | Blob | string): Promise<void>;
+  write(data: BufferSource | Blob | string | FileSystemWriteChunkParams): Promise<void>;
   close(): Promise<void>;
+  abort(reason?: unknown): Promise<void>;
 }
 
 interface FileSystemHandle {
@@ -15,6 +22,7 @@ interface FileSystemHandle {
   isSameEntry(other: FileSystemHandle): Promise<boolean>;
   queryPermission?(descriptor?: FileSystemHandlePermissionDescriptor): Promise<PermissionState>;
   requestPermission?(descriptor?: FileSystemHandlePermissionDescriptor): Promise<PermissionState>;
+  remove?(options?: { recursive?: boolean }): Promise<void>;
 }
 
 interface FileSystemFileHandle extends FileSystemHandle {
@@ -29,8 +37,37 @@ interface FileSystemDirectoryHandle extends FileSystemHandle {
   getDirectoryHandle(name: string, options?: { create?: boolean }): Promise<FileSystemDirectoryHandle>;
   removeEntry(name: string, options?: { recursive?: boolean }): Promise<void>;
   entries(): AsyncIterableIterator<[string, FileSystemFileHandle | FileSystemDirectoryHandle]>;
+  resolve(possibleDescendant: FileSystemHandle): Promise<string[] | null>;
+}
+
+interface FilePickerAcceptType {
+  description?: string;
+  accept: Record<string, string[]>;
+}
+
+interface SaveFilePickerOptions {
+  suggestedName?: string;
+  types?: FilePickerAcceptType[];
+  excludeAcceptAllOption?: boolean;
+}
+
+interface OpenFilePickerOptions {
+  multiple?: boolean;
+  types?: FilePickerAcceptType[];
+}
+
+interface LaunchParams {
+  readonly targetURL?: string;
+  readonly files: ReadonlyArray<FileSystemFileHandle | FileSystemDirectoryHandle>;
+}
+
+interface LaunchQueue {
+  setConsumer(consumer: (params: LaunchParams) => void): void;
 }
 
 interface Window {
+  readonly launchQueue?: LaunchQueue;
   showDirectoryPicker(options?: { mode?: FileSystemPermissionMode }): Promise<FileSystemDirectoryHandle>;
+  showSaveFilePicker(options?: SaveFilePickerOptions): Promise<FileSystemFileHandle>;
+  showOpenFilePicker(options?: OpenFilePickerOptions): Promise<FileSystemFileHandle[]>;
 }
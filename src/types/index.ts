@@ -11,6 +11,9 @@ export interface FileNode {
   /** Absolute path to the file or directory */
   path: string;
   
+  /** Path from the workspace root with forward slashes ("" for the root itself) */
+  relative_path: string;
+  
   /** Display name (file/folder name without path) */
   name: string;
   
@@ -20,16 +23,73 @@ export interface FileNode {
   /** File size in bytes (null for directories) */
   size: number | null;
   
-  /** Last modified timestamp (ISO string from Rust SystemTime) */
-  modified: string | null;
+  /** Last modified time in ms since the epoch (null for directories) */
+  modified: number | null;
+  
+  /**
+   * Creation time in ms since the epoch (null when unknown)
+   * The File System Access API doesn't expose a file's birth time, so
+   * listings leave it null rather than guess.
+   */
+  created: number | null;
+  
+  /** Lowercase extension without the dot ("" for directories and files without one) */
+  extension: string;
   
   /** Child nodes for directories (null if not loaded/lazy loaded) */
   children: FileNode[] | null;
   
+  /** Whether a directory has visible entries, known before its children load (false for files) */
+  has_children: boolean;
+  
+  /** What the entry holds, for choosing its icon and how it opens */
+  kind: FileKind;
+  
   /** Whether this node has a pending operation (optimistic update indicator) */
   isPending?: boolean;
 }
 
+/**
+ * What a file tree entry holds
+ * Files are classified by extension; files without one by their first bytes.
+ */
+export type FileKind = 'directory' | 'markdown' | 'image' | 'pdf' | 'code' | 'other';
+
+/**
+ * How the file tree orders siblings
+ * - name: alphabetically
+ * - modified: most recently modified first
+ * - size: largest first
+ */
+export type TreeSortOrder = 'name' | 'modified' | 'size';
+
+/**
+ * File tree display preferences, remembered across restarts
+ */
+export interface ViewPreferences {
+  /** List dotfiles and dot-folders */
+  show_hidden_files: boolean;
+
+  sort_order: TreeSortOrder;
+
+  /** Folders before files, whatever the sort order */
+  folders_first: boolean;
+}
+
+/**
+ * How workspace reads and writes handle slow or flaky storage, such as network mounts
+ */
+export interface IoPolicy {
+  /** Times a transiently failed operation is tried again (0: never) */
+  retries: number;
+
+  /** Wait before the first retry; each further retry waits twice as long */
+  retry_delay_ms: number;
+
+  /** Time an operation may take before failing with a "timeout" error (0: no limit) */
+  timeout_ms: number;
+}
+
 /**
  * Pagination result for large directories
  * Matches Rust DirectoryPage struct
@@ -45,6 +105,39 @@ export interface DirectoryPage {
   has_more: boolean;
 }
 
+/**
+ * A page of a directory listing fetched by cursor
+ */
+export interface DirectoryCursorPage extends DirectoryPage {
+  /** Cursor for the next page of the same listing (null on the last page) */
+  next_cursor: string | null;
+}
+
+/**
+ * A batch of entries from a streaming directory scan
+ */
+export interface DirectoryScanBatch {
+  operation_id: string;
+
+  /** Path of the directory being scanned */
+  path: string;
+
+  /** Entries in this batch, sorted within the batch only */
+  nodes: FileNode[];
+}
+
+/**
+ * Sent once a streaming directory scan has listed every entry
+ */
+export interface DirectoryScanComplete {
+  operation_id: string;
+
+  path: string;
+
+  /** Entries across all batches */
+  total_count: number;
+}
+
 /**
  * Helper type for path operations
  */
@@ -66,3 +159,1212 @@ export type FsEventPayload =
   | { type: 'Modified'; data: { path: string } }
   | { type: 'Deleted'; data: { path: string } };
 
+
+/**
+ * Per-workspace editor session restored on startup
+ */
+export interface WorkspaceSession {
+  /** Workspace-relative path of the open file */
+  activePath: string | null;
+
+  /** Workspace-relative paths of expanded folders */
+  expandedFolders: string[];
+}
+
+/**
+ * Application configuration snapshot broadcast on change
+ */
+export interface AppConfig {
+  /** Name of the open workspace (null if none) */
+  workspace_path: string | null;
+}
+
+/**
+ * Where the app is in updating to a newer build
+ * - idle: no update found
+ * - checking: asking the server for a newer build
+ * - downloading: the service worker is fetching a newer build
+ * - ready: a newer build is downloaded and takes over on restart
+ */
+export type UpdateStatus = 'idle' | 'checking' | 'downloading' | 'ready';
+
+/**
+ * A window opened a workspace: picked, restored on start or granted access again
+ */
+export interface WorkspaceOpenedEvent {
+  workspace_path: string;
+
+  /** Workspace the window had open before, when this is a switch */
+  previous_path: string | null;
+}
+
+/**
+ * A window left a workspace, by closing it or switching to another
+ */
+export interface WorkspaceClosedEvent {
+  workspace_path: string;
+}
+
+/**
+ * Kind of change made to a workspace entry
+ */
+export type FileChangeKind = 'created' | 'modified' | 'renamed' | 'deleted';
+
+/**
+ * Whether a changed workspace entry is a file or a folder
+ */
+export type FileEntryKind = 'file' | 'directory';
+
+/**
+ * A file or folder change made by one window, broadcast to the others
+ */
+export interface FileChangeEvent {
+  /** Name of the workspace the change was made in */
+  workspace_path: string;
+
+  kind: FileChangeKind;
+
+  entry_kind: FileEntryKind;
+
+  /** Workspace-relative path of the entry (the old path for renames) */
+  path: string;
+
+  /** Workspace-relative destination path (renames only) */
+  new_path: string | null;
+}
+
+/**
+ * A CRDT update to an open document, broadcast to the other windows' replicas
+ */
+export interface DocumentUpdateEvent {
+  /** Name of the workspace the document belongs to */
+  workspace_path: string;
+
+  /** Workspace-relative path of the note */
+  path: string;
+
+  /** Encoded Yjs update */
+  update: Uint8Array;
+}
+
+/**
+ * Payload types for app events, keyed by event name
+ */
+export interface AppEventMap {
+  "config-changed": AppConfig;
+  "view-preferences-changed": ViewPreferences;
+  "workspace-opened": WorkspaceOpenedEvent;
+  "workspace-closed": WorkspaceClosedEvent;
+  "operation-progress": OperationProgressEvent;
+  "app-lock-changed": AppLockStatus;
+  "file-changed": FileChangeEvent;
+  "document-update": DocumentUpdateEvent;
+  "update-status": UpdateStatus;
+  "directory-scan-batch": DirectoryScanBatch;
+  "directory-scan-complete": DirectoryScanComplete;
+}
+
+/**
+ * File type filter for native file dialogs
+ */
+export interface DialogFilter {
+  /** Label shown in the file type dropdown */
+  name: string;
+
+  /** Allowed extensions without the leading dot */
+  extensions: string[];
+}
+
+/**
+ * Options for the save-as dialog
+ */
+export interface SaveDialogOptions {
+  /** File name pre-filled in the dialog */
+  suggestedName?: string;
+
+  /** Allowed file types (any type if empty) */
+  filters?: DialogFilter[];
+}
+
+/**
+ * Options for the multi-file open dialog
+ */
+export interface OpenFilesDialogOptions {
+  /** Allow selecting more than one file (default true) */
+  multiple?: boolean;
+
+  /** Allowed file types (any type if empty) */
+  filters?: DialogFilter[];
+}
+
+/**
+ * Location chosen in the save-as dialog
+ */
+export interface SaveTarget {
+  /** Writable handle granted by the user */
+  handle: FileSystemFileHandle;
+
+  /** File name chosen by the user */
+  name: string;
+
+  /** Workspace path if the file lies inside the workspace, otherwise null */
+  workspacePath: string | null;
+}
+
+/**
+ * Formats a single note can be exported to
+ */
+export type ExportFormatId = 'html' | 'docx';
+
+/**
+ * Export format as listed in menus
+ */
+export interface ExportFormat {
+  id: ExportFormatId;
+
+  /** Display name, e.g. "Word Document" */
+  name: string;
+
+  /** File extension of the output, without the dot */
+  extension: string;
+}
+
+/**
+ * Built-in stylesheet applied to HTML exports
+ */
+export type HtmlExportTheme = 'light' | 'dark' | 'none';
+
+/**
+ * Options for rendering markdown to HTML
+ */
+export interface RenderMarkdownOptions {
+  /** Drop a leading frontmatter block (default true) */
+  stripFrontmatter?: boolean;
+
+  /** Render [^note] references and their definitions as footnotes (default true) */
+  footnotes?: boolean;
+}
+
+/**
+ * Options for exporting a note to HTML
+ */
+export interface HtmlExportOptions {
+  /** Stylesheet embedded in the document (default 'light') */
+  theme?: HtmlExportTheme;
+
+  /** Embed referenced workspace images as data URLs (default true) */
+  inlineAssets?: boolean;
+}
+
+/**
+ * Options for exporting the workspace as a ZIP archive
+ */
+export interface ZipExportOptions {
+  /** Include dot-files and dot-folders (default false) */
+  includeHidden?: boolean;
+
+  /** Leave out the assets folder (assets/ unless configured otherwise) */
+  excludeAssets?: boolean;
+
+  /** Glob patterns to skip (patterns without "/" match names at any depth) */
+  ignorePatterns?: string[];
+}
+
+/**
+ * Options for publishing notes as a static HTML site
+ */
+export interface SiteExportOptions {
+  /** Notes and folders to publish (default the whole workspace) */
+  paths?: string[];
+
+  /** Leave out notes whose frontmatter has draft: true or publish: false */
+  excludeDrafts?: boolean;
+
+  /** Stylesheet embedded in every page (default 'light') */
+  theme?: HtmlExportTheme;
+}
+
+/**
+ * Summary of a finished static site export
+ */
+export interface SiteExportReport {
+  /** Name of the folder the site was written to */
+  destination: string;
+
+  /** Note pages written */
+  pages: number;
+
+  /** Linked files copied next to the pages */
+  assets: number;
+
+  /** Notes left out as drafts */
+  drafts: number;
+}
+
+/**
+ * Where a selection of notes is exported to: a folder or a ZIP file
+ */
+export type SelectionExportFormat = 'folder' | 'zip';
+
+/**
+ * Options for exporting a selection of notes
+ */
+export interface SelectionExportOptions {
+  /** Also export the notes the selection links to, and the notes those link to (default false) */
+  followLinks?: boolean;
+}
+
+/**
+ * Summary of a finished selection export
+ */
+export interface SelectionExportReport {
+  /** Name of the folder or ZIP file written */
+  destination: string;
+
+  /** Notes exported */
+  notes: number;
+
+  /** Linked images and files exported with them */
+  assets: number;
+}
+
+/**
+ * Progress update for long-running file operations
+ */
+export interface OperationProgress {
+  /** Items handled so far */
+  processed: number;
+
+  /** Total items in the operation */
+  total: number;
+
+  /** Workspace-relative path of the item just handled */
+  current_path: string;
+
+  /** File bytes handled so far */
+  bytes: number;
+}
+
+/**
+ * Progress of a cancellable operation, broadcast as an app event
+ */
+export interface OperationProgressEvent extends OperationProgress {
+  operation_id: string;
+
+  kind: 'copy' | 'delete' | 'publish' | 'import' | 'export';
+}
+
+/**
+ * A source construct an importer could not convert faithfully
+ */
+export interface ImportIssue {
+  /** Workspace-relative path of the imported note */
+  path: string;
+
+  /** 1-based line in the source note (null for note-level issues) */
+  line: number | null;
+
+  /** The original text, left in place unless the message says otherwise */
+  construct: string;
+
+  message: string;
+}
+
+/**
+ * Summary of an import into the workspace
+ */
+export interface ImportReport {
+  /** Workspace-relative folder the notes were imported into */
+  destination: string;
+
+  imported_notes: number;
+
+  imported_attachments: number;
+
+  issues: ImportIssue[];
+}
+
+/**
+ * What a folder import does with a file that already exists in the workspace
+ * - skip: keep the workspace file and report the skip
+ * - overwrite: replace the workspace file
+ * - rename: import under a suffixed name ("name-1.md")
+ */
+export type ImportConflictPolicy = 'skip' | 'overwrite' | 'rename';
+
+/**
+ * Which files a folder import copies
+ */
+export type ImportFilter = 'all' | 'notes' | 'notes-and-images';
+
+/**
+ * Options for importing a folder from outside the workspace
+ */
+export interface FolderImportOptions {
+  /** Defaults to rename */
+  conflict?: ImportConflictPolicy;
+
+  /** Defaults to all */
+  filter?: ImportFilter;
+
+  /** Copy dot-files and dot-folders too */
+  includeHidden?: boolean;
+}
+
+/**
+ * Why a link was reported as broken
+ */
+export type BrokenLinkReason = 'missing-file' | 'missing-anchor';
+
+/**
+ * A link that points to a missing note, file or heading
+ */
+export interface BrokenLink {
+  /** Workspace-relative path of the note containing the link */
+  path: string;
+
+  /** 1-based line of the link */
+  line: number;
+
+  /** Link destination as written */
+  target: string;
+
+  reason: BrokenLinkReason;
+
+  /** Destination that was probably meant, in the same link style */
+  suggestion: string | null;
+}
+
+/**
+ * Result of checking the links in a folder or note
+ */
+export interface LinkCheckReport {
+  checked_notes: number;
+
+  checked_links: number;
+
+  broken: BrokenLink[];
+}
+
+/**
+ * How copyPathToClipboard writes a path
+ * 'absolute' is the path as the app shows it, starting at the workspace
+ * folder, since browsers don't reveal where the folder is on disk
+ */
+export type PathCopyKind = 'absolute' | 'relative' | 'markdown-link';
+
+/**
+ * Which way convertLinks rewrites links between notes
+ */
+export type LinkConversion = 'to-markdown' | 'to-wikilink';
+
+/**
+ * Links rewritten in one note by a link conversion
+ */
+export interface LinkConversionChange {
+  path: string;
+
+  /** Number of links rewritten */
+  converted: number;
+
+  /** Changes to the note, as written (or as they would be, for a dry run) */
+  hunks: DiffHunk[];
+}
+
+export type FrontmatterScalar = string | number | boolean | null;
+
+/**
+ * A parsed frontmatter field: a scalar or a list of scalars
+ */
+export type FrontmatterValue = FrontmatterScalar | FrontmatterScalar[];
+
+/**
+ * Indexed metadata of a note, as returned by note queries
+ */
+export interface NoteMetadata {
+  /** Workspace-relative path */
+  path: string;
+
+  /** Frontmatter title, else the file name without extension */
+  title: string;
+
+  aliases: string[];
+
+  /** Frontmatter and inline tags, without the # */
+  tags: string[];
+
+  frontmatter: Record<string, FrontmatterValue>;
+
+  /** Last modified time in ms since the epoch */
+  modified: number;
+
+  /** Size in bytes */
+  size: number;
+}
+
+/**
+ * Sorting and paging for note queries
+ */
+export interface NoteQueryOptions {
+  /** Field to sort by, as written in queries (default "path") */
+  sortBy?: string;
+
+  descending?: boolean;
+
+  /** Maximum number of notes to return */
+  limit?: number;
+}
+
+/**
+ * Replace the text between two offsets of a file
+ * Offsets are string indices (UTF-16 code units) into the current content
+ */
+export interface TextEdit {
+  start: number;
+
+  /** Exclusive; equal to start for a pure insertion */
+  end: number;
+
+  replacement: string;
+}
+
+/**
+ * A checkbox task found in a note
+ */
+export interface Task {
+  /** Workspace-relative path of the note */
+  path: string;
+
+  /** 1-based line number */
+  line: number;
+
+  /** Task text after the checkbox, as written */
+  text: string;
+
+  completed: boolean;
+
+  /** Due date (YYYY-MM-DD), if annotated */
+  due: string | null;
+}
+
+/**
+ * Filters for listing tasks; dates are YYYY-MM-DD and inclusive
+ */
+export interface TaskFilters {
+  completed?: boolean;
+
+  /** Only tasks with (true) or without (false) a due date */
+  hasDue?: boolean;
+
+  dueBefore?: string;
+
+  dueAfter?: string;
+}
+
+/**
+ * A note suggested while typing a [[wikilink]]
+ */
+export interface LinkCompletion {
+  /** Workspace-relative path of the note */
+  path: string;
+
+  /** Display title */
+  title: string;
+
+  /** Text to insert between [[ and ]] (includes the folder when the title is ambiguous) */
+  link_text: string;
+}
+
+/**
+ * How a name was matched to a note: workspace path, file name,
+ * frontmatter title or frontmatter alias
+ */
+export type NoteMatchKind = 'path' | 'name' | 'title' | 'alias';
+
+/**
+ * The note a human-readable name refers to
+ */
+export interface ResolvedNote {
+  /** Workspace-relative path of the note */
+  path: string;
+
+  /** Display title */
+  title: string;
+
+  matched_by: NoteMatchKind;
+}
+
+/**
+ * A tag suggested while typing a #tag
+ */
+export interface TagCompletion {
+  /** Tag without the leading # */
+  tag: string;
+
+  /** Number of notes using the tag */
+  count: number;
+}
+
+/**
+ * Options for building the link graph
+ */
+export interface GraphOptions {
+  /** Only include notes inside this workspace folder */
+  folder?: string;
+
+  /** Only include notes with this tag (or one nested under it) */
+  tag?: string;
+
+  /** Add a node per tag, linked to the notes that use it */
+  includeTags?: boolean;
+
+  /** Keep notes without any edges (default true) */
+  includeOrphans?: boolean;
+}
+
+export type GraphNodeKind = 'note' | 'tag';
+
+export interface GraphNode {
+  /** Note path, or "tag:<name>" for tags */
+  id: string;
+
+  kind: GraphNodeKind;
+
+  /** Note title or tag name */
+  label: string;
+
+  /** Number of edges touching the node */
+  degree: number;
+}
+
+export interface GraphEdge {
+  /** Id of the linking note */
+  source: string;
+
+  /** Id of the linked note or tag */
+  target: string;
+
+  kind: 'link' | 'tag';
+
+  /** Number of links from source to target */
+  weight: number;
+}
+
+/**
+ * Notes (and optionally tags) with the links between them
+ */
+export interface NoteGraph {
+  nodes: GraphNode[];
+  edges: GraphEdge[];
+}
+
+/**
+ * A heading in a note's outline
+ */
+export interface OutlineHeading {
+  /** 1-6 */
+  level: number;
+
+  /** Heading text with inline markdown removed */
+  text: string;
+
+  /** Anchor slug, unique within the note */
+  slug: string;
+
+  /** 1-based line number in the note */
+  line: number;
+}
+
+/**
+ * Kind of reference: [^footnote] or [reference-style][link]
+ */
+export type ReferenceKind = 'footnote' | 'link';
+
+/**
+ * Where a note uses a footnote or link reference
+ */
+export interface ReferenceUsage {
+  /** 1-based line number in the note */
+  line: number;
+
+  /** 1-based column of the opening bracket */
+  column: number;
+}
+
+/**
+ * A footnote or link reference definition and the places that use it
+ * Unused definitions have no usages; missing ones have no definition
+ */
+export interface NoteReference {
+  kind: ReferenceKind;
+
+  /** Label as written in the definition, or at first use when missing (without the ^ of footnotes) */
+  label: string;
+
+  /** 1-based line of the definition (null when it is missing) */
+  line: number | null;
+
+  /** Footnote text or link destination (null when missing) */
+  definition: string | null;
+
+  usages: ReferenceUsage[];
+}
+
+/**
+ * A fenced code block in a note
+ */
+export interface CodeBlock {
+  /** First word of the info string (null when there is none) */
+  language: string | null;
+
+  /** Full info string after the opening fence */
+  info: string;
+
+  /** Code between the fences, without the fence indentation */
+  content: string;
+
+  /** 1-based line of the opening fence */
+  start_line: number;
+
+  /** 1-based line of the closing fence (the last line when the block is never closed) */
+  end_line: number;
+}
+
+/**
+ * Column alignment set by a table's delimiter row (null when unspecified)
+ */
+export type TableAlignment = 'left' | 'center' | 'right' | null;
+
+/**
+ * Options for normalizing a note's markdown
+ */
+export interface FormatOptions {
+  /** Marker for bullet list items (default '-') */
+  listMarker?: '-' | '*' | '+';
+
+  /** Pad table columns to line up (default true) */
+  alignTables?: boolean;
+
+  /** Renumber ordered lists from their first number (default true) */
+  renumberLists?: boolean;
+
+  /** Rewrap paragraphs to this many characters (default null: leave line breaks alone) */
+  lineWidth?: number | null;
+}
+
+/**
+ * Workspace formatting style, applied on manual save when enabled
+ */
+export interface FormatSettings extends FormatOptions {
+  onSave: boolean;
+}
+
+/**
+ * Web clipper links, which let a browser extension or bookmarklet save pages
+ * into the workspace; off until a token is created
+ */
+export interface ClipperSettings {
+  enabled: boolean;
+
+  /** Workspace folder clippings are saved in */
+  folder: string;
+
+  /** Secret every clip link has to carry (null: none created yet) */
+  token: string | null;
+}
+
+/**
+ * A page sent by the web clipper
+ */
+export interface WebClipping {
+  title: string;
+
+  /** Address of the clipped page */
+  url: string;
+
+  /** Clipped content as markdown */
+  markdown: string;
+}
+
+/**
+ * Kind of file in a storage breakdown
+ */
+export type StorageCategory = 'notes' | 'images' | 'other';
+
+/**
+ * Disk usage of a folder and everything below it
+ */
+export interface StorageNode {
+  /** Folder name (the workspace name for the root) */
+  name: string;
+
+  /** Workspace-relative path ("." for the root) */
+  path: string;
+
+  /** Total bytes of every file below the folder */
+  size: number;
+
+  file_count: number;
+
+  /** Total bytes per kind of file */
+  by_category: Record<StorageCategory, number>;
+
+  /** Subfolders, largest first */
+  children: StorageNode[];
+}
+
+/**
+ * Why notes were grouped as duplicates
+ * 'identical' notes have the same content; 'similar-title' notes have titles
+ * that only differ in case, punctuation or a copy suffix such as "(1)"
+ */
+export type DuplicateReason = 'identical' | 'similar-title';
+
+export interface DuplicateGroup {
+  reason: DuplicateReason;
+
+  /** Workspace-relative paths of the notes, sorted */
+  paths: string[];
+
+  /** Title of the first note, for display */
+  title: string;
+}
+
+/**
+ * What unsaved content is compared against
+ * 'head' (the last git commit) is not available in the web build
+ */
+export type DiffBase = 'file' | 'head';
+
+export type DiffLineKind = 'context' | 'added' | 'removed';
+
+export interface DiffLine {
+  kind: DiffLineKind;
+
+  text: string;
+
+  /** 1-based line in the base (null for added lines) */
+  old_line: number | null;
+
+  /** 1-based line in the new content (null for removed lines) */
+  new_line: number | null;
+
+  /**
+   * Word-level changes of a modified line, for word diffs only
+   * A removed line holds its context and removed words, its added
+   * counterpart the context and added words
+   */
+  words?: DiffSegment[];
+}
+
+/**
+ * A run of words inside a modified line
+ */
+export interface DiffSegment {
+  kind: DiffLineKind;
+
+  /** Text including its whitespace, so segments concatenate back to the line */
+  text: string;
+}
+
+/**
+ * A run of changes with surrounding context, like a unified diff hunk
+ */
+export interface DiffHunk {
+  old_start: number;
+
+  old_lines: number;
+
+  new_start: number;
+
+  new_lines: number;
+
+  lines: DiffLine[];
+}
+
+/**
+ * An image that could not be downloaded into the workspace
+ */
+export interface RemoteImageFailure {
+  url: string;
+
+  message: string;
+}
+
+/**
+ * Result of downloading a note's remote images
+ */
+export interface LocalizeImagesReport {
+  /** Number of image links rewritten to local assets */
+  localized: number;
+
+  failed: RemoteImageFailure[];
+}
+
+/**
+ * What a web page says about itself, for turning a pasted URL into a link
+ */
+export interface UrlMetadata {
+  /** Final URL after redirects */
+  url: string;
+
+  /** og:title, or the page's <title> */
+  title: string | null;
+
+  description: string | null;
+
+  /** Absolute URL of the og:image */
+  image: string | null;
+}
+
+export type ImageOutputFormat = 'original' | 'webp' | 'jpeg';
+
+/**
+ * Optional processing applied to images before they are stored
+ */
+export interface ImageProcessingOptions {
+  /** Longest side in pixels; larger images are downscaled (null to keep size) */
+  maxDimension: number | null;
+
+  /** Format PNG images are re-encoded to */
+  pngFormat: ImageOutputFormat;
+
+  /** Encoder quality between 0 and 1 for WebP/JPEG output */
+  quality: number;
+}
+
+/**
+ * Options for storing an image in the workspace
+ */
+export interface UploadImageOptions {
+  /** Downscaling/re-encoding applied before storing */
+  processing?: ImageProcessingOptions | null;
+
+  /** Note the image is inserted into, for the {note} pattern variable */
+  notePath?: string | null;
+
+  /** Remove EXIF/XMP metadata such as GPS position and camera details (default true) */
+  stripMetadata?: boolean;
+
+  /** Format HEIC/HEIF photos are converted to (default 'jpeg') */
+  heicFormat?: Exclude<ImageOutputFormat, 'original'>;
+}
+
+/**
+ * Result of storing an image in the workspace
+ */
+export interface UploadedImage {
+  /** Workspace-relative asset path */
+  path: string;
+
+  /** Size of the image as provided, in bytes */
+  original_size: number;
+
+  /** Size of the stored file, in bytes */
+  stored_size: number;
+
+  /** An identical image was already stored; path points at that asset */
+  reused: boolean;
+}
+
+/**
+ * An image whose recognized text matches a search
+ */
+export interface ImageTextMatch {
+  /** Workspace-relative asset path */
+  path: string;
+
+  /** Recognized text around the first match */
+  snippet: string;
+}
+
+/**
+ * How uploaded images are arranged under the assets root
+ * - flat: {name}
+ * - per-note: {note}/{name}
+ * - date: {date}/{name} (YYYY-MM)
+ * - custom: the pattern in AssetSettings
+ */
+export type AssetLayout = 'flat' | 'per-note' | 'date' | 'custom';
+
+export interface AssetSettings {
+  /** Workspace folder images are stored in */
+  root: string;
+
+  layout: AssetLayout;
+
+  /**
+   * Path below the root for the custom layout, without extension
+   * Variables: {note} (note path without extension), {date} (YYYY-MM),
+   * {hash} (content hash) and {name} (original file name)
+   */
+  pattern: string;
+
+  /**
+   * Template for stored images' file names, replacing the pattern's last
+   * segment (null keeps the pattern's name), e.g. "{note}-{date}-{counter}.{ext}"
+   * Variables: {name}, {note} (note file name), {date} (YYYY-MM-DD), {time}
+   * (HHMMSS), {counter} (first free number from 1), {hash} and {ext}
+   */
+  filename: string | null;
+
+  /**
+   * Downscaling and re-encoding applied to pasted and dropped images (null
+   * stores them exactly as given)
+   */
+  processing: ImageProcessingOptions | null;
+
+  /**
+   * Recognize the text in uploaded images so screenshots can be found by what
+   * they show (off by default; the recognizer is loaded on first use)
+   */
+  ocr: boolean;
+}
+
+/**
+ * A bibliography entry, offered when completing a [@key] citation
+ */
+export interface Citation {
+  key: string;
+
+  /** BibTeX entry type, lowercased (article, book, ...) */
+  entry_type: string;
+
+  title: string | null;
+
+  /** Author (or editor) names as written, in order */
+  authors: string[];
+
+  year: string | null;
+}
+
+/**
+ * Per-workspace settings, stored in .mdx/settings.json
+ */
+export interface WorkspaceSettings {
+  assets: AssetSettings;
+
+  /** BibTeX file citations resolve against (null: the first .bib file found) */
+  bibliography: string | null;
+
+  format: FormatSettings;
+
+  clipper: ClipperSettings;
+}
+
+export type AuditOperation =
+  | 'write'
+  | 'create-file'
+  | 'create-folder'
+  | 'rename'
+  | 'delete'
+  | 'upload-image'
+  | 'import'
+  | 'settings';
+
+/**
+ * One mutating operation recorded in the audit log
+ */
+export interface AuditEntry {
+  /** ISO 8601 time the operation completed */
+  timestamp: string;
+
+  /** Window that performed the operation (random per window) */
+  window_id: string;
+
+  workspace: string | null;
+
+  operation: AuditOperation;
+
+  path: string;
+
+  /** Destination of a rename, otherwise null */
+  new_path: string | null;
+}
+
+/**
+ * Writing activity on one day
+ */
+export interface WritingDay {
+  /** Local date, YYYY-MM-DD */
+  date: string;
+
+  notes_created: number;
+
+  /** Distinct notes saved that day */
+  notes_edited: number;
+
+  /** Words added by saves, net of each save's deletions */
+  words_written: number;
+}
+
+/**
+ * Writing activity over a range of days
+ */
+export interface WritingStats {
+  /** Every day in the range, oldest first; days without activity are zeros */
+  days: WritingDay[];
+
+  /** Consecutive days with activity up to today (or yesterday) */
+  current_streak: number;
+
+  longest_streak: number;
+}
+
+/**
+ * A console message logged shortly before a crash
+ */
+export interface CrashBreadcrumb {
+  timestamp: string;
+
+  level: 'log' | 'info' | 'warn' | 'error';
+
+  message: string;
+}
+
+/**
+ * An uncaught error captured while crash reporting was on
+ */
+export interface CrashReport {
+  id: string;
+
+  /** ISO 8601 time of the crash */
+  timestamp: string;
+
+  app_version: string;
+
+  /** Browser and operating system, as the browser reports them */
+  user_agent: string;
+
+  /** 'error' for uncaught exceptions, 'rejection' for unhandled promise rejections */
+  kind: 'error' | 'rejection';
+
+  message: string;
+
+  stack: string | null;
+
+  /** Console messages before the crash, oldest first */
+  breadcrumbs: CrashBreadcrumb[];
+
+  /** The report was sent to the crash report server */
+  submitted: boolean;
+}
+
+export type FilenameProblemCode =
+  | 'empty'
+  | 'invalid-character'
+  | 'control-character'
+  | 'reserved-name'
+  | 'trailing-dot-or-space'
+  | 'too-long';
+
+/**
+ * A reason a file name is not portable across Windows, macOS and Linux
+ */
+export interface FilenameProblem {
+  code: FilenameProblemCode;
+
+  message: string;
+}
+
+/**
+ * Stable, machine-readable error codes carried by AppError
+ */
+export type AppErrorCode =
+  | 'not-found'
+  | 'already-exists'
+  | 'permission-denied'
+  | 'invalid-path'
+  | 'invalid-name'
+  | 'invalid-argument'
+  | 'no-workspace'
+  | 'unsupported'
+  | 'too-large'
+  | 'quota-exceeded'
+  | 'cancelled'
+  | 'locked'
+  | 'conflict'
+  | 'timeout'
+  | 'io'
+  | 'unknown';
+
+/**
+ * Contents of .mdx/lock, written by the app instance editing a workspace
+ */
+export interface WorkspaceLockInfo {
+  /** Browser profile holding the lock (shared by its windows) */
+  instance_id: string;
+
+  user_agent: string;
+
+  /** ISO 8601 time the lock was taken */
+  acquired_at: string;
+
+  /** ISO 8601 time of the last heartbeat; old heartbeats mean a stale lock */
+  heartbeat_at: string;
+}
+
+export interface WorkspaceLockStatus {
+  acquired: boolean;
+
+  /** Current holder when the lock is held by another instance */
+  holder: WorkspaceLockInfo | null;
+}
+
+/**
+ * Where a custom dictionary word is stored: the workspace's own list
+ * (.mdx/dictionary.txt, travels with the folder) or the app-level one
+ */
+export type DictionaryScope = "workspace" | "app";
+
+/**
+ * A region both sides of a three-way merge changed differently
+ */
+export interface MergeConflict {
+  /** 1-based line of the "<<<<<<<" marker in the merged content */
+  line: number;
+
+  base: string[];
+
+  ours: string[];
+
+  theirs: string[];
+}
+
+export interface MergeResult {
+  /** Merged text; conflicts are written with git-style markers */
+  content: string;
+
+  conflicts: MergeConflict[];
+}
+
+/**
+ * Sync tool that left a conflicted copy behind
+ */
+export type ConflictedCopySource = 'dropbox' | 'syncthing';
+
+export interface ConflictedCopy {
+  /** Workspace-relative path of the conflicted copy */
+  path: string;
+
+  /** The file it is a copy of (null if that no longer exists) */
+  original_path: string | null;
+
+  source: ConflictedCopySource;
+}
+
+/**
+ * Password lock state; while locked every workspace operation fails with 'locked'
+ */
+export interface AppLockStatus {
+  /** A password is set */
+  enabled: boolean;
+
+  locked: boolean;
+
+  /** Minutes without input before the app locks itself (null to never) */
+  auto_lock_minutes: number | null;
+
+  /** Touch ID, Windows Hello or another platform authenticator can unlock the app */
+  biometric: boolean;
+}
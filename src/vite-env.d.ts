@@ -1 +1,10 @@
 /// <reference types="vite/client" />
+/// <reference types="vite-plugin-pwa/client" />
+
+interface ImportMetaEnv {
+  /** Server crash reports are submitted to; builds without one keep reports local */
+  readonly VITE_CRASH_REPORT_URL?: string;
+}
+
+/** package.json version, set at build time */
+declare const __APP_VERSION__: string;
@@ -2,14 +2,25 @@ import { defineConfig } from "vite";
 import react from "@vitejs/plugin-react";
 import tailwindcss from "@tailwindcss/vite";
 import { VitePWA } from "vite-plugin-pwa";
+import { viteStaticCopy } from "vite-plugin-static-copy";
 import path from "path";
 
 export default defineConfig({
   plugins: [
     react(),
     tailwindcss(),
+    // OCR runs on files served by the app (see src/lib/image-text.ts), not a CDN
+    viteStaticCopy({
+      targets: [
+        { src: "node_modules/tesseract.js/dist/worker.min.js", dest: "tesseract" },
+        { src: "node_modules/tesseract.js-core/tesseract-core*", dest: "tesseract/core" },
+        { src: "node_modules/@tesseract.js-data/eng/4.0.0_best_int/eng.traineddata.gz", dest: "tesseract/lang" },
+      ],
+    }),
     VitePWA({
-      registerType: "autoUpdate",
+      // New builds wait for the user to restart into them (see src/lib/updater.ts)
+      registerType: "prompt",
+      injectRegister: false,
       includeAssets: ["favicon.png", "pwa-192.png", "pwa-512.png"],
       manifest: {
         name: "MDX Web",
@@ -18,6 +29,26 @@ export default defineConfig({
         start_url: "/",
         scope: "/",
         display: "standalone",
+        // A second launch focuses the running window and hands it the launch
+        launch_handler: {
+          client_mode: ["focus-existing", "auto"],
+        },
+        // Links like web+mdx://open?path=notes/todo.md from other apps
+        protocol_handlers: [
+          {
+            protocol: "web+mdx",
+            url: "/?open=%s",
+          },
+        ],
+        // "Open with" for markdown files once the app is installed
+        file_handlers: [
+          {
+            action: "/",
+            accept: {
+              "text/markdown": [".md", ".mdx"],
+            },
+          },
+        ],
         background_color: "#0f172a",
         theme_color: "#0f172a",
         icons: [
@@ -38,6 +69,8 @@ export default defineConfig({
       workbox: {
         navigateFallback: "/index.html",
         globPatterns: ["**/*.{html,js,css,png,svg,ico,woff2}"],
+        // Loaded only when a workspace turns OCR on, and far too large to precache
+        globIgnores: ["tesseract/**"],
         runtimeCaching: [],
         cleanupOutdatedCaches: true,
       },
@@ -46,6 +79,9 @@ export default defineConfig({
       },
     }),
   ],
+  define: {
+    __APP_VERSION__: JSON.stringify(process.env.npm_package_version ?? "0.0.0"),
+  },
   resolve: {
     alias: {
       "~": path.resolve(__dirname, "./src"),